@@ -79,8 +79,8 @@ const App: React.FC = () => {
 
   // バリデーション状態トグル処理
   const handleValidationToggle = useCallback(
-    (newState: boolean) => {
-      const updatedMarkdown = toggleValidation(newState);
+    async (newState: boolean) => {
+      const updatedMarkdown = await toggleValidation(newState);
       if (updatedMarkdown) {
         setMarkdownContent(updatedMarkdown);
         updateContent('markdown', updatedMarkdown);
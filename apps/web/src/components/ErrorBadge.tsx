@@ -1,5 +1,11 @@
 import React, { useEffect, useState, useRef } from 'react';
-import { ValidationError, ErrorCode } from '../hooks/validation-error.type';
+import {
+  ValidationError,
+  ErrorCode,
+  isFrontmatterError,
+  isSchemaValidationCode,
+  isSchemaValidationError,
+} from '../hooks/validation-error.type';
 
 // エラータイプのユニオン型を定義
 type ErrorType =
@@ -56,9 +62,7 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
   useEffect(() => {
     // フィルタ済みのエラーを計算
     // validated=falseの場合、スキーマ検証エラーを除外する
-    const filteredErrors = validated
-      ? errors
-      : errors.filter(err => err.code !== ErrorCode.SchemaValidation);
+    const filteredErrors = validated ? errors : errors.filter(err => !isSchemaValidationError(err));
 
     // 新しいエラーがある場合は表示
     if (filteredErrors.length > 0) {
@@ -73,17 +77,25 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
             case ErrorCode.FrontmatterValidation:
               type = 'frontmatter_error';
               break;
-            case ErrorCode.SchemaValidation:
-              if (err.message.includes('required')) type = 'required_field';
-              else if (err.message.includes('type')) type = 'type_mismatch';
-              else if (err.message.includes('pattern')) type = 'pattern_mismatch';
-              else type = 'schema_error';
+            case ErrorCode.RequiredMissing:
+              type = 'required_field';
+              break;
+            case ErrorCode.TypeMismatch:
+              type = 'type_mismatch';
+              break;
+            case ErrorCode.PatternMismatch:
+              type = 'pattern_mismatch';
               break;
             case ErrorCode.SchemaCompile:
               type = 'schema_error';
               break;
             default:
-              type = 'other';
+              // フロントマターのフィールドの検証エラーはキーワードごとのコードでもフロントマターとして数える
+              if (isFrontmatterError(err)) {
+                type = 'frontmatter_error';
+              } else {
+                type = isSchemaValidationCode(err.code) ? 'schema_error' : 'other';
+              }
           }
           acc[type] = (acc[type] || 0) + 1;
           return acc;
@@ -128,9 +140,7 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
   }, [errors, log, validated]);
 
   // フィルタ済みのエラーを計算（表示判定時にも必要）
-  const filteredErrors = validated
-    ? errors
-    : errors.filter(err => err.code !== ErrorCode.SchemaValidation);
+  const filteredErrors = validated ? errors : errors.filter(err => !isSchemaValidationError(err));
 
   // 表示されない場合は早期リターン
   if (!visible || filteredErrors.length === 0) {
@@ -157,15 +167,11 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
       return 'bg-purple-100 border-purple-400 text-purple-700';
     }
     // フロントマターエラー（赤）
-    else if (
-      type === 'frontmatter' ||
-      error.code === ErrorCode.FrontmatterParse ||
-      error.code === ErrorCode.FrontmatterValidation
-    ) {
+    else if (type === 'frontmatter' || isFrontmatterError(error)) {
       return 'bg-red-100 border-red-400 text-red-700';
     }
     // スキーマ検証エラー（黄）
-    else if (type === 'schemaValidation' || error.code === ErrorCode.SchemaValidation) {
+    else if (type === 'schemaValidation' || isSchemaValidationError(error)) {
       return 'bg-yellow-100 border-yellow-400 text-yellow-700';
     }
     return 'bg-red-100 border-red-400 text-red-700'; // デフォルト（赤）
@@ -185,14 +191,9 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
     // スキーマ構文エラー判定
     const hasSchemaStructureError = errors.some(error => error.code === ErrorCode.SchemaCompile);
 
-    const hasFrontmatterError = errors.some(
-      error =>
-        error.code === ErrorCode.FrontmatterParse || error.code === ErrorCode.FrontmatterValidation
-    );
+    const hasFrontmatterError = errors.some(isFrontmatterError);
 
-    const hasSchemaValidationError = errors.some(
-      error => error.code === ErrorCode.SchemaValidation
-    );
+    const hasSchemaValidationError = errors.some(isSchemaValidationError);
 
     if (hasSchemaStructureError) {
       return 'bg-purple-100 border border-purple-400 text-purple-700'; // スキーマ構文エラー（紫）
@@ -232,14 +233,9 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
     // スキーマ構文エラー判定
     const hasSchemaStructureError = errors.some(error => error.code === ErrorCode.SchemaCompile);
 
-    const hasFrontmatterError = errors.some(
-      error =>
-        error.code === ErrorCode.FrontmatterParse || error.code === ErrorCode.FrontmatterValidation
-    );
+    const hasFrontmatterError = errors.some(isFrontmatterError);
 
-    const hasSchemaValidationError = errors.some(
-      error => error.code === ErrorCode.SchemaValidation
-    );
+    const hasSchemaValidationError = errors.some(isSchemaValidationError);
 
     if (hasSchemaStructureError) {
       // スキーマ構文エラー（紫）
@@ -339,7 +335,7 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
    */
   const renderError = (error: ValidationError, index: number) => {
     // validated=falseの場合はスキーマ検証エラーを表示しない
-    if (!validated && error.code === ErrorCode.SchemaValidation) {
+    if (!validated && isSchemaValidationError(error)) {
       return null;
     }
 
@@ -392,21 +388,15 @@ export const ErrorBadge: React.FC<ErrorBadgeProps> = ({
     // スキーマ構文エラー
     const schemaStructureErrors = errors.filter(error => error.code === ErrorCode.SchemaCompile);
 
-    const frontmatterErrors = errors.filter(
-      error =>
-        error.code === ErrorCode.FrontmatterParse || error.code === ErrorCode.FrontmatterValidation
-    );
+    const frontmatterErrors = errors.filter(isFrontmatterError);
 
-    const schemaValidationErrors = errors.filter(
-      error => error.code === ErrorCode.SchemaValidation
-    );
+    const schemaValidationErrors = errors.filter(isSchemaValidationError);
 
     const otherErrors = errors.filter(
       error =>
         error.code !== ErrorCode.SchemaCompile &&
-        error.code !== ErrorCode.FrontmatterParse &&
-        error.code !== ErrorCode.FrontmatterValidation &&
-        error.code !== ErrorCode.SchemaValidation
+        !isFrontmatterError(error) &&
+        !isSchemaValidationError(error)
     );
 
     return (
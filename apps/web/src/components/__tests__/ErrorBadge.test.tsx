@@ -106,6 +106,27 @@ describe('ErrorBadge', () => {
     expect(screen.getByText(/スキーマ構文エラー: 無効なスキーマです/)).toBeInTheDocument();
   });
 
+  it('キーワードごとのコードのフロントマターエラーはフロントマターエラーとして表示する', () => {
+    const frontmatterKeywordError: ValidationError = {
+      line: 3,
+      message: '"Wasm" does not match "^[a-z]+$"',
+      path: '/tags/1',
+      code: ErrorCode.PatternMismatch,
+      frontmatter: true,
+    };
+
+    render(
+      <LoggerProvider>
+        <ErrorBadge errors={[frontmatterKeywordError]} validated={false} />
+      </LoggerProvider>
+    );
+
+    expect(screen.getByText('フロントマターエラー:')).toBeInTheDocument();
+    expect(screen.queryByText('スキーマ検証エラー:')).not.toBeInTheDocument();
+    const badgeContainer = screen.getByText(/バリデーションエラー/).parentElement?.parentElement;
+    expect(badgeContainer?.className).toContain('bg-red-100');
+  });
+
   it('文字列コードでも黄色バッジが表示される', () => {
     const stringCodeError: ValidationError = {
       line: 3,
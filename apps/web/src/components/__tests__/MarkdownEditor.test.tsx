@@ -33,7 +33,9 @@ describe('MarkdownEditor', () => {
       wasmLoaded: true,
       wasmLoading: false,
       error: null,
+      checkFrontmatter: vi.fn().mockResolvedValue({ errors: [], content: null }),
       validateFrontmatter: vi.fn().mockResolvedValue([]),
+      readFrontmatter: vi.fn().mockResolvedValue(null),
     });
   });
 
@@ -82,13 +84,16 @@ validated: true
       wasmLoaded: true,
       wasmLoading: false,
       error: null,
-      validateFrontmatter: vi.fn().mockResolvedValue([
-        {
-          line: 2,
-          message: 'Frontmatter validation error: Invalid schema_path',
-          path: 'schema_path',
-        },
-      ]),
+      checkFrontmatter: vi.fn().mockResolvedValue({
+        errors: [
+          {
+            line: 2,
+            message: 'Frontmatter validation error: Invalid schema_path',
+            path: 'schema_path',
+          },
+        ],
+        content: null,
+      }),
     });
 
     // 初期コンテンツを設定して、エラーを表示させる
@@ -168,7 +173,9 @@ validated: invalid
       wasmLoaded: false,
       wasmLoading: true,
       error: null,
+      checkFrontmatter: vi.fn().mockResolvedValue({ errors: [], content: null }),
       validateFrontmatter: vi.fn().mockResolvedValue([]),
+      readFrontmatter: vi.fn().mockResolvedValue(null),
     });
 
     render(
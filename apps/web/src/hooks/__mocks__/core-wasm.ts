@@ -31,13 +31,27 @@ export function parse_and_validate_frontmatter(md: string): string {
           code: 3, // ErrorCode.FrontmatterValidation
         },
       ],
+      frontmatter: { schema_path: '', validated: true },
+      format: 'yaml',
     });
   }
-  return JSON.stringify({ success: true, errors: [] });
+  return JSON.stringify({ success: true, errors: [], frontmatter: { validated: true }, format: 'yaml' });
+}
+
+export function convert_frontmatter(md: string, _format: string): string {
+  return JSON.stringify({ success: true, markdown: md, errors: [] });
+}
+
+export function set_frontmatter_field(md: string, _key: string, _value_json: string): string {
+  return JSON.stringify({ success: true, markdown: md, errors: [] });
 }
 
 export function apply_patch(_yaml_str: string, _patch_str: string): string {
-  return 'patched yaml';
+  return JSON.stringify({ success: true, yaml: 'patched yaml', errors: [] });
+}
+
+export function yaml_to_md(_yaml_str: string, _frontmatter_str?: string | null): string {
+  return JSON.stringify({ success: true, markdown: '# mock\n', errors: [] });
 }
 
 export function version(): string {
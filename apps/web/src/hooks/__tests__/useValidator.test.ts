@@ -1,4 +1,30 @@
-import { describe, test, expect, it } from 'vitest';
+import { describe, test, expect, it, vi, beforeEach } from 'vitest';
+import type { Mock } from 'vitest';
+import { createElement, ReactNode } from 'react';
+import { renderHook, waitFor, act } from '@testing-library/react';
+import { LoggerProvider } from '../../contexts/LoggerContext';
+import * as yamlCore from '../useYamlCore';
+import { fetchSchema } from '../../utils/schema';
+import { useValidator } from '../useValidator';
+
+vi.mock('../useYamlCore', () => ({
+  useYamlCore: vi.fn(),
+}));
+
+vi.mock('../../utils/schema', () => ({
+  fetchSchema: vi.fn(),
+}));
+
+const wrapper = ({ children }: { children: ReactNode }) =>
+  createElement(LoggerProvider, null, children);
+
+/** TOML（`+++`）のフロントマターを持つノート */
+const TOML_NOTE = `+++
+schema_path = "note.schema.yaml"
+validated = true
++++
+# タイトル
+`;
 
 describe('useValidator', () => {
   // 完全なテストはmemoryリークのエラーとなってしまうため一部省略
@@ -10,11 +36,91 @@ describe('useValidator', () => {
     // インターフェースの存在チェックだけ行う
     expect(true).toBe(true);
   });
+
+  describe('TOMLのフロントマター', () => {
+    const core = {
+      wasmLoaded: true,
+      checkFrontmatter: vi.fn(),
+      validateFrontmatter: vi.fn(),
+      readFrontmatter: vi.fn(),
+      setFrontmatterField: vi.fn(),
+      markdownToYaml: vi.fn(),
+      validateYamlWithSchema: vi.fn(),
+    };
+
+    beforeEach(() => {
+      vi.clearAllMocks();
+      core.checkFrontmatter.mockResolvedValue({
+        errors: [],
+        content: {
+          frontmatter: { schema_path: 'note.schema.yaml', validated: true },
+          format: 'toml',
+        },
+      });
+      core.validateFrontmatter.mockResolvedValue([]);
+      core.readFrontmatter.mockResolvedValue({
+        frontmatter: { schema_path: 'note.schema.yaml', validated: true },
+        format: 'toml',
+      });
+      core.markdownToYaml.mockResolvedValue('title: タイトル\n');
+      core.validateYamlWithSchema.mockResolvedValue([]);
+      (yamlCore.useYamlCore as Mock).mockReturnValue(core);
+      (fetchSchema as Mock).mockResolvedValue('type: object\n');
+    });
+
+    test('コアで解析した schema_path のスキーマで検証する', async () => {
+      const { result } = renderHook(() => useValidator(TOML_NOTE), { wrapper });
+
+      await waitFor(() => expect(result.current.schemaPath).toBe('note.schema.yaml'));
+      expect(core.checkFrontmatter).toHaveBeenCalledWith(TOML_NOTE);
+      expect(core.readFrontmatter).not.toHaveBeenCalled();
+      expect(fetchSchema).toHaveBeenCalledWith('note.schema.yaml', undefined);
+      await waitFor(() =>
+        expect(core.validateYamlWithSchema).toHaveBeenCalledWith(
+          'title: タイトル\n',
+          'type: object\n'
+        )
+      );
+    });
+
+    test('frontmatter_schema_path は schema_path がなくても検証する', async () => {
+      core.checkFrontmatter.mockResolvedValue({
+        errors: [],
+        content: {
+          frontmatter: { frontmatter_schema_path: 'meta.schema.yaml', validated: true },
+          format: 'toml',
+        },
+      });
+      (fetchSchema as Mock).mockResolvedValue('required: [title]\n');
+      renderHook(() => useValidator(TOML_NOTE), { wrapper });
+
+      await waitFor(() =>
+        expect(core.validateFrontmatter).toHaveBeenCalledWith(TOML_NOTE, 'required: [title]\n')
+      );
+      expect(fetchSchema).toHaveBeenCalledWith('meta.schema.yaml', undefined);
+      expect(core.validateYamlWithSchema).not.toHaveBeenCalled();
+    });
+
+    test('toggleValidation はコアでフロントマターの validated を書き換える', async () => {
+      const updated = TOML_NOTE.replace('validated = true', 'validated = false');
+      core.setFrontmatterField.mockResolvedValue(updated);
+      const { result } = renderHook(() => useValidator(TOML_NOTE), { wrapper });
+
+      let markdown: string | null = null;
+      await act(async () => {
+        markdown = await result.current.toggleValidation();
+      });
+
+      expect(core.setFrontmatterField).toHaveBeenCalledWith(TOML_NOTE, 'validated', false);
+      expect(markdown).toBe(updated);
+      expect(result.current.validated).toBe(false);
+    });
+  });
 });
 
 /* コメント: 以下のような完全なテストはメモリリーク対策のため実装しないが、
    toggleValidation は以下のような動作が期待されている:
-   
+
    1. Markdownにフロントマターがある場合、validated を切り替えた新しいMarkdownを返す
    2. フロントマターがない場合は null を返す
    3. validated フィールドがない場合は、デフォルトで true とみなし、false に切り替える
@@ -4,7 +4,13 @@
  */
 
 import { describe, it, expect } from 'vitest';
-import { mapNumericToStringErrorCode, ErrorCode } from '../validation-error.type';
+import {
+  mapNumericToStringErrorCode,
+  ErrorCode,
+  isSchemaValidationCode,
+  isFrontmatterError,
+  isSchemaValidationError,
+} from '../validation-error.type';
 
 describe('mapNumericToStringErrorCode', () => {
   it('数値コードを正しく変換する', () => {
@@ -15,8 +21,46 @@ describe('mapNumericToStringErrorCode', () => {
     expect(mapNumericToStringErrorCode('SchemaValidation')).toBe(ErrorCode.SchemaValidation);
   });
 
+  it('キーワードごとのスキーマ検証エラーのコードを変換する', () => {
+    expect(mapNumericToStringErrorCode(10)).toBe(ErrorCode.RequiredMissing);
+    expect(mapNumericToStringErrorCode(18)).toBe(ErrorCode.FormatMismatch);
+    expect(mapNumericToStringErrorCode('EnumMismatch')).toBe(ErrorCode.EnumMismatch);
+  });
+
+  it('Markdownから生成したキーの重複のコードを変換する', () => {
+    expect(mapNumericToStringErrorCode(19)).toBe(ErrorCode.DuplicateKey);
+    expect(mapNumericToStringErrorCode('DuplicateKey')).toBe(ErrorCode.DuplicateKey);
+  });
+
+  it('変換オプションの誤りのコードを変換する', () => {
+    expect(mapNumericToStringErrorCode(20)).toBe(ErrorCode.InvalidOptions);
+  });
+
   it('不正な入力は Unknown を返す', () => {
     expect(mapNumericToStringErrorCode('invalid')).toBe(ErrorCode.Unknown);
     expect(mapNumericToStringErrorCode(null)).toBe(ErrorCode.Unknown);
   });
 });
+
+describe('isSchemaValidationCode', () => {
+  it('キーワードごとのコードもスキーマ検証エラーとして扱う', () => {
+    expect(isSchemaValidationCode(ErrorCode.SchemaValidation)).toBe(true);
+    expect(isSchemaValidationCode(ErrorCode.TypeMismatch)).toBe(true);
+    expect(isSchemaValidationCode(ErrorCode.FrontmatterValidation)).toBe(false);
+    expect(isSchemaValidationCode(ErrorCode.SchemaLint)).toBe(false);
+  });
+});
+
+describe('isFrontmatterError / isSchemaValidationError', () => {
+  it('フロントマターのフィールドの検証エラーはキーワードごとのコードでもフロントマターとして扱う', () => {
+    const error = { line: 2, message: '', path: '', code: ErrorCode.RequiredMissing };
+    expect(isFrontmatterError(error)).toBe(false);
+    expect(isSchemaValidationError(error)).toBe(true);
+
+    const frontmatterError = { ...error, frontmatter: true };
+    expect(isFrontmatterError(frontmatterError)).toBe(true);
+    expect(isSchemaValidationError(frontmatterError)).toBe(false);
+
+    expect(isFrontmatterError({ ...error, code: ErrorCode.FrontmatterParse })).toBe(true);
+  });
+});
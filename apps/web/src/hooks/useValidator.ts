@@ -1,40 +1,8 @@
 import { useState, useEffect, useCallback } from 'react';
-import { ValidationError, ErrorCode } from './validation-error.type';
+import { ValidationError, ErrorCode, isBlockingError } from './validation-error.type';
 import { useYamlCore } from './useYamlCore';
 import { fetchSchema } from '../utils/schema';
 import useLogger from './useLogger';
-import { Frontmatter } from '../types/frontmatter';
-
-/**
- * Markdown文字列からフロントマター部分を抽出する
- *
- * @param {string} markdown - 解析対象のMarkdown文字列
- * @returns {Frontmatter | null} フロントマターオブジェクト（存在しない場合は null）
- */
-const extractFrontmatter = (markdown: string): Frontmatter | null => {
-  const match = markdown.match(/^---\n([\s\S]*?)\n---/);
-  if (!match) return null;
-
-  const frontmatterLines = match[1].split('\n');
-  const frontmatter: Frontmatter = {};
-
-  frontmatterLines.forEach(line => {
-    const colonIndex = line.indexOf(':');
-    if (colonIndex !== -1) {
-      const key = line.slice(0, colonIndex).trim();
-      const value =
-        line.slice(colonIndex + 1).trim() === 'true'
-          ? true
-          : line.slice(colonIndex + 1).trim() === 'false'
-            ? false
-            : line.slice(colonIndex + 1).trim();
-
-      frontmatter[key] = value;
-    }
-  });
-
-  return frontmatter;
-};
 
 /**
  * Markdownのバリデーション機能を提供するHook
@@ -46,12 +14,13 @@ const extractFrontmatter = (markdown: string): Frontmatter | null => {
  *   isValidating: boolean,
  *   schemaPath: string | null,
  *   validated: boolean,
- *   toggleValidation: (newState?: boolean) => string | null,
+ *   toggleValidation: (newState?: boolean) => Promise<string | null>,
  *   clearErrors: () => void
  * }}
  *
  * @description
  * Markdownテキストを受け取り、フロントマター検証とスキーマ検証を実行する。
+ * フロントマター（YAML / TOML / JSON）はWASMコアで解析する。
  * 30msのデバウンス処理を行い、エラー結果を返却する。
  * toggleValidation関数により検証の有効/無効を切り替えることができる。
  */
@@ -61,38 +30,36 @@ export const useValidator = (markdown: string) => {
   const [schemaPath, setSchemaPath] = useState<string | null>(null);
   const [validated, setValidated] = useState<boolean>(true);
 
-  const { wasmLoaded, validateFrontmatter, markdownToYaml, validateYamlWithSchema } = useYamlCore();
+  const {
+    wasmLoaded,
+    checkFrontmatter,
+    validateFrontmatter,
+    readFrontmatter,
+    setFrontmatterField,
+    markdownToYaml,
+    validateYamlWithSchema,
+  } = useYamlCore();
 
   const { log } = useLogger();
 
   // 検証トグル関数
   const toggleValidation = useCallback(
-    (newState?: boolean) => {
+    async (newState?: boolean) => {
       if (!markdown) return null;
 
       try {
         // フロントマターを解析
-        const frontmatter = extractFrontmatter(markdown);
-        if (!frontmatter) return null;
+        const content = await readFrontmatter(markdown);
+        if (!content) return null;
 
         // validated フィールドの現在値を取得
-        const currentValidated = frontmatter.validated !== false;
+        const currentValidated = content.frontmatter.validated !== false;
 
         // 新しい状態を決定（引数があればその値、なければトグル）
         const nextValidated = newState !== undefined ? newState : !currentValidated;
 
-        // フロントマター内の validated 値を更新
-        const updatedFrontmatter = { ...frontmatter, validated: nextValidated };
-
-        // マークダウン文字列のフロントマター部分を置換
-        const frontmatterBlock = Object.entries(updatedFrontmatter)
-          .map(([key, value]) => `${key}: ${value}`)
-          .join('\n');
-
-        const updatedMarkdown = markdown.replace(
-          /^---\n[\s\S]*?\n---/,
-          `---\n${frontmatterBlock}\n---`
-        );
+        // フロントマター内の validated 値を更新（フロントマターの形式はそのまま）
+        const updatedMarkdown = await setFrontmatterField(markdown, 'validated', nextValidated);
 
         // 内部状態を更新
         setValidated(nextValidated);
@@ -112,7 +79,7 @@ export const useValidator = (markdown: string) => {
         return null;
       }
     },
-    [markdown, log]
+    [markdown, readFrontmatter, setFrontmatterField, log]
   );
 
   // エラーを手動でクリアする関数
@@ -140,57 +107,83 @@ export const useValidator = (markdown: string) => {
         const startTime = performance.now();
         let allErrors: ValidationError[] = [];
 
-        // ステップ1: フロントマター検証
-        const frontmatterErrors = await validateFrontmatter(markdown);
+        // ステップ1: フロントマター検証（フィールドも同じ解析結果から取得する）
+        const { errors: frontmatterErrors, content } = await checkFrontmatter(markdown);
         allErrors = [...frontmatterErrors];
 
-        // フロントマターが正常な場合のみスキーマ検証を行う
-        if (frontmatterErrors.length === 0) {
-          // フロントマターから schema_path と validated を抽出
-          const frontmatter = extractFrontmatter(markdown);
+        // フロントマターが正常な場合のみスキーマ検証を行う（警告のみなら正常とみなす）
+        if (!frontmatterErrors.some(isBlockingError)) {
+          // フロントマターから schema_path / frontmatter_schema_path と validated を抽出
+          const frontmatter = content?.frontmatter;
           const currentSchemaPath = frontmatter?.schema_path || null;
+          const frontmatterSchemaPath = frontmatter?.frontmatter_schema_path || null;
           const isValidated = frontmatter?.validated !== false; // デフォルトはtrue
 
           setSchemaPath(currentSchemaPath);
           setValidated(isValidated);
 
-          // schema_path が設定されており、validated が true の場合のみスキーマ検証を実行
-          if (currentSchemaPath && isValidated) {
+          // スキーマの取得・検証の失敗をエラーとして追加する
+          const addSchemaError = (
+            schemaError: unknown,
+            key: 'schema_path' | 'frontmatter_schema_path'
+          ) => {
+            const errorMessage =
+              schemaError instanceof Error ? schemaError.message : String(schemaError);
+
+            // スキーマパスを解決できない場合は特別なエラーメッセージを表示
+            const isSchemaPathError = errorMessage.includes('スキーマを解決できません');
+
+            allErrors.push({
+              line: 2, // フロントマターの行（schema_pathの行を指すよう推定）
+              message: isSchemaPathError
+                ? `スキーマパスエラー: ${errorMessage}`
+                : `スキーマ検証エラー: ${errorMessage}`,
+              path: isSchemaPathError ? key : '',
+              code: isSchemaPathError ? ErrorCode.SchemaCompile : ErrorCode.SchemaValidation,
+            });
+
+            log('error', isSchemaPathError ? 'schema_path_error' : 'schema_validation_error', {
+              error: errorMessage,
+              schemaPath: key === 'schema_path' ? currentSchemaPath : frontmatterSchemaPath,
+            });
+          };
+
+          // validated が true の場合のみスキーマ検証を実行
+          if (isValidated && frontmatterSchemaPath) {
+            // ステップ2: フロントマターのフィールドを frontmatter_schema_path のスキーマ全体で検証
+            // （schema_path の有無によらない）
             try {
-              // ステップ2: スキーマを取得
+              const frontmatterSchema = await fetchSchema(frontmatterSchemaPath, undefined);
+              allErrors = [
+                ...allErrors,
+                ...(await validateFrontmatter(markdown, frontmatterSchema)),
+              ];
+            } catch (schemaError) {
+              addSchemaError(schemaError, 'frontmatter_schema_path');
+            }
+          }
+
+          if (isValidated && currentSchemaPath) {
+            try {
+              // ステップ3: スキーマを取得
               const schema = await fetchSchema(currentSchemaPath, undefined);
 
-              // ステップ3: Markdown → YAML変換
+              // frontmatter_schema_path がなければ schema_path のスキーマの frontmatter セクションを適用
+              if (!frontmatterSchemaPath) {
+                allErrors = [...allErrors, ...(await validateFrontmatter(markdown, schema))];
+              }
+
+              // ステップ4: Markdown → YAML変換
               const yaml = await markdownToYaml(markdown);
               console.log('🔵 yaml', yaml);
 
-              // ステップ4: YAML × Schema 検証
+              // ステップ5: YAML × Schema 検証
               const schemaErrors = await validateYamlWithSchema(yaml, schema);
 
               // スキーマ検証エラーを追加
               allErrors = [...allErrors, ...schemaErrors];
             } catch (schemaError) {
-              const errorMessage =
-                schemaError instanceof Error ? schemaError.message : String(schemaError);
-
-              // 絶対パスエラーの場合は特別なエラーメッセージを表示
-              const isAbsolutePathError = errorMessage.includes(
-                '絶対パスでのスキーマ参照はサポートされていません'
-              );
-
-              allErrors.push({
-                line: 2, // フロントマターの行（schema_pathの行を指すよう推定）
-                message: isAbsolutePathError
-                  ? `スキーマパスエラー: ${errorMessage}`
-                  : `スキーマ検証エラー: ${errorMessage}`,
-                path: isAbsolutePathError ? 'schema_path' : '',
-                code: isAbsolutePathError ? ErrorCode.SchemaCompile : ErrorCode.SchemaValidation,
-              });
-
-              log('error', isAbsolutePathError ? 'schema_path_error' : 'schema_validation_error', {
-                error: errorMessage,
-                schemaPath: currentSchemaPath,
-              });
+              addSchemaError(schemaError, 'schema_path');
             }
           }
         }
@@ -203,7 +196,7 @@ export const useValidator = (markdown: string) => {
         log('info', 'validation_time', {
           component: 'useValidator',
           timeMs: validationTime.toFixed(2),
-          hasErrors: allErrors.length > 0,
+          hasErrors: allErrors.some(isBlockingError),
           phase: 'S3',
         });
       } catch (error) {
@@ -230,7 +223,15 @@ export const useValidator = (markdown: string) => {
 
     // クリーンアップ
     return () => clearTimeout(timerId);
-  }, [markdown, wasmLoaded, validateFrontmatter, markdownToYaml, validateYamlWithSchema, log]);
+  }, [
+    markdown,
+    wasmLoaded,
+    checkFrontmatter,
+    validateFrontmatter,
+    markdownToYaml,
+    validateYamlWithSchema,
+    log,
+  ]);
 
   return { errors, isValidating, schemaPath, validated, toggleValidation, clearErrors };
 };
@@ -11,13 +11,71 @@ import {
  */
 interface CoreWasmType {
   validate_yaml: (yaml: string, schema: string) => string;
-  md_headings_to_yaml: (md: string) => string; // 追加: 見出し構造を解析してYAML化する関数
-  parse_and_validate_frontmatter: (md: string) => string;
-  compile_schema: (schema: string) => string; // 追加: スキーマ自体の検証
+  md_headings_to_yaml: (md: string, options?: string) => string; // 追加: 見出し構造を解析してYAML化する関数
+  parse_and_validate_frontmatter: (md: string, schema?: string) => string;
+  set_frontmatter_field: (md: string, key: string, valueJson: string) => string;
+  compile_schema: (schema: string, options?: string | null) => string; // 追加: スキーマ自体の検証（リントの設定は任意）
   version: () => string;
   apply_patch: (yaml: string, patch: string) => string;
 }
 
+/**
+ * パッチ適用結果の型定義（Rust側のPatchResult構造体に対応）
+ */
+interface PatchResult extends ValidationResult {
+  yaml: string;
+}
+
+/**
+ * Markdownの書き換え結果の型定義（Rust側のMarkdownResult構造体に対応）
+ */
+interface MarkdownResult extends ValidationResult {
+  markdown: string;
+}
+
+/**
+ * フロントマターの形式（Rust側のFrontmatterFormatに対応）
+ */
+export type FrontmatterFormat = 'yaml' | 'toml' | 'json';
+
+/**
+ * フロントマターのフィールド（Rust側のFrontmatter構造体に対応）
+ * schema_path / validated 以外のフィールドも記述順のまま含まれる
+ */
+export interface FrontmatterData {
+  schema_path?: string;
+  frontmatter_schema_path?: string;
+  validated: boolean;
+  title?: string;
+  tags?: string[] | string;
+  created_at?: string;
+  [key: string]: unknown;
+}
+
+/**
+ * フロントマターの解析・検証結果の型定義（Rust側のFrontmatterResult構造体に対応）
+ */
+interface FrontmatterResult extends ValidationResult {
+  frontmatter: FrontmatterData | null;
+  format: FrontmatterFormat | null;
+}
+
+/**
+ * readFrontmatter の結果（フィールドと記述されている形式）
+ */
+export interface FrontmatterContent {
+  frontmatter: FrontmatterData;
+  format: FrontmatterFormat;
+}
+
+/**
+ * checkFrontmatter の結果（検証エラーとフィールド）
+ */
+export interface FrontmatterCheck {
+  errors: ValidationError[];
+  content: FrontmatterContent | null;
+}
+
 /**
  * 変換結果の型定義
  * @property {boolean} success - 成功フラグ
@@ -41,7 +99,10 @@ export interface ConversionResult {
  *   wasmLoaded: boolean;
  *   wasmLoading: boolean;
  *   error: Error | null;
- *   validateFrontmatter: (md: string) => Promise<ValidationError[]>;
+ *   checkFrontmatter: (md: string, schema?: string) => Promise<FrontmatterCheck>;
+ *   validateFrontmatter: (md: string, schema?: string) => Promise<ValidationError[]>;
+ *   readFrontmatter: (md: string) => Promise<FrontmatterContent | null>;
+ *   setFrontmatterField: (md: string, key: string, value: unknown) => Promise<string>;
  * }}
  */
 export function useYamlCore() {
@@ -81,33 +142,39 @@ export function useYamlCore() {
   }, [wasmLoaded, wasmLoading]);
 
   /**
-   * Markdownのフロントマターを検証し、エラーがあれば返す
+   * Markdownのフロントマターを1回の解析で検証し、エラーとフィールドをまとめて返す
    *
-   * @param {string} markdown - 検証対象のMarkdown文字列
-   * @returns {Promise<ValidationError[]>} - 検証エラーの配列（エラーなしなら空配列）
+   * @param {string} markdown - 対象のMarkdown文字列
+   * @param {string} [schema] - フィールドの検証に使うスキーマ（`frontmatter` セクション、
+   *   または frontmatter_schema_path のスキーマ全体を適用）
+   * @returns {Promise<FrontmatterCheck>} - 検証エラーの配列とフィールド（解析できなければnull）
    */
-  const validateFrontmatter = useCallback(
-    async (markdown: string): Promise<ValidationError[]> => {
+  const checkFrontmatter = useCallback(
+    async (markdown: string, schema?: string): Promise<FrontmatterCheck> => {
       if (!instance || !wasmLoaded) {
         throw new Error('WASM module not loaded');
       }
 
       try {
         // WASMコア関数呼び出し
-        const resultJson = instance.parse_and_validate_frontmatter(markdown);
-        const result = JSON.parse(resultJson) as ValidationResult;
-
-        // 結果をValidationError[]形式に変換
-        if (!result.success) {
-          return result.errors.map((err: WasmErrorInfo) => ({
-            line: err.line ?? 0,
-            message: err.message,
-            path: err.path ?? '',
-            code: mapNumericToStringErrorCode(err.code),
-          }));
-        }
+        const resultJson = instance.parse_and_validate_frontmatter(markdown, schema);
+        const result = JSON.parse(resultJson) as FrontmatterResult;
 
-        return [];
+        // 結果をValidationError[]形式に変換（x-severityで下げた警告はsuccess: trueでも返される）
+        const errors = result.errors.map((err: WasmErrorInfo) => ({
+          line: err.line ?? 0,
+          message: err.message,
+          path: err.path ?? '',
+          code: mapNumericToStringErrorCode(err.code),
+          severity: err.severity ?? 'error',
+          params: err.params,
+          frontmatter: err.frontmatter,
+        }));
+        const content =
+          result.frontmatter && result.format
+            ? { frontmatter: result.frontmatter, format: result.format }
+            : null;
+        return { errors, content };
       } catch (error) {
         console.error('Error validating frontmatter:', error);
         throw error;
@@ -116,6 +183,56 @@ export function useYamlCore() {
     [instance, wasmLoaded]
   );
 
+  /**
+   * Markdownのフロントマターを検証し、エラーがあれば返す
+   *
+   * @param {string} markdown - 検証対象のMarkdown文字列
+   * @param {string} [schema] - フィールドの検証に使うスキーマ（checkFrontmatter と同じ）
+   * @returns {Promise<ValidationError[]>} - 検証エラーの配列（エラーなしなら空配列、警告は `severity` で区別する）
+   */
+  const validateFrontmatter = useCallback(
+    async (markdown: string, schema?: string): Promise<ValidationError[]> =>
+      (await checkFrontmatter(markdown, schema)).errors,
+    [checkFrontmatter]
+  );
+
+  /**
+   * Markdownのフロントマターのフィールドを取得する（YAML / TOML / JSONのいずれの形式でもよい）
+   *
+   * @param {string} markdown - 対象のMarkdown文字列
+   * @returns {Promise<FrontmatterContent | null>} - フィールドと形式（フロントマターを解析できなければnull）
+   */
+  const readFrontmatter = useCallback(
+    async (markdown: string): Promise<FrontmatterContent | null> =>
+      (await checkFrontmatter(markdown)).content,
+    [checkFrontmatter]
+  );
+
+  /**
+   * フロントマターのフィールドを1つ書き換える（ない場合は追加する）
+   *
+   * @param {string} markdown - 対象のMarkdown文字列
+   * @param {string} key - フィールド名
+   * @param {unknown} value - 新しい値（JSONで表現できる値）
+   * @returns {Promise<string>} - 書き換えたMarkdown（フロントマターは元の形式のまま）
+   * @throws フロントマターがない、または書き換えられない場合は最初のエラーメッセージで例外を送出
+   */
+  const setFrontmatterField = useCallback(
+    async (markdown: string, key: string, value: unknown): Promise<string> => {
+      if (!instance || !wasmLoaded) {
+        throw new Error('WASM module not loaded');
+      }
+
+      const resultJson = instance.set_frontmatter_field(markdown, key, JSON.stringify(value));
+      const result = JSON.parse(resultJson) as MarkdownResult;
+      if (!result.success) {
+        throw new Error(result.errors[0]?.message ?? 'Failed to update frontmatter');
+      }
+      return result.markdown;
+    },
+    [instance, wasmLoaded]
+  );
+
   /**
    * マークダウンからYAMLに変換
    *
@@ -147,7 +264,7 @@ export function useYamlCore() {
    *
    * @param {string} yaml - 検証するYAML文字列
    * @param {string} schema - JSONスキーマ文字列
-   * @returns {Promise<ValidationError[]>} 検証エラーの配列
+   * @returns {Promise<ValidationError[]>} 検証エラーの配列（`x-severity` で下げた警告を含む）
    *
    * @description
    * YAMLをJSONスキーマで検証し、エラーがあればValidationError[]として返す。
@@ -163,16 +280,18 @@ export function useYamlCore() {
         const resultJson = instance.validate_yaml(yaml, schema);
         const result = JSON.parse(resultJson) as ValidationResult;
 
-        if (!result.success) {
-          return result.errors.map((err: WasmErrorInfo) => ({
+        // x-severityで下げた警告はsuccess: trueでも返される（エラー以外は接頭辞を付けない）
+        return result.errors.map((err: WasmErrorInfo) => {
+          const severity = err.severity ?? 'error';
+          return {
             line: err.line ?? 0,
-            message: `スキーマ検証エラー: ${err.message}`,
+            message: severity === 'error' ? `スキーマ検証エラー: ${err.message}` : err.message,
             path: err.path ?? '',
             code: mapNumericToStringErrorCode(err.code),
-          }));
-        }
-
-        return [];
+            severity,
+            params: err.params,
+          };
+        });
       } catch (error) {
         console.error('YAML schema validation error:', error);
         throw error;
@@ -185,8 +304,9 @@ export function useYamlCore() {
    * YAMLパッチを適用
    *
    * @param {string} yaml - 元のYAML文字列
-   * @param {string} patch - 適用するパッチ（JSON Patch形式）
+   * @param {string} patch - 適用するパッチ（JSON Patch / Merge Patch形式）
    * @returns {Promise<string>} パッチ適用後のYAML文字列
+   * @throws パッチの適用に失敗した場合は最初のエラーメッセージで例外を送出
    */
   const applyPatch = useCallback(
     async (yaml: string, patch: string): Promise<string> => {
@@ -195,7 +315,11 @@ export function useYamlCore() {
       }
 
       try {
-        return instance.apply_patch(yaml, patch);
+        const result = JSON.parse(instance.apply_patch(yaml, patch)) as PatchResult;
+        if (!result.success) {
+          throw new Error(result.errors[0]?.message ?? 'Failed to apply patch');
+        }
+        return result.yaml;
       } catch (error) {
         console.error('YAML patch application error:', error);
         throw error;
@@ -208,34 +332,38 @@ export function useYamlCore() {
    * スキーマのコンパイル検証を行う
    *
    * @param {string} schemaYaml - 検証するスキーマYAML
-   * @returns {Promise<ValidationError[]>} 検証エラーの配列
+   * @param {string} [lintOptions] - リントの設定（YAML/JSON、例: `{lints: {unknown-keyword: off}}`）
+   * @returns {Promise<ValidationError[]>} 検証エラーとリントの警告の配列（`severity` で区別する）
    *
    * @description
    * スキーマYAMLの構文とJSONスキーマとしての論理的正当性を検証する。
    * WASMコアの`compile_schema`関数を使用。
    */
   const compileSchema = useCallback(
-    async (schemaYaml: string): Promise<ValidationError[]> => {
+    async (schemaYaml: string, lintOptions?: string): Promise<ValidationError[]> => {
       if (!instance || !wasmLoaded) {
         throw new Error('WASM module not loaded');
       }
 
       try {
-        const resultJson = instance.compile_schema(schemaYaml);
+        const resultJson = instance.compile_schema(schemaYaml, lintOptions);
         const result = JSON.parse(resultJson) as ValidationResult;
 
-        if (!result.success) {
-          return result.errors.map((err: WasmErrorInfo) => ({
+        // リントの警告はsuccess: trueでも返される
+        return result.errors.map((err: WasmErrorInfo) => {
+          const severity = err.severity ?? 'error';
+          return {
             line: err.line ?? 0,
-            message: err.message.startsWith('スキーマ構文エラー:')
-              ? err.message
-              : `スキーマ構文エラー: ${err.message}`,
+            message:
+              severity !== 'error' || err.message.startsWith('スキーマ構文エラー:')
+                ? err.message
+                : `スキーマ構文エラー: ${err.message}`,
             path: err.path ?? '',
             code: mapNumericToStringErrorCode(err.code),
-          }));
-        }
-
-        return [];
+            severity,
+            rule: err.rule,
+          };
+        });
       } catch (error) {
         console.error('Schema compilation error:', error);
         throw error;
@@ -248,7 +376,10 @@ export function useYamlCore() {
     wasmLoaded,
     wasmLoading,
     error,
+    checkFrontmatter,
     validateFrontmatter,
+    readFrontmatter,
+    setFrontmatterField,
     markdownToYaml,
     validateYamlWithSchema,
     compileSchema,
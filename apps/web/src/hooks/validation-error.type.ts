@@ -4,14 +4,56 @@
  * @property {number} line - エラー発生行番号（0の場合は特定不可）
  * @property {string} message - エラーメッセージ
  * @property {string} path - エラー発生箇所のパス（YAML/JSON Pointer等）
+ * @property {Severity} severity - 重大度（省略時はerror）
  */
 export interface ValidationError {
   line: number;
   message: string;
   path: string;
   code: ErrorCode;
+  severity?: Severity;
+  /** 診断を出したリントのルール名 */
+  rule?: string;
+  /** 検証エラーのパラメータ（期待される型、許可された値、上限・下限など） */
+  params?: ErrorParams;
+  /** フロントマターのフィールドの検証エラーかどうか（codeはキーワードごとのコードになる） */
+  frontmatter?: boolean;
 }
 
+/**
+ * 検証エラーの構造化されたパラメータ（失敗したキーワードに応じて一部だけ設定される）
+ *
+ * Rust側のErrorParams構造体に対応する型定義
+ */
+export interface ErrorParams {
+  /** 存在しない必須のプロパティ（RequiredMissing） */
+  property?: string;
+  /** 期待される型（TypeMismatch） */
+  expected?: string[];
+  /** 許可された値（EnumMismatch） */
+  allowed?: unknown[];
+  /** 上限・下限（TooShort / TooLong / OutOfRange） */
+  limit?: number;
+  /** 正規表現（PatternMismatch） */
+  pattern?: string;
+  /** 形式名（FormatMismatch） */
+  format?: string;
+  /** 許可されていないプロパティ（AdditionalProperty） */
+  unexpected?: string[];
+}
+
+/** 診断の重大度（error以外は検証を失敗させない） */
+export type Severity = 'error' | 'warning' | 'info' | 'hint';
+
+/**
+ * 検証を失敗させる診断（severityがerror）かどうか
+ *
+ * @param {ValidationError} error - 判定する診断
+ * @returns {boolean} severityがerror（省略時を含む）ならtrue
+ */
+export const isBlockingError = (error: ValidationError): boolean =>
+  (error.severity ?? 'error') === 'error';
+
 /** バリデーションエラー種別 */
 export enum ErrorCode {
   YamlParse = 'YamlParse',
@@ -20,8 +62,65 @@ export enum ErrorCode {
   FrontmatterValidation = 'FrontmatterValidation',
   SchemaValidation = 'SchemaValidation',
   Unknown = 'Unknown',
+  PatchApply = 'PatchApply',
+  DuplicateHeading = 'DuplicateHeading',
+  SchemaResolve = 'SchemaResolve',
+  SchemaLint = 'SchemaLint',
+  RequiredMissing = 'RequiredMissing',
+  TypeMismatch = 'TypeMismatch',
+  EnumMismatch = 'EnumMismatch',
+  PatternMismatch = 'PatternMismatch',
+  TooShort = 'TooShort',
+  TooLong = 'TooLong',
+  OutOfRange = 'OutOfRange',
+  AdditionalProperty = 'AdditionalProperty',
+  FormatMismatch = 'FormatMismatch',
+  DuplicateKey = 'DuplicateKey',
+  InvalidOptions = 'InvalidOptions',
 }
 
+/** 失敗したキーワードごとのスキーマ検証エラーのコード（ErrorCode.SchemaValidation以外） */
+const SCHEMA_VALIDATION_CODES: ReadonlySet<ErrorCode> = new Set([
+  ErrorCode.RequiredMissing,
+  ErrorCode.TypeMismatch,
+  ErrorCode.EnumMismatch,
+  ErrorCode.PatternMismatch,
+  ErrorCode.TooShort,
+  ErrorCode.TooLong,
+  ErrorCode.OutOfRange,
+  ErrorCode.AdditionalProperty,
+  ErrorCode.FormatMismatch,
+]);
+
+/**
+ * スキーマ検証エラー（ErrorCode.SchemaValidation、またはキーワードごとのコード）かどうか
+ *
+ * @param {ErrorCode} code - 判定するエラーコード
+ * @returns {boolean} スキーマ検証エラーのコードならtrue
+ */
+export const isSchemaValidationCode = (code: ErrorCode): boolean =>
+  code === ErrorCode.SchemaValidation || SCHEMA_VALIDATION_CODES.has(code);
+
+/**
+ * フロントマターのエラー（解析エラー、またはフロントマターのフィールドの検証エラー）かどうか
+ *
+ * @param {ValidationError} error - 判定する診断
+ * @returns {boolean} フロントマターのエラーならtrue
+ */
+export const isFrontmatterError = (error: ValidationError): boolean =>
+  error.frontmatter === true ||
+  error.code === ErrorCode.FrontmatterParse ||
+  error.code === ErrorCode.FrontmatterValidation;
+
+/**
+ * 本文のスキーマ検証エラー（フロントマターのフィールドの検証エラーを除く）かどうか
+ *
+ * @param {ValidationError} error - 判定する診断
+ * @returns {boolean} 本文のスキーマ検証エラーならtrue
+ */
+export const isSchemaValidationError = (error: ValidationError): boolean =>
+  !error.frontmatter && isSchemaValidationCode(error.code);
+
 /**
  * WASMから返されるエラー情報
  *
@@ -29,9 +128,27 @@ export enum ErrorCode {
  */
 export interface WasmErrorInfo {
   line?: number;
+  /** エラー開始列番号（1始まり、0の場合は特定不可） */
+  column?: number;
+  /** エラー範囲の終了行番号 */
+  end_line?: number;
+  /** エラー範囲の終了列番号（排他的） */
+  end_column?: number;
+  /** エラー範囲の開始バイトオフセット */
+  offset?: number;
+  /** エラー範囲の終了バイトオフセット（排他的） */
+  end_offset?: number;
   message: string;
   path?: string;
   code: number;
+  /** 重大度（省略時はerror） */
+  severity?: Severity;
+  /** 診断を出したリントのルール名 */
+  rule?: string;
+  /** 検証エラーのパラメータ */
+  params?: ErrorParams;
+  /** フロントマターのフィールドの検証エラーかどうか */
+  frontmatter?: boolean;
 }
 
 /**
@@ -66,6 +183,36 @@ export function mapNumericToStringErrorCode(code: unknown): ErrorCode {
         return ErrorCode.SchemaValidation;
       case ErrorCode.Unknown:
         return ErrorCode.Unknown;
+      case ErrorCode.PatchApply:
+        return ErrorCode.PatchApply;
+      case ErrorCode.DuplicateHeading:
+        return ErrorCode.DuplicateHeading;
+      case ErrorCode.SchemaResolve:
+        return ErrorCode.SchemaResolve;
+      case ErrorCode.SchemaLint:
+        return ErrorCode.SchemaLint;
+      case ErrorCode.RequiredMissing:
+        return ErrorCode.RequiredMissing;
+      case ErrorCode.TypeMismatch:
+        return ErrorCode.TypeMismatch;
+      case ErrorCode.EnumMismatch:
+        return ErrorCode.EnumMismatch;
+      case ErrorCode.PatternMismatch:
+        return ErrorCode.PatternMismatch;
+      case ErrorCode.TooShort:
+        return ErrorCode.TooShort;
+      case ErrorCode.TooLong:
+        return ErrorCode.TooLong;
+      case ErrorCode.OutOfRange:
+        return ErrorCode.OutOfRange;
+      case ErrorCode.AdditionalProperty:
+        return ErrorCode.AdditionalProperty;
+      case ErrorCode.FormatMismatch:
+        return ErrorCode.FormatMismatch;
+      case ErrorCode.DuplicateKey:
+        return ErrorCode.DuplicateKey;
+      case ErrorCode.InvalidOptions:
+        return ErrorCode.InvalidOptions;
       default:
         return ErrorCode.Unknown;
     }
@@ -87,6 +234,36 @@ export function mapNumericToStringErrorCode(code: unknown): ErrorCode {
       return ErrorCode.FrontmatterValidation;
     case 4:
       return ErrorCode.SchemaValidation;
+    case 6:
+      return ErrorCode.PatchApply;
+    case 7:
+      return ErrorCode.DuplicateHeading;
+    case 8:
+      return ErrorCode.SchemaResolve;
+    case 9:
+      return ErrorCode.SchemaLint;
+    case 10:
+      return ErrorCode.RequiredMissing;
+    case 11:
+      return ErrorCode.TypeMismatch;
+    case 12:
+      return ErrorCode.EnumMismatch;
+    case 13:
+      return ErrorCode.PatternMismatch;
+    case 14:
+      return ErrorCode.TooShort;
+    case 15:
+      return ErrorCode.TooLong;
+    case 16:
+      return ErrorCode.OutOfRange;
+    case 17:
+      return ErrorCode.AdditionalProperty;
+    case 18:
+      return ErrorCode.FormatMismatch;
+    case 19:
+      return ErrorCode.DuplicateKey;
+    case 20:
+      return ErrorCode.InvalidOptions;
     case 5:
     default:
       return ErrorCode.Unknown;
@@ -14,11 +14,75 @@ declare module 'core-wasm' {
    */
   export function validate_yaml(yaml_str: string, schema_str: string): string;
 
+  /**
+   * Markdownを見出し構造のYAMLに変換してバリデーションする（エラー位置はMarkdownの行）
+   */
+  export function validate_markdown(md_str: string, schema_str: string, options_str?: string | null): string;
+
+  /**
+   * スキーマ自体が有効かを検証し、問題とリントの診断(JSON)を返す（オプションはリントの設定）
+   */
+  export function compile_schema(schema_str: string, options_str?: string | null): string;
+
+  /**
+   * Markdownのフロントマターを解析・検証し、結果とフィールド(JSON)を返す
+   * （スキーマを渡すと `frontmatter` セクションでフィールドも検証する）
+   */
+  export function parse_and_validate_frontmatter(md_str: string, schema_str?: string | null): string;
+
+  /**
+   * フロントマターを別の形式(yaml / toml / json)に書き換え、結果(JSON)を返す
+   */
+  export function convert_frontmatter(md_str: string, format: string): string;
+
+  /**
+   * フロントマターのフィールドを1つ書き換え（ない場合は追加）、結果(JSON)を返す
+   * （値はJSON形式、フロントマターは元の形式のまま書き直す）
+   */
+  export function set_frontmatter_field(md_str: string, key: string, value_json: string): string;
+
+  /**
+   * コンパイル済みスキーマ（スキーマ変更時のみ生成し、繰り返しバリデーションに利用する）
+   */
+  export class CompiledSchema {
+    constructor(schema_str: string);
+    free(): void;
+    /** スキーマのコンパイル結果(JSON) */
+    compile_result(): string;
+    /** YAMLをバリデーションし、結果(JSON)を返す */
+    validate(yaml_str: string): string;
+    /** Markdownをバリデーションし、結果(JSON)を返す */
+    validate_markdown(md_str: string, options_str?: string | null): string;
+  }
+
+  /**
+   * スキーマのレジストリ（登録したスキーマから schema_path などの参照を解決する）
+   */
+  export class SchemaRegistry {
+    constructor();
+    free(): void;
+    /** スキーマを登録し、結果(JSON)を返す */
+    register(path: string, schema_str: string): string;
+    /** 登録済みのスキーマを削除する */
+    unregister(path: string): boolean;
+    /** 登録済みのパスの一覧(JSON配列) */
+    paths(): string;
+    /** 参照元のノートからの参照を解決し、結果(JSON)を返す */
+    resolve(reference: string, base: string): string;
+    /** 参照を解決し、ファイルをまたぐ $ref を含めてコンパイルする */
+    compile(reference: string, base: string): CompiledSchema;
+  }
+
   /**
    * YAMLに対してパッチを適用する
    */
   export function apply_patch(yaml_str: string, patch_str: string): string;
 
+  /**
+   * 見出し構造のYAMLをMarkdownに戻し、結果(JSON)を返す
+   */
+  export function yaml_to_md(yaml_str: string, frontmatter_str?: string | null): string;
+
   /**
    * 2つのYAML文字列の差分(JSON Patch形式)を生成する
    */
@@ -40,7 +104,12 @@ declare module 'core-wasm' {
   export function md_to_yaml(md: string): string;
 
   /**
-   * YAML文字列をMarkdown文字列に変換する
+   * Markdownの見出し構造をYAML文字列に変換する（オプションはYAML/JSON形式）
+   */
+  export function md_headings_to_yaml(md_str: string, options_str?: string | null): string;
+
+  /**
+   * Markdownの見出し構造をYAMLに変換し、ソースマップと共に結果(JSON)を返す
    */
-  export function yaml_to_md(yaml: string): string;
+  export function md_headings_to_yaml_with_source_map(md_str: string, options_str?: string | null): string;
 }
@@ -1,4 +1,4 @@
-import { fetchSchema, isAbsolutePath } from '../schema';
+import { fetchSchema, normalizeSchemaPath } from '../schema';
 import { http, HttpResponse } from 'msw';
 import { setupServer } from 'msw/node';
 import { describe, test, expect, beforeAll, afterAll, afterEach } from 'vitest';
@@ -67,31 +67,41 @@ afterEach(() => {
 });
 afterAll(() => server.close());
 
-describe('isAbsolutePath', () => {
-  test('Unixスタイルの絶対パスを正しく判定する', () => {
-    expect(isAbsolutePath('/schemas/test.yaml')).toBe(true);
-    expect(isAbsolutePath('/var/www/schemas/test.yaml')).toBe(true);
-  });
-
-  test('Windowsスタイルの絶対パスを正しく判定する', () => {
-    expect(isAbsolutePath('C:\\schemas\\test.yaml')).toBe(true);
-    expect(isAbsolutePath('D:/schemas/test.yaml')).toBe(true);
+describe('normalizeSchemaPath', () => {
+  test('相対パスを参照元のディレクトリから解決する', () => {
+    expect(normalizeSchemaPath('./schema.yaml', '/notes/today.md')).toBe('/notes/schema.yaml');
+    expect(normalizeSchemaPath('schema.yaml', '/notes/today.md')).toBe('/notes/schema.yaml');
+    expect(normalizeSchemaPath('../schemas/note.yaml', '/notes/today.md')).toBe(
+      '/schemas/note.yaml'
+    );
   });
 
-  test('URLを絶対パスとして判定する', () => {
-    expect(isAbsolutePath('http://example.com/schema.yaml')).toBe(true);
-    expect(isAbsolutePath('https://example.com/schema.yaml')).toBe(true);
+  test('/ で始まるパスをワークスペースルートから解決する', () => {
+    expect(normalizeSchemaPath('/schemas/note.yaml', '/notes/today.md')).toBe('/schemas/note.yaml');
+    expect(normalizeSchemaPath('/schemas/./a/../note.yaml', '/notes/today.md')).toBe(
+      '/schemas/note.yaml'
+    );
   });
 
-  test('相対パスを正しく判定する', () => {
-    expect(isAbsolutePath('./schema.yaml')).toBe(false);
-    expect(isAbsolutePath('../schema.yaml')).toBe(false);
-    expect(isAbsolutePath('schema.yaml')).toBe(false);
-    expect(isAbsolutePath('schemas/test.yaml')).toBe(false);
+  test('\\ を / として扱う', () => {
+    expect(normalizeSchemaPath('..\\schemas\\note.yaml', '/notes/today.md')).toBe(
+      '/schemas/note.yaml'
+    );
   });
 
-  test('空文字列はfalseを返す', () => {
-    expect(isAbsolutePath('')).toBe(false);
+  test('URL・ドライブ文字付きのパス・ワークスペースの外を指す参照はエラーになる', () => {
+    for (const reference of [
+      '',
+      'http://example.com/schema.yaml',
+      'C:\\schemas\\test.yaml',
+      'D:/schemas/test.yaml',
+      '../../schema.yaml',
+      '/schemas/',
+    ]) {
+      expect(() => normalizeSchemaPath(reference, '/notes/today.md')).toThrow(
+        'スキーマを解決できません'
+      );
+    }
   });
 });
 
@@ -129,16 +139,34 @@ describe('fetchSchema', () => {
     expect(schema).toContain('PARENT DIRECTORY');
   });
 
-  test('絶対パスを指定するとエラーになる', async () => {
-    await expect(fetchSchema('/schemas/test.yaml')).rejects.toThrow(
-      '絶対パスでのスキーマ参照はサポートされていません'
+  test('/ で始まるパスのスキーマをワークスペースルートから取得できる', async () => {
+    server.use(
+      http.get('/schemas/root.yaml', () => {
+        return HttpResponse.text('WORKSPACE ROOT');
+      })
     );
-    await expect(fetchSchema('C:\\schemas\\test.yaml')).rejects.toThrow(
-      '絶対パスでのスキーマ参照はサポートされていません'
+
+    const schema = await fetchSchema('/schemas/root.yaml', '/custom/path/file.md');
+    expect(schema).toContain('WORKSPACE ROOT');
+  });
+
+  test('ベースパスを省略するとサンプルノートの位置から解決する', async () => {
+    server.use(
+      http.get('/sample/default-base.yaml', () => {
+        return HttpResponse.text('DEFAULT BASE');
+      })
     );
+
+    const schema = await fetchSchema('default-base.yaml');
+    expect(schema).toContain('DEFAULT BASE');
+  });
+
+  test('解決できないパスを指定するとエラーになる', async () => {
+    await expect(fetchSchema('C:\\schemas\\test.yaml')).rejects.toThrow('スキーマを解決できません');
     await expect(fetchSchema('http://example.com/schema.yaml')).rejects.toThrow(
-      '絶対パスでのスキーマ参照はサポートされていません'
+      'スキーマを解決できません'
     );
+    await expect(fetchSchema('../../outside.yaml')).rejects.toThrow('スキーマを解決できません');
   });
 
   test('存在しないスキーマの場合はエラーが発生する', async () => {
@@ -9,116 +9,72 @@ const schemaCache: Record<string, { content: string; timestamp: number }> = {};
 /**キャッシュの有効期限（ミリ秒）*/
 const CACHE_TTL = 60000;
 
+/**ベースパス未指定時の参照元（サンプルノートの位置）*/
+const DEFAULT_BASE_PATH = '/sample/note.md';
+
 /**
- * 与えられたパスが絶対パスかどうかを判定する
+ * スキーマ参照を正規化したワークスペース内のパスに変換する
  *
- * @param {string} path - 判定するパス文字列
- * @returns {boolean} 絶対パスの場合true、相対パスの場合false
+ * @param {string} schemaPath - スキーマへの参照
+ * @param {string} basePath - 参照元のノートのパス
+ * @returns {string} `/` で始まる正規化したパス（例: `/schemas/note.yaml`）
+ * @throws {Error} 空の参照、URLやドライブ文字付きのパス、ワークスペースの外を指す参照の場合
  *
  * @description
- * 先頭が/(Unix系)、C:/やC:\（Windows系）で始まるパス、
- * http://やhttps://（URL）で始まるパスを絶対パスと判定する
+ * コアの `normalize_path` と同じ規則で解決する。
+ * `/` で始まる参照はワークスペースルートから、それ以外は参照元のディレクトリからの相対パスとして扱い、
+ * `\` は `/` として扱って `.` と `..` を取り除く。
  */
-export const isAbsolutePath = (path: string): boolean => {
-  // 文字列が空か null/undefined の場合は絶対パスではない
-  if (!path) return false;
+export const normalizeSchemaPath = (schemaPath: string, basePath: string): string => {
+  const reference = schemaPath.trim().replace(/\\/g, '/');
+  const fail = (reason: string) => new Error(`スキーマを解決できません: ${reference} (${reason})`);
 
-  // Unix系絶対パス（/で始まる）
-  if (path.startsWith('/')) return true;
+  if (!reference) throw fail('パスが空です');
+  if (reference.includes('://') || reference[1] === ':') {
+    throw fail('URLやドライブ文字付きのパスは参照できません');
+  }
 
-  // Windows系絶対パス（C:\やD:/など）
-  // ドライブ文字（A-Z）の後に「:」が続く場合
-  if (/^[A-Za-z]:\\?/.test(path)) return true;
+  const base = basePath.replace(/\\/g, '/');
+  const baseDir = reference.startsWith('/') ? '' : base.substring(0, base.lastIndexOf('/') + 1);
 
-  // URL（http://やhttps://など）
-  if (/^[a-z]+:\/\//.test(path)) return true;
+  const segments: string[] = [];
+  for (const segment of [...baseDir.split('/'), ...reference.split('/')]) {
+    if (segment === '' || segment === '.') continue;
+    if (segment === '..') {
+      if (segments.pop() === undefined) throw fail('ワークスペースの外を参照しています');
+      continue;
+    }
+    segments.push(segment);
+  }
+  if (segments.length === 0 || reference.endsWith('/')) throw fail('ファイル名がありません');
 
-  // 相対パス
-  return false;
+  return `/${segments.join('/')}`;
 };
 
 /**
  * スキーマパスからスキーマを取得
  *
  * @param {string} schemaPath - スキーマファイルへのパス
- * @param {string} basePath - 相対パス解決のためのベースパス（オプション）
+ * @param {string} basePath - 相対パス解決のためのベースパス（オプション、省略時はサンプルノートの位置）
  * @returns {Promise<string>} スキーマの内容
- * @throws {Error} スキーマファイルが見つからないか、読み込めない場合、または絶対パスが指定された場合
+ * @throws {Error} スキーマパスを解決できない場合、またはスキーマファイルが見つからないか読み込めない場合
  *
  * @description
- * 指定されたパスからスキーマを取得し、キャッシュに保存。
+ * 指定されたパスを `normalizeSchemaPath` で解決してスキーマを取得し、キャッシュに保存。
  * キャッシュ有効期限内であればキャッシュから返す。
- * 絶対パスは受け付けず、相対パスのみをサポートする。
  */
 export const fetchSchema = async (schemaPath: string, basePath?: string): Promise<string> => {
-  // 絶対パスのチェック
-  if (isAbsolutePath(schemaPath)) {
-    throw new Error(
-      `絶対パスでのスキーマ参照はサポートされていません: ${schemaPath}。相対パス（例: "./schema.yaml"）を使用してください。`
-    );
-  }
+  // スキーマパス解決（コアの normalize_path と同じ規則）
+  const resolvedPath = normalizeSchemaPath(schemaPath, basePath || DEFAULT_BASE_PATH);
 
   // キャッシュチェック（有効期限内）
   const now = Date.now();
-  const cacheKey = `${basePath || ''}:${schemaPath}`;
-  const cached = schemaCache[cacheKey];
+  const cached = schemaCache[resolvedPath];
 
   if (cached && now - cached.timestamp < CACHE_TTL) {
     return cached.content;
   }
 
-  // スキーマパス解決
-  let resolvedPath;
-
-  console.log('Schema path resolution:', { schemaPath, basePath });
-
-  // ブラウザの制約でbasePathがフルパスでない可能性が高いため
-  // 現在のスキーマで相対パスで参照できるようするための特別処理
-
-  try {
-    // テストモードでは別のパス解決を使用します
-    if (import.meta.env?.MODE === 'test') {
-      if (schemaPath.startsWith('./') && basePath) {
-        const baseDir = basePath.substring(0, basePath.lastIndexOf('/') + 1);
-        resolvedPath = baseDir + schemaPath.substring(2);
-      } else if (schemaPath.startsWith('../') && basePath) {
-        const baseDir = basePath.substring(0, basePath.lastIndexOf('/') + 1);
-        const parentDir = baseDir.substring(0, baseDir.slice(0, -1).lastIndexOf('/') + 1);
-        resolvedPath = parentDir + schemaPath.substring(3);
-      } else if (basePath) {
-        const baseDir = basePath.substring(0, basePath.lastIndexOf('/') + 1);
-        resolvedPath = baseDir + schemaPath;
-      } else {
-        resolvedPath = `./${schemaPath}`;
-      }
-    } else {
-      // ブラウザ環境（通常モード）
-      // 明示的な相対パスの場合（./で始まる）
-      if (schemaPath.startsWith('./')) {
-        // ./schema.yaml → /sample/schema.yaml のようにサンプルディレクトリからの相対パスとして解決
-        resolvedPath = `/sample/${schemaPath.substring(2)}`;
-        console.log('Resolved explicit relative path:', resolvedPath);
-      }
-      // 親ディレクトリ参照の相対パス（../で始まる）
-      else if (schemaPath.startsWith('../')) {
-        // ../schema.yaml → /schema.yaml のようにプロジェクトルートからの相対パスとして解決
-        resolvedPath = `/${schemaPath.substring(3)}`;
-        console.log('Resolved parent directory path:', resolvedPath);
-      }
-      // 暗黙的な相対パス（./なしで始まる）
-      else {
-        // schema.yaml → /sample/schema.yaml のようにサンプルディレクトリからの相対パスとして解決
-        resolvedPath = `/sample/${schemaPath}`;
-        console.log('Resolved implicit relative path:', resolvedPath);
-      }
-    }
-  } catch (error) {
-    console.error('Error resolving schema path:', error);
-    throw new Error(
-      `スキーマパスの解決に失敗しました: ${error instanceof Error ? error.message : String(error)}`
-    );
-  }
-
   try {
     // スキーマファイルの取得
     const response = await fetch(resolvedPath);
@@ -130,7 +86,7 @@ export const fetchSchema = async (schemaPath: string, basePath?: string): Promis
     const schemaContent = await response.text();
 
     // キャッシュに保存
-    schemaCache[cacheKey] = {
+    schemaCache[resolvedPath] = {
       content: schemaContent,
       timestamp: now,
     };
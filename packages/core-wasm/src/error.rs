@@ -3,6 +3,7 @@ use thiserror::Error;
 use wasm_bindgen::prelude::*;
 
 use crate::error_code::ErrorCode;
+use crate::yaml_span::SourceSpan;
 
 /// コアモジュールのエラー型
 ///
@@ -29,29 +30,126 @@ pub enum CoreError {
     #[error("Frontmatter parse error: {0}")]
     FrontmatterParseError(String),
 
+    #[error("Frontmatter parse error: フロントマターのパースに失敗しました: {source}")]
+    FrontmatterYamlError {
+        source: serde_yaml::Error,
+        /// フロントマター本文の開始位置（Markdown上の行番号とバイトオフセット）
+        line_offset: u32,
+        byte_offset: u32,
+    },
+
+    #[error("Frontmatter parse error: フロントマターのパースに失敗しました: {message}")]
+    FrontmatterSyntaxError {
+        message: String,
+        /// エラー位置（Markdown上の位置）
+        span: SourceSpan,
+    },
+
     #[error("Frontmatter validation error: {0}")]
     FrontmatterValidationError(String),
 }
 
+/// 診断の重大度
+///
+/// `Error` 以外はノートやスキーマを無効にしない（リントの警告や、スキーマの
+/// `x-severity` で格下げした検証エラーなど）
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// エラー（検証失敗）
+    #[default]
+    Error,
+    /// 警告（検証は成功）
+    Warning,
+    /// 情報
+    Info,
+    /// ヒント（エディタでの控えめな表示向け）
+    Hint,
+}
+
+/// 検証エラーの構造化されたパラメータ（失敗したキーワードに応じて一部だけ設定する）
+///
+/// # フィールド
+/// - `property`: 存在しない必須のプロパティ（`RequiredMissing`）
+/// - `expected`: 期待される型（`TypeMismatch`、例: `["string", "null"]`）
+/// - `allowed`: 許可された値（`EnumMismatch`、`const` の場合は1要素）
+/// - `limit`: 上限・下限（`TooShort` / `TooLong` / `OutOfRange`、`multipleOf` の場合は除数）
+/// - `pattern`: 正規表現（`PatternMismatch`）
+/// - `format`: 形式名（`FormatMismatch`）
+/// - `unexpected`: 許可されていないプロパティ（`AdditionalProperty`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unexpected: Option<Vec<String>>,
+}
+
 /// フロントエンドに返すエラー情報
 /// バリデーションやパース時のエラー情報
 ///
 /// # フィールド
 /// - `line`: エラー発生行番号（0の場合は特定不可）
+/// - `column`: エラー発生列番号（1始まり、0の場合は特定不可）
+/// - `end_line` / `end_column`: エラー範囲の終了位置（排他的、0の場合は特定不可）
+/// - `offset` / `end_offset`: エラー範囲のバイトオフセット
 /// - `message`: エラーメッセージ
 /// - `path`: エラー発生箇所のパス（YAML/JSON Pointer等）
 /// - `code`: エラー種別を表すコード
+/// - `severity`: 重大度（`error` / `warning` / `info` / `hint`、省略時は `error`）
+/// - `rule`: 診断を出したリントのルール名（リント以外はなし）
+/// - `params`: 検証エラーのパラメータ（期待される型、許可された値、上限・下限など。該当しない場合はなし）
+/// - `frontmatter`: フロントマターのフィールドの検証エラーかどうか（`code` はキーワードごとのコードになる）
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorInfo {
     #[wasm_bindgen(readonly)]
     pub line: u32,
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub column: u32,
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub end_line: u32,
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub end_column: u32,
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub offset: u32,
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub end_offset: u32,
     #[wasm_bindgen(getter_with_clone)]
     pub message: String,
     #[wasm_bindgen(getter_with_clone)]
     pub path: String,
     #[wasm_bindgen(readonly)]
     pub code: ErrorCode,
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub severity: Severity,
+    #[wasm_bindgen(getter_with_clone)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// JSON文字列でのみ返す（wasmのゲッターは生成しない、ErrorInfoを小さく保つためBox）
+    #[wasm_bindgen(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Box<ErrorParams>>,
+    #[wasm_bindgen(readonly)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub frontmatter: bool,
 }
 
 impl ErrorInfo {
@@ -64,23 +162,107 @@ impl ErrorInfo {
     ) -> Self {
         Self {
             line,
+            column: 0,
+            end_line: 0,
+            end_column: 0,
+            offset: 0,
+            end_offset: 0,
             message: message.into(),
             path: path.into(),
             code,
+            severity: Severity::Error,
+            rule: None,
+            params: None,
+            frontmatter: false,
+        }
+    }
+
+    /// 重大度を設定する
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// 検証エラーのパラメータを設定する
+    pub fn with_params(mut self, params: ErrorParams) -> Self {
+        self.params = Some(Box::new(params));
+        self
+    }
+
+    /// エラー（`Severity::Error`）かどうか
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// エラー位置をソース上の範囲で設定する
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        self.line = span.start_line;
+        self.column = span.start_column;
+        self.end_line = span.end_line;
+        self.end_column = span.end_column;
+        self.offset = span.start_offset;
+        self.end_offset = span.end_offset;
+        self
+    }
+
+    /// 現在のエラー位置をSourceSpanとして返す
+    pub fn span(&self) -> SourceSpan {
+        SourceSpan {
+            start_line: self.line,
+            start_column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            start_offset: self.offset,
+            end_offset: self.end_offset,
         }
     }
 
     /// serde_yaml::ErrorからErrorInfoを生成
+    ///
+    /// serde_yamlはエラー位置を1点でしか返さないため、終了位置は開始位置と同じになる
     pub fn from_yaml_error(error: &serde_yaml::Error, code: ErrorCode) -> Self {
-        let line = match error.location() {
-            Some(location) => location.line() as u32,
-            None => 0,
-        };
-        Self {
-            line,
-            message: error.to_string(),
-            path: "".to_string(),
-            code,
+        let info = Self::new(0, error.to_string(), "", code);
+        match error.location() {
+            Some(location) => info.with_span(SourceSpan {
+                start_line: location.line() as u32,
+                start_column: location.column() as u32,
+                end_line: location.line() as u32,
+                end_column: location.column() as u32,
+                start_offset: location.index() as u32,
+                end_offset: location.index() as u32,
+            }),
+            None => info,
+        }
+    }
+
+    /// CoreErrorからErrorInfoを生成
+    ///
+    /// 位置情報を持つエラー（YAMLパースエラーなど）は行・列も設定する
+    pub fn from_core_error(error: &CoreError, code: ErrorCode) -> Self {
+        match error {
+            CoreError::YamlParseError(e) => Self::from_yaml_error(e, code),
+            CoreError::FrontmatterYamlError {
+                source,
+                line_offset,
+                byte_offset,
+            } => {
+                let info = Self::from_yaml_error(source, code);
+                let info = match source.location() {
+                    Some(_) => {
+                        let span = info.span().shifted(*line_offset, *byte_offset);
+                        info.with_span(span)
+                    }
+                    None => info,
+                };
+                Self {
+                    message: error.to_string(),
+                    ..info
+                }
+            }
+            CoreError::FrontmatterSyntaxError { span, .. } => {
+                Self::new(0, error.to_string(), "", code).with_span(*span)
+            }
+            _ => Self::new(0, error.to_string(), "", code),
         }
     }
 }
@@ -89,8 +271,8 @@ impl ErrorInfo {
 /// バリデーションの結果を表す構造体
 ///
 /// # フィールド
-/// - `success`: バリデーション成功時はtrue
-/// - `errors`: エラー情報の配列（成功時は空配列）
+/// - `success`: エラー（`Severity::Error`）の診断がなければtrue
+/// - `errors`: 診断の配列（成功時も警告などを含むことがある）
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -115,6 +297,17 @@ impl ValidationResult {
         }
     }
 
+    /// 診断の一覧から結果を生成する
+    ///
+    /// `success` はエラー（`Severity::Error`）の診断がない場合にtrueとなり、
+    /// 警告・情報・ヒントだけの場合は成功として扱う
+    pub fn from_diagnostics(errors: Vec<ErrorInfo>) -> Self {
+        Self {
+            success: !errors.iter().any(ErrorInfo::is_error),
+            errors,
+        }
+    }
+
     pub fn single_error(error: ErrorInfo) -> Self {
         Self {
             success: false,
@@ -4,6 +4,11 @@ use wasm_bindgen::prelude::*;
 /// バリデーションエラー種別を表すコード
 ///
 /// JS側でも利用できるよう `wasm_bindgen` で公開する
+///
+/// スキーマ検証エラーは、失敗したキーワードごとのコード（`RequiredMissing` など）で報告し、
+/// 対応するコードがないキーワード（`anyOf` / `oneOf` / `not` など）は `SchemaValidation` とする。
+/// フロントマターのフィールドの検証エラーもキーワードごとのコードで報告し（`ErrorInfo::frontmatter` で区別する）、
+/// 対応するコードがないキーワードは `FrontmatterValidation` とする
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ErrorCode {
@@ -19,4 +24,34 @@ pub enum ErrorCode {
     SchemaValidation,
     /// 未分類のエラー
     Unknown,
+    /// パッチ適用エラー
+    PatchApply,
+    /// 見出しの重複（見出しテキストをキーとするセクション出力時）
+    DuplicateHeading,
+    /// スキーマ参照の解決エラー（見つからない・不正なパス・循環参照）
+    SchemaResolve,
+    /// スキーマのリント（`compile_schema` の警告など、重大度はルールの設定による）
+    SchemaLint,
+    /// 必須のプロパティがない（`required`）
+    RequiredMissing,
+    /// 型が異なる（`type`）
+    TypeMismatch,
+    /// 許可された値のいずれでもない（`enum` / `const`）
+    EnumMismatch,
+    /// 正規表現に一致しない（`pattern`）
+    PatternMismatch,
+    /// 文字列・配列・オブジェクトが短すぎる（`minLength` / `minItems` / `minProperties`）
+    TooShort,
+    /// 文字列・配列・オブジェクトが長すぎる（`maxLength` / `maxItems` / `maxProperties` / `additionalItems`）
+    TooLong,
+    /// 数値が範囲外（`minimum` / `maximum` / `exclusiveMinimum` / `exclusiveMaximum` / `multipleOf`）
+    OutOfRange,
+    /// 許可されていないプロパティ（`additionalProperties` / `unevaluatedProperties`）
+    AdditionalProperty,
+    /// 形式が異なる（`format`）
+    FormatMismatch,
+    /// キーの重複（表の列名など、Markdownから生成するオブジェクトのキー）
+    DuplicateKey,
+    /// 変換オプションの値が不正（見出しマッピング設定の誤りなど。YAMLとしての構文エラーは `YamlParse`）
+    InvalidOptions,
 }
@@ -1,33 +1,519 @@
 //! frontmatter.rs
 //!
 //! Markdownドキュメントのフロントマター部分を解析・検証するモジュール。
-//! - フロントマターの抽出と解析
+//! - フロントマターの検出（文書先頭の `---` 〜 `---` / `...`、BOM可）と解析
+//!   - YAML（`---`）/ TOML（`+++`、Hugo・Zola形式）/ JSON（`{` 〜 `}`）を同じモデルで扱う
+//!   - 形式間の変換（`convert_frontmatter`）とフィールドの書き換え（`set_frontmatter_field`）
 //! - フロントマター構文の検証
 //! - スキーマパスとバリデーションフラグの管理
+//! - スキーマの `frontmatter` セクション（または `frontmatter_schema_path` のスキーマ）によるフィールドの検証
+//! - その他のフィールド（`tags` や `title` など）の保持と型付きの取得
 
 use crate::error::{CoreError, ErrorInfo, ValidationResult};
 use crate::error_code::ErrorCode;
+use crate::validate;
+use crate::yaml_span::{escape_pointer_segment, SourceSpan, SpanMap};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::ops::Range;
+
+/// UTF-8のBOM
+const BOM: char = '\u{feff}';
+
+/// フロントマターの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontmatterFormat {
+    /// `---` で囲んだYAML
+    #[default]
+    Yaml,
+    /// `+++` で囲んだTOML
+    Toml,
+    /// `{` 〜 `}` のJSONオブジェクト
+    Json,
+}
+
+impl FrontmatterFormat {
+    /// 形式名（`yaml` / `toml` / `json`）から変換する
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Markdown上のフロントマターの位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FrontmatterBlock {
+    pub format: FrontmatterFormat,
+    /// 本文のバイト範囲（YAML/TOMLは区切り行と最後の改行を除き、JSONは `{` 〜 `}` を含む）
+    pub body: Range<usize>,
+    /// YAML本文の開始行のインデックス（0始まり）
+    pub body_line: usize,
+    /// 閉じ区切り行（改行を含む）の終端オフセット。フロントマターを除いた本文はここから始まる
+    pub end: usize,
+}
+
+/// 文書先頭のフロントマターを検出する
+///
+/// 1行目（BOMは無視）が開始行で、以降に閉じ行がある場合のみフロントマターとみなす。
+/// 文書の途中にある `---`（水平線やセットext見出しの下線）は対象外。
+/// - YAML: `---` 〜 `---` または `...`
+/// - TOML: `+++` 〜 `+++`
+/// - JSON: `{`（直後が空または `"`）〜 行頭の `}`
+///
+/// # 戻り値
+/// * フロントマターの位置（フロントマターがない、または閉じられていない場合はNone）
+pub(crate) fn locate_frontmatter(md: &str) -> Option<FrontmatterBlock> {
+    let mut lines = md.split_inclusive('\n');
+    let opener = lines.next()?;
+    let bom = if opener.starts_with(BOM) {
+        BOM.len_utf8()
+    } else {
+        0
+    };
+    let first = opener[bom..].trim_end();
+    let format = match first {
+        "---" => FrontmatterFormat::Yaml,
+        "+++" => FrontmatterFormat::Toml,
+        _ if first.strip_prefix('{').is_some_and(|rest| {
+            rest.trim_start().is_empty() || rest.trim_start().starts_with('"')
+        }) =>
+        {
+            FrontmatterFormat::Json
+        }
+        _ => return None,
+    };
+
+    let mut offset = opener.len();
+    for line in lines {
+        let closer = line.trim_end();
+        let closed = match format {
+            FrontmatterFormat::Yaml => matches!(closer, "---" | "..."),
+            FrontmatterFormat::Toml => closer == "+++",
+            FrontmatterFormat::Json => closer == "}",
+        };
+        if closed {
+            let end = offset + line.len();
+            return Some(match format {
+                FrontmatterFormat::Json => FrontmatterBlock {
+                    format,
+                    body: bom..offset + closer.len(),
+                    body_line: 0,
+                    end,
+                },
+                _ => {
+                    // 閉じ行の直前の改行は本文に含めない
+                    let body_end = md[..offset]
+                        .strip_suffix('\n')
+                        .map_or(offset, |body| body.strip_suffix('\r').unwrap_or(body).len())
+                        .max(opener.len());
+                    FrontmatterBlock {
+                        format,
+                        body: opener.len()..body_end,
+                        body_line: 1,
+                        end,
+                    }
+                }
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// フロントマター本文を形式に応じて解析する
+///
+/// # エラー
+/// - 構文エラー: FrontmatterYamlError / FrontmatterSyntaxError（位置はMarkdown上の位置）
+pub(crate) fn parse_fields(md: &str, block: &FrontmatterBlock) -> Result<Value, CoreError> {
+    let body = &md[block.body.clone()];
+    match block.format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str(body).map_err(|source| {
+            match source.location() {
+                // 本文の末尾で検出したエラー（閉じていない括弧など）は閉じ行ではなく本文の最終行を指す
+                Some(location) if location.index() >= body.len() => {
+                    CoreError::FrontmatterSyntaxError {
+                        message: source.to_string(),
+                        span: SourceSpan::from_offsets(md, block.body.end, block.body.end),
+                    }
+                }
+                _ => CoreError::FrontmatterYamlError {
+                    source,
+                    line_offset: block.body_line as u32,
+                    byte_offset: block.body.start as u32,
+                },
+            }
+        }),
+        FrontmatterFormat::Toml => match toml::from_str::<toml::Table>(body) {
+            Ok(table) => Ok(toml_to_yaml(toml::Value::Table(table))),
+            Err(e) => {
+                let range = e.span().unwrap_or(0..0);
+                Err(CoreError::FrontmatterSyntaxError {
+                    message: e.message().trim().to_string(),
+                    span: SourceSpan::from_offsets(
+                        md,
+                        block.body.start + range.start,
+                        block.body.start + range.end,
+                    ),
+                })
+            }
+        },
+        FrontmatterFormat::Json => match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(value) => Ok(serde_yaml::to_value(value).unwrap_or_default()),
+            Err(e) => {
+                // JSONは開始行から本文なので、行番号はMarkdown上の行番号と一致する
+                let line_start: usize = body
+                    .split_inclusive('\n')
+                    .take(e.line().saturating_sub(1))
+                    .map(str::len)
+                    .sum();
+                let column = body[line_start..]
+                    .char_indices()
+                    .nth(e.column().saturating_sub(1))
+                    .map_or(body.len() - line_start, |(i, _)| i);
+                let offset = block.body.start + line_start + column;
+                Err(CoreError::FrontmatterSyntaxError {
+                    message: e.to_string(),
+                    span: SourceSpan::from_offsets(md, offset, offset),
+                })
+            }
+        },
+    }
+}
+
+/// TOMLの値をYAMLの値に変換する（日時は文字列になる）
+fn toml_to_yaml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Value::Number(f.into()),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.into_iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .into_iter()
+                .map(|(key, value)| (Value::String(key), toml_to_yaml(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// フロントマターのトップレベルのフィールドの位置
+pub(crate) enum FieldSpans {
+    /// YAML / JSON（JSONはYAMLのフロースタイルとして解析する）
+    Yaml(SpanMap),
+    /// TOML（フィールド名、キーの位置、値の位置）
+    Toml(Vec<(String, SourceSpan, SourceSpan)>),
+}
+
+impl FieldSpans {
+    /// フロントマター本文からフィールドの位置を求める（位置は本文に対する相対位置）
+    pub(crate) fn parse(format: FrontmatterFormat, body: &str) -> FieldSpans {
+        match format {
+            FrontmatterFormat::Toml => FieldSpans::Toml(
+                toml_field_ranges(body)
+                    .into_iter()
+                    .map(|(key, key_range, value_range)| {
+                        (
+                            key,
+                            SourceSpan::from_offsets(body, key_range.start, key_range.end),
+                            SourceSpan::from_offsets(body, value_range.start, value_range.end),
+                        )
+                    })
+                    .collect(),
+            ),
+            _ => FieldSpans::Yaml(SpanMap::parse(body).unwrap_or_default()),
+        }
+    }
+
+    /// フィールドのキーの位置
+    pub(crate) fn key(&self, key: &str) -> Option<SourceSpan> {
+        match self {
+            FieldSpans::Yaml(spans) => spans.key(&format!("/{}", escape_pointer_segment(key))),
+            FieldSpans::Toml(fields) => fields
+                .iter()
+                .find(|(k, ..)| k == key)
+                .map(|(_, span, _)| *span),
+        }
+    }
+
+    /// フィールドの値の位置
+    pub(crate) fn value(&self, key: &str) -> Option<SourceSpan> {
+        match self {
+            FieldSpans::Yaml(spans) => spans.node(&format!("/{}", escape_pointer_segment(key))),
+            FieldSpans::Toml(fields) => fields
+                .iter()
+                .find(|(k, ..)| k == key)
+                .map(|(.., span)| *span),
+        }
+    }
+
+    /// JSON Pointerが指すノードの位置（見つからない場合は最も近い祖先）
+    ///
+    /// `prefer_key` がtrueの場合はキーの位置を優先する。TOMLはトップレベルのフィールド単位でのみ求める
+    pub(crate) fn locate(&self, pointer: &str, prefer_key: bool) -> Option<SourceSpan> {
+        match self {
+            FieldSpans::Yaml(spans) => {
+                if prefer_key { spans.key(pointer) } else { None }.or_else(|| spans.locate(pointer))
+            }
+            FieldSpans::Toml(_) => {
+                let segment = pointer
+                    .split('/')
+                    .nth(1)?
+                    .replace("~1", "/")
+                    .replace("~0", "~");
+                let is_field = pointer.matches('/').count() == 1;
+                if prefer_key && is_field {
+                    self.key(&segment)
+                } else {
+                    None
+                }
+                .or_else(|| self.value(&segment))
+            }
+        }
+    }
+}
+
+/// TOMLのトップレベルのフィールドの位置（フィールド名、キーのバイト範囲、値のバイト範囲）を求める
+///
+/// 位置は `toml_edit` の解析結果から求める。テーブル（`[a]` / `[[a]]`）はヘッダーから最後のフィールドまで、
+/// ドット区切りのキー（`a.b = 1`）などで暗黙に作られたテーブルは含まれる値の全体を値の範囲とする。
+/// 構文エラーの場合は空（エラーは `parse_fields` で報告する）
+fn toml_field_ranges(src: &str) -> Vec<(String, Range<usize>, Range<usize>)> {
+    let Ok(document) = toml_edit::ImDocument::parse(src) else {
+        return Vec::new();
+    };
+    let table = document.as_table();
+    table
+        .iter()
+        .filter_map(|(name, _)| {
+            let (key, item) = table.get_key_value(name)?;
+            Some((name.to_string(), key.span()?, toml_item_range(item)?))
+        })
+        .collect()
+}
+
+/// TOMLの値のバイト範囲（位置を持たない暗黙のテーブルは、含まれる値の範囲をまとめたもの）
+fn toml_item_range(item: &toml_edit::Item) -> Option<Range<usize>> {
+    if let Some(span) = item.span() {
+        return Some(span);
+    }
+    item.as_table_like()?
+        .iter()
+        .filter_map(|(_, child)| toml_item_range(child))
+        .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+}
 
 /// フロントマターの構造体
 ///
 /// # フィールド
 /// - `schema_path`: スキーマファイルへのパス（オプション）
+/// - `frontmatter_schema_path`: フロントマター専用のスキーマファイルへのパス（オプション）
 /// - `validated`: バリデーションフラグ（デフォルトはtrue）
+/// - `extra`: 上記以外のフィールド（記述順を保持）
+/// - `format`: フロントマターの形式（内部利用のみ）
 /// - `raw`: 元のフロントマター文字列（内部利用のみ）
+/// - `line_offset` / `byte_offset`: Markdown上での`raw`の開始位置（内部利用のみ）
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Frontmatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frontmatter_schema_path: Option<String>,
     #[serde(default = "default_validated")]
     pub validated: bool,
+    #[serde(flatten)]
+    pub extra: Mapping,
+    #[serde(skip)]
+    pub format: FrontmatterFormat,
     #[serde(skip)]
     pub raw: String,
+    #[serde(skip)]
+    pub line_offset: u32,
+    #[serde(skip)]
+    pub byte_offset: u32,
 }
 
+/// ノートの設定のキー（スキーマに書かなくても `additionalProperties: false` などのエラーにならない）
+const SETTINGS_KEYS: [&str; 3] = ["schema_path", "frontmatter_schema_path", "validated"];
+
 fn default_validated() -> bool {
     true
 }
 
+impl Frontmatter {
+    /// 解析済みのフィールドから構築する（`schema_path` などの既知のフィールドの型を検査する）
+    ///
+    /// # 戻り値
+    /// * 失敗時: 型が不正なフィールド名（全体がマッピングでない場合は空文字列）とエラーメッセージ
+    fn from_fields(fields: Value) -> Result<Self, (&'static str, &'static str)> {
+        let mut extra = match fields {
+            Value::Null => Mapping::new(),
+            Value::Mapping(mapping) => mapping,
+            _ => {
+                return Err((
+                    "",
+                    "フロントマターはマッピング（キーと値の組）である必要があります",
+                ))
+            }
+        };
+        let schema_path = match extra.shift_remove("schema_path") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(path)) => Some(path),
+            Some(_) => return Err(("schema_path", "schema_pathは文字列である必要があります")),
+        };
+        let frontmatter_schema_path = match extra.shift_remove("frontmatter_schema_path") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(path)) => Some(path),
+            Some(_) => {
+                return Err((
+                    "frontmatter_schema_path",
+                    "frontmatter_schema_pathは文字列である必要があります",
+                ))
+            }
+        };
+        let validated = match extra.shift_remove("validated") {
+            None => default_validated(),
+            Some(Value::Bool(validated)) => validated,
+            Some(_) => return Err(("validated", "validatedは真偽値である必要があります")),
+        };
+        Ok(Self {
+            schema_path,
+            frontmatter_schema_path,
+            validated,
+            extra,
+            format: FrontmatterFormat::default(),
+            raw: String::new(),
+            line_offset: 0,
+            byte_offset: 0,
+        })
+    }
+
+    /// フィールドの位置を求める（位置は `raw` に対する相対位置）
+    pub(crate) fn field_spans(&self) -> FieldSpans {
+        FieldSpans::parse(self.format, &self.raw)
+    }
+
+    /// `schema_path` / `frontmatter_schema_path` / `validated` 以外のフィールドの値を返す
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.extra.get(key)
+    }
+
+    /// 文字列のフィールドを返す（文字列でない場合はNone）
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+
+    /// 真偽値のフィールドを返す（真偽値でない場合はNone）
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key)?.as_bool()
+    }
+
+    /// 整数のフィールドを返す（整数でない場合はNone）
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key)?.as_i64()
+    }
+
+    /// 文字列の配列のフィールドを返す
+    ///
+    /// 単一の文字列は要素1つの配列として扱い、文字列以外の要素は無視する
+    pub fn get_str_list(&self, key: &str) -> Option<Vec<&str>> {
+        match self.get(key)? {
+            Value::String(s) => Some(vec![s.as_str()]),
+            Value::Sequence(items) => Some(items.iter().filter_map(Value::as_str).collect()),
+            _ => None,
+        }
+    }
+
+    /// フィールドを任意の型に変換して返す
+    ///
+    /// # 戻り値
+    /// * フィールドがない場合: None
+    /// * 変換できない場合: `Some(Err(..))`
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<Result<T, serde_yaml::Error>> {
+        self.get(key).map(|v| serde_yaml::from_value(v.clone()))
+    }
+
+    /// `title` フィールド
+    pub fn title(&self) -> Option<&str> {
+        self.get_str("title")
+    }
+
+    /// `tags` フィールド（ない場合は空配列）
+    pub fn tags(&self) -> Vec<&str> {
+        self.get_str_list("tags").unwrap_or_default()
+    }
+
+    /// `created_at` フィールド（日付・日時の文字列）
+    pub fn created_at(&self) -> Option<&str> {
+        self.get_str("created_at")
+    }
+}
+
+/// フロントマターの解析・検証結果
+///
+/// # フィールド
+/// - `success`: 解析・検証が成功した場合はtrue
+/// - `errors`: エラー情報の配列（成功時は空配列）
+/// - `frontmatter`: 解析したフロントマターの全フィールド（解析できなかった場合はnull）
+/// - `format`: フロントマターの形式（`yaml` / `toml` / `json`、見つからない場合はnull）
+#[derive(Debug, Serialize)]
+pub struct FrontmatterResult {
+    pub success: bool,
+    pub errors: Vec<ErrorInfo>,
+    pub frontmatter: Option<Frontmatter>,
+    pub format: Option<FrontmatterFormat>,
+}
+
+impl FrontmatterResult {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            r#"{"success":false,"errors":[{"line":0,"message":"Failed to serialize errors","path":""}],"frontmatter":null,"format":null}"#.to_string()
+        })
+    }
+}
+
+/// フロントマターを解析・検証し、結果とフィールドをまとめて返す
+///
+/// # 引数
+/// * `md_str` - フロントマターを含むMarkdown文字列
+/// * `schema_str` - フィールドの検証に使うスキーマ（YAML/JSON形式、任意）
+///   - `frontmatter_schema_path` がある場合: そのスキーマ（スキーマ全体をフロントマターに適用）
+///   - それ以外: `schema_path` のスキーマ（`frontmatter` セクションがある場合のみ適用）
+///
+/// # 備考
+/// - `validated: false` の場合はスキーマによる検証を行わない
+pub fn parse_and_validate(md_str: &str, schema_str: Option<&str>) -> FrontmatterResult {
+    match parse_frontmatter(md_str) {
+        Ok(frontmatter) => {
+            let mut result = validate_frontmatter(&frontmatter);
+            if let Some(schema_str) = schema_str.filter(|_| frontmatter.validated) {
+                let fields = validate_fields(md_str, &frontmatter, schema_str);
+                result.success &= fields.success;
+                result.errors.extend(fields.errors);
+            }
+            FrontmatterResult {
+                success: result.success,
+                errors: result.errors,
+                format: Some(frontmatter.format),
+                frontmatter: Some(frontmatter),
+            }
+        }
+        Err(e) => FrontmatterResult {
+            success: false,
+            errors: vec![ErrorInfo::from_core_error(&e, ErrorCode::FrontmatterParse)],
+            frontmatter: None,
+            format: locate_frontmatter(md_str).map(|block| block.format),
+        },
+    }
+}
+
 /// Markdownからフロントマターを抽出して解析する
 ///
 /// # 引数
@@ -38,49 +524,235 @@ fn default_validated() -> bool {
 /// * 失敗時: 適切なエラー（フロントマターがない場合や解析エラー等）
 ///
 /// # エラー
-/// - フロントマターが存在しない場合: FrontmatterParseError
+/// - フロントマターが存在しない場合（文書先頭にない場合を含む）: FrontmatterParseError
 /// - フロントマターのYAMLパースに失敗した場合: FrontmatterParseError
 pub fn parse_frontmatter(md_str: &str) -> Result<Frontmatter, CoreError> {
-    // フロントマターの境界を検出
-    let fm_pattern = "---";
-    let lines: Vec<&str> = md_str.lines().collect();
-
-    // フロントマターの開始と終了位置を検索
-    let mut start_idx = None;
-    let mut end_idx = None;
-
-    for (i, line) in lines.iter().enumerate() {
-        if line.trim() == fm_pattern {
-            if start_idx.is_none() {
-                start_idx = Some(i);
-            } else if end_idx.is_none() {
-                end_idx = Some(i);
-                break;
-            }
+    let block = locate_frontmatter(md_str).ok_or_else(|| {
+        CoreError::FrontmatterParseError("フロントマターが見つからないか不完全です".to_string())
+    })?;
+
+    // フロントマター内容を抽出（Markdown上での開始位置も保持する）
+    let fm_content = &md_str[block.body.clone()];
+    let line_offset = block.body_line as u32;
+    let byte_offset = block.body.start as u32;
+
+    // 形式に応じてパースし、既知のフィールドの型を検査する
+    let fields = parse_fields(md_str, &block)?;
+    match Frontmatter::from_fields(fields) {
+        Ok(frontmatter) => Ok(Frontmatter {
+            format: block.format,
+            raw: fm_content.to_string(),
+            line_offset,
+            byte_offset,
+            ..frontmatter
+        }),
+        Err((key, message)) => {
+            let spans = FieldSpans::parse(block.format, fm_content);
+            let span = spans
+                .value(key)
+                .or_else(|| spans.key(key))
+                .map(|span| span.shifted(line_offset, byte_offset))
+                .unwrap_or_else(|| {
+                    SourceSpan::from_offsets(md_str, block.body.start, block.body.start)
+                });
+            Err(CoreError::FrontmatterSyntaxError {
+                message: message.to_string(),
+                span,
+            })
         }
     }
+}
 
-    // フロントマターがない、または不完全な場合
-    if start_idx.is_none() || end_idx.is_none() {
-        return Err(CoreError::FrontmatterParseError(
-            "フロントマターが見つからないか不完全です".to_string()));
-    }
+/// フロントマターのフィールドをスキーマで検証する
+///
+/// エラーの `path` はフロントマター内のJSON Pointer、位置はMarkdown上のフロントマター内の位置
+fn validate_fields(md_str: &str, frontmatter: &Frontmatter, schema_str: &str) -> ValidationResult {
+    let compiled = match validate::compile(schema_str) {
+        Ok(compiled) => compiled,
+        Err(result) => return result,
+    };
+    let schema = if frontmatter.frontmatter_schema_path.is_some() {
+        compiled.schema
+    } else {
+        match frontmatter_section(&compiled.schema) {
+            Some(section) => section,
+            None => return ValidationResult::success(),
+        }
+    };
+    let compiled = match validate::compile_value(allow_settings_keys(schema)) {
+        Ok(compiled) => compiled,
+        Err(result) => return result,
+    };
 
-    // フロントマター内容を抽出
-    let fm_content = lines[(start_idx.unwrap() + 1)..end_idx.unwrap()]
-        .join("\n");
+    // ノートの設定（`schema_path` など）を含む、書かれたとおりのフィールドを検証する
+    let fields = locate_frontmatter(md_str)
+        .and_then(|block| read_mapping(md_str, &block).ok())
+        .map(|mapping| serde_json::to_value(mapping).unwrap_or_default())
+        .unwrap_or_default();
 
-    // YAMLとしてパース
-    match serde_yaml::from_str::<Frontmatter>(&fm_content) {
-        Ok(mut frontmatter) => {
-            frontmatter.raw = fm_content;
-            Ok(frontmatter)
+    // 位置を特定できないエラー（TOMLのフロントマター全体に対するエラーなど）は本文の先頭を指す
+    let spans = frontmatter.field_spans();
+    let start = frontmatter.byte_offset as usize;
+    validate::validate_value_with(
+        &fields,
+        &compiled,
+        ErrorCode::FrontmatterValidation,
+        |path, is_collection| {
+            spans
+                .locate(path, is_collection)
+                .map(|span| span.shifted(frontmatter.line_offset, frontmatter.byte_offset))
+                .or_else(|| Some(SourceSpan::from_offsets(md_str, start, start)))
         },
-        Err(e) => {
-            Err(CoreError::FrontmatterParseError(
-                format!("フロントマターのパースに失敗しました: {}", e)))
+    )
+}
+
+/// ノートの設定のキーを、スキーマに定義がなくても許可する
+///
+/// ルートの `properties` に定義のない設定のキーを制約なし（`{}`）で追加する。
+/// `required` や定義済みの制約はスキーマのとおりに適用し、`additionalProperties: false` などのエラーにだけならない
+fn allow_settings_keys(mut schema: serde_json::Value) -> serde_json::Value {
+    if let Some(object) = schema.as_object_mut() {
+        let properties = object
+            .entry("properties")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(properties) = properties.as_object_mut() {
+            for key in SETTINGS_KEYS {
+                properties
+                    .entry(key)
+                    .or_insert_with(|| serde_json::json!({}));
+            }
         }
     }
+    schema
+}
+
+/// スキーマの `frontmatter` セクションを取り出す
+///
+/// ルートの `$schema`（draftの選択）と `definitions` / `$defs`（`$ref` の参照先）はセクションにも引き継ぐ
+fn frontmatter_section(schema: &serde_json::Value) -> Option<serde_json::Value> {
+    let mut section = schema.get("frontmatter")?.clone();
+    if let Some(section) = section.as_object_mut() {
+        for key in ["$schema", "definitions", "$defs"] {
+            if let Some(definitions) = schema.get(key) {
+                section.entry(key).or_insert_with(|| definitions.clone());
+            }
+        }
+    }
+    Some(section)
+}
+
+/// フロントマターを別の形式に書き換える
+///
+/// # 引数
+/// * `md_str` - フロントマターを含むMarkdown文字列
+/// * `to` - 変換先の形式
+///
+/// # 戻り値
+/// * 成功時: フロントマターを書き換えたMarkdown（フロントマターがない場合や同じ形式の場合はそのまま）
+/// * 失敗時: エラー情報
+///
+/// # エラーケース
+/// - フロントマターの解析エラー
+/// - フロントマターがマッピングでない場合
+/// - 変換先の形式で表現できない値（TOMLのnullなど）
+pub fn convert_frontmatter(md_str: &str, to: FrontmatterFormat) -> Result<String, ErrorInfo> {
+    let Some(block) = locate_frontmatter(md_str) else {
+        return Ok(md_str.to_string());
+    };
+    if block.format == to {
+        return Ok(md_str.to_string());
+    }
+
+    let fields = read_mapping(md_str, &block)?;
+    render_frontmatter(md_str, &block, &fields, to)
+}
+
+/// フロントマターのフィールドを1つ書き換える（ない場合は末尾に追加する）
+///
+/// フロントマターは元の形式のまま書き直す（コメントや書式は保持されない）
+///
+/// # 引数
+/// * `md_str` - フロントマターを含むMarkdown文字列
+/// * `key` - フィールド名
+/// * `value` - 新しい値
+///
+/// # 戻り値
+/// * 成功時: フロントマターを書き換えたMarkdown
+/// * 失敗時: エラー情報
+///
+/// # エラーケース
+/// - フロントマターがない、または解析エラー
+/// - フロントマターがマッピングでない場合
+/// - 形式で表現できない値（TOMLのnullなど）
+pub fn set_frontmatter_field(md_str: &str, key: &str, value: Value) -> Result<String, ErrorInfo> {
+    let Some(block) = locate_frontmatter(md_str) else {
+        return Err(ErrorInfo::new(
+            0,
+            "フロントマターが見つからないか不完全です",
+            "",
+            ErrorCode::FrontmatterParse,
+        ));
+    };
+    let mut fields = read_mapping(md_str, &block)?;
+    fields.insert(Value::String(key.to_string()), value);
+    render_frontmatter(md_str, &block, &fields, block.format)
+}
+
+/// フロントマターのフィールドをマッピングとして読む（空のフロントマターは空のマッピング）
+fn read_mapping(md_str: &str, block: &FrontmatterBlock) -> Result<Mapping, ErrorInfo> {
+    match parse_fields(md_str, block) {
+        Ok(Value::Null) => Ok(Mapping::new()),
+        Ok(Value::Mapping(mapping)) => Ok(mapping),
+        Ok(_) => Err(ErrorInfo::new(
+            block.body_line as u32 + 1,
+            "フロントマターはマッピング（キーと値の組）である必要があります",
+            "",
+            ErrorCode::FrontmatterParse,
+        )),
+        Err(e) => Err(ErrorInfo::from_core_error(&e, ErrorCode::FrontmatterParse)),
+    }
+}
+
+/// フィールドを指定の形式で書き出し、Markdownのフロントマター（`block`）を置き換える
+fn render_frontmatter(
+    md_str: &str,
+    block: &FrontmatterBlock,
+    fields: &Mapping,
+    to: FrontmatterFormat,
+) -> Result<String, ErrorInfo> {
+    let error = |message: String| {
+        ErrorInfo::new(
+            block.body_line as u32 + 1,
+            message,
+            "",
+            ErrorCode::FrontmatterParse,
+        )
+    };
+    let rendered = match to {
+        FrontmatterFormat::Yaml if fields.is_empty() => "---\n---\n".to_string(),
+        FrontmatterFormat::Yaml => serde_yaml::to_string(fields)
+            .map(|yaml| format!("---\n{}---\n", yaml))
+            .map_err(|e| error(e.to_string()))?,
+        FrontmatterFormat::Toml => toml::Value::try_from(fields)
+            .and_then(|value| toml::to_string(&value))
+            .map(|toml| format!("+++\n{}+++\n", toml))
+            .map_err(|e| error(format!("TOMLに変換できません: {}", e)))?,
+        FrontmatterFormat::Json => serde_json::to_string_pretty(fields)
+            .map(|json| format!("{}\n", json))
+            .map_err(|e| error(format!("JSONに変換できません: {}", e)))?,
+    };
+
+    let bom = if md_str.starts_with(BOM) {
+        BOM.len_utf8()
+    } else {
+        0
+    };
+    Ok(format!(
+        "{}{}{}",
+        &md_str[..bom],
+        rendered,
+        &md_str[block.end..]
+    ))
 }
 
 /// フロントマターの構文を検証する
@@ -94,20 +766,35 @@ pub fn parse_frontmatter(md_str: &str) -> Result<Frontmatter, CoreError> {
 ///   - 失敗時: success=false, errors=エラー情報の配列
 ///
 /// # 検証項目
-/// - schema_pathが存在して空でないこと
+/// - schema_path / frontmatter_schema_pathが存在して空でないこと
 /// - validated項目が不正な値でないこと（既にBool型ならパース時に検出）
 pub fn validate_frontmatter(frontmatter: &Frontmatter) -> ValidationResult {
     let mut errors = Vec::new();
 
-    // schema_pathの検証（存在する場合）
-    if let Some(path) = &frontmatter.schema_path {
-        if path.trim().is_empty() {
-            errors.push(ErrorInfo::new(
+    let spans = frontmatter.field_spans();
+
+    // schema_path / frontmatter_schema_pathの検証（存在する場合）
+    let paths = [
+        ("schema_path", &frontmatter.schema_path),
+        (
+            "frontmatter_schema_path",
+            &frontmatter.frontmatter_schema_path,
+        ),
+    ];
+    for (key, path) in paths {
+        if path.as_ref().is_some_and(|path| path.trim().is_empty()) {
+            let error = ErrorInfo::new(
                 0,
-                "schema_pathが空です".to_string(),
-                "schema_path".to_string(),
+                format!("{}が空です", key),
+                key.to_string(),
                 ErrorCode::FrontmatterValidation,
-            ));
+            );
+            errors.push(match spans.key(key) {
+                Some(span) => {
+                    error.with_span(span.shifted(frontmatter.line_offset, frontmatter.byte_offset))
+                }
+                None => error,
+            });
         }
     }
 
@@ -136,7 +823,7 @@ validated: true
 
         let fm = result.unwrap();
         assert_eq!(fm.schema_path, Some("./schemas/note.yaml".to_string()));
-        assert_eq!(fm.validated, true);
+        assert!(fm.validated);
     }
 
     #[test]
@@ -145,7 +832,7 @@ validated: true
         let result = parse_frontmatter(md);
         assert!(result.is_err());
         match result {
-            Err(CoreError::FrontmatterParseError(_)) => assert!(true),
+            Err(CoreError::FrontmatterParseError(_)) => {}
             _ => panic!("Expected FrontmatterParseError"),
         }
     }
@@ -154,8 +841,13 @@ validated: true
     fn test_validate_frontmatter_empty_schema_path() {
         let frontmatter = Frontmatter {
             schema_path: Some("".to_string()),
+            frontmatter_schema_path: None,
             validated: true,
+            extra: Mapping::new(),
+            format: FrontmatterFormat::Yaml,
             raw: "".to_string(),
+            line_offset: 0,
+            byte_offset: 0,
         };
 
         let result = validate_frontmatter(&frontmatter);
@@ -174,6 +866,416 @@ schema_path: ./schemas/note.yaml
         assert!(result.is_ok());
 
         let fm = result.unwrap();
-        assert_eq!(fm.validated, true); // デフォルト値がtrueであることを確認
+        assert!(fm.validated); // デフォルト値がtrueであることを確認
+    }
+
+    #[test]
+    fn test_parse_error_location_is_relative_to_markdown() {
+        let md = "---\nschema_path: ./schema.yaml\nvalidated: [\n---\n# Test Document";
+
+        let error = parse_frontmatter(md).unwrap_err();
+        let info = ErrorInfo::from_core_error(&error, ErrorCode::FrontmatterParse);
+        assert!(info
+            .message
+            .contains("フロントマターのパースに失敗しました"));
+        assert_eq!(info.line, 3);
+        assert!(info.column > 0);
+    }
+
+    #[test]
+    fn test_empty_schema_path_error_position() {
+        let md = "---\nvalidated: true\nschema_path: \"\"\n---\n# Test Document";
+
+        let frontmatter = parse_frontmatter(md).unwrap();
+        let result = validate_frontmatter(&frontmatter);
+        let error = &result.errors[0];
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!((error.end_line, error.end_column), (3, 12));
+        assert_eq!(
+            &md[error.offset as usize..error.end_offset as usize],
+            "schema_path"
+        );
+    }
+
+    #[test]
+    fn test_extra_fields_are_preserved() {
+        let md = "---\ntitle: Weekly\nschema_path: ./schema.yaml\ntags: [rust, wasm]\ncreated_at: 2024-01-02T10:00:00Z\ndraft: false\nrevision: 3\n---\n# Test Document";
+
+        let fm = parse_frontmatter(md).unwrap();
+        assert_eq!(fm.schema_path.as_deref(), Some("./schema.yaml"));
+        assert_eq!(
+            fm.extra
+                .keys()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>(),
+            vec!["title", "tags", "created_at", "draft", "revision"]
+        );
+        assert_eq!(fm.title(), Some("Weekly"));
+        assert_eq!(fm.tags(), vec!["rust", "wasm"]);
+        assert_eq!(fm.created_at(), Some("2024-01-02T10:00:00Z"));
+        assert_eq!(fm.get_bool("draft"), Some(false));
+        assert_eq!(fm.get_i64("revision"), Some(3));
+        assert_eq!(fm.get_str("revision"), None);
+        assert_eq!(
+            fm.get_as::<Vec<String>>("tags").unwrap().unwrap(),
+            vec!["rust", "wasm"]
+        );
+        assert!(fm.get_as::<u8>("title").unwrap().is_err());
+        assert!(fm.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_and_validate_includes_fields() {
+        let md = "---\nschema_path: ./schema.yaml\nauthor: alice\ntags: note\n---\n# Test Document";
+
+        let json: serde_json::Value =
+            serde_json::from_str(&parse_and_validate(md, None).to_json()).unwrap();
+        assert_eq!(json["success"], true);
+        assert_eq!(
+            json["frontmatter"],
+            serde_json::json!({"schema_path": "./schema.yaml", "validated": true, "author": "alice", "tags": "note"})
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&parse_and_validate("# No frontmatter", None).to_json()).unwrap();
+        assert_eq!(json["success"], false);
+        assert!(json["frontmatter"].is_null());
+    }
+
+    #[test]
+    fn test_horizontal_rule_is_not_frontmatter() {
+        let md = "# Title\n\nFirst paragraph\n\n---\n\nkey: [unclosed\n\n---\n";
+
+        assert_eq!(locate_frontmatter(md), None);
+        let err = parse_frontmatter(md).unwrap_err();
+        assert!(matches!(err, CoreError::FrontmatterParseError(_)));
+    }
+
+    #[test]
+    fn test_locate_frontmatter_variants() {
+        // BOMと `...` の閉じ行
+        let md = "\u{feff}---\nschema_path: a.yaml\n...\n# Title";
+        let block = locate_frontmatter(md).unwrap();
+        assert_eq!(&md[block.body.clone()], "schema_path: a.yaml");
+        assert_eq!(&md[block.end..], "# Title");
+        assert_eq!(
+            parse_frontmatter(md).unwrap().schema_path.as_deref(),
+            Some("a.yaml")
+        );
+
+        // CRLFと空のフロントマター
+        let md = "---\r\n---\r\nbody";
+        let block = locate_frontmatter(md).unwrap();
+        assert!(block.body.is_empty());
+        assert_eq!(&md[block.end..], "body");
+
+        // 閉じられていない、または先頭行以外から始まる場合
+        assert_eq!(locate_frontmatter("---\ntitle: a\n"), None);
+        assert_eq!(locate_frontmatter("\n---\ntitle: a\n---\n"), None);
+        assert_eq!(locate_frontmatter("----\ntitle: a\n---\n"), None);
+    }
+
+    #[test]
+    fn test_parse_toml_frontmatter() {
+        let md = "+++\ntitle = \"Weekly\"\nschema_path = \"./schema.yaml\"\ntags = [\"rust\", \"wasm\"]\ncreated_at = 2024-01-02T10:00:00Z\n\n[author]\nname = \"alice\"\n+++\n# Test Document";
+
+        let fm = parse_frontmatter(md).unwrap();
+        assert_eq!(fm.format, FrontmatterFormat::Toml);
+        assert_eq!(fm.schema_path.as_deref(), Some("./schema.yaml"));
+        assert_eq!(fm.title(), Some("Weekly"));
+        assert_eq!(fm.tags(), vec!["rust", "wasm"]);
+        assert_eq!(fm.created_at(), Some("2024-01-02T10:00:00Z"));
+        assert_eq!(
+            fm.extra
+                .keys()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>(),
+            vec!["title", "tags", "created_at", "author"]
+        );
+        assert_eq!(
+            fm.get("author")
+                .and_then(|a| a.get("name"))
+                .and_then(Value::as_str),
+            Some("alice")
+        );
+    }
+
+    #[test]
+    fn test_parse_json_frontmatter() {
+        let md = "{\n  \"schema_path\": \"./schema.yaml\",\n  \"validated\": false,\n  \"tags\": [\"a\"]\n}\n# Test Document";
+
+        let fm = parse_frontmatter(md).unwrap();
+        assert_eq!(fm.format, FrontmatterFormat::Json);
+        assert_eq!(fm.schema_path.as_deref(), Some("./schema.yaml"));
+        assert!(!fm.validated);
+        assert_eq!(fm.tags(), vec!["a"]);
+
+        // 段落の先頭の `{` はフロントマターではない
+        assert_eq!(locate_frontmatter("{{< figure >}}\n}\n"), None);
+    }
+
+    #[test]
+    fn test_syntax_error_location_by_format() {
+        let md = "+++\ntitle = \"a\"\ntags = [\"x\",\n+++\n# Test Document";
+        let info = ErrorInfo::from_core_error(
+            &parse_frontmatter(md).unwrap_err(),
+            ErrorCode::FrontmatterParse,
+        );
+        assert!(info
+            .message
+            .contains("フロントマターのパースに失敗しました"));
+        assert_eq!(info.line, 3);
+
+        let md = "{\n  \"title\": \"a\",\n  \"tags\": [1,]\n}\n# Test Document";
+        let info = ErrorInfo::from_core_error(
+            &parse_frontmatter(md).unwrap_err(),
+            ErrorCode::FrontmatterParse,
+        );
+        assert_eq!(info.line, 3);
+        assert!(info.column > 0);
+    }
+
+    #[test]
+    fn test_invalid_field_type_location() {
+        let md = "+++\ntitle = \"a\"\nvalidated = \"yes\"\n+++\n";
+        let info = ErrorInfo::from_core_error(
+            &parse_frontmatter(md).unwrap_err(),
+            ErrorCode::FrontmatterParse,
+        );
+        assert!(info.message.contains("validated"));
+        assert_eq!((info.line, info.column), (3, 13));
+        assert_eq!(
+            &md[info.offset as usize..info.end_offset as usize],
+            "\"yes\""
+        );
+
+        let md = "---\ntitle: a\nschema_path: [a]\n---\n";
+        let info = ErrorInfo::from_core_error(
+            &parse_frontmatter(md).unwrap_err(),
+            ErrorCode::FrontmatterParse,
+        );
+        assert!(info.message.contains("schema_path"));
+        assert_eq!(info.line, 3);
+    }
+
+    #[test]
+    fn test_toml_empty_schema_path_error_position() {
+        let md = "+++\nvalidated = true\nschema_path = \"\"\n+++\n# Test Document";
+
+        let result = validate_frontmatter(&parse_frontmatter(md).unwrap());
+        let error = &result.errors[0];
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(
+            &md[error.offset as usize..error.end_offset as usize],
+            "schema_path"
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_toml_field_ranges() {
+        let src = "title = \"a = b\"\npath = \"a\\\\\"\nlist = [\n  [1, 2],\n]\nnote = \"\"\"\nline = 1\n\"\"\"\nseo.title = \"x\"\nseo.description = \"y\"\n[author]\nname = \"alice\"\n\n[[links]]\nurl = \"a\"\n[[links]]\nurl = \"b\"\n[site.meta]\nlang = \"ja\"\n";
+        let fields: Vec<(String, &str, &str)> = toml_field_ranges(src)
+            .into_iter()
+            .map(|(key, k, v)| (key, &src[k], &src[v]))
+            .collect();
+
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, ..)| k == key)
+                .map(|(_, k, v)| (*k, *v))
+        };
+        assert_eq!(get("title"), Some(("title", "\"a = b\"")));
+        // 末尾がエスケープした `\` の文字列
+        assert_eq!(get("path"), Some(("path", "\"a\\\\\"")));
+        assert_eq!(get("list"), Some(("list", "[\n  [1, 2],\n]")));
+        assert_eq!(get("note"), Some(("note", "\"\"\"\nline = 1\n\"\"\"")));
+        assert_eq!(get("line"), None);
+        assert_eq!(get("seo"), Some(("seo", "\"x\"\nseo.description = \"y\"")));
+        assert_eq!(
+            get("author"),
+            Some(("author", "[author]\nname = \"alice\""))
+        );
+        assert_eq!(get("name"), None);
+        assert_eq!(
+            get("links").unwrap().1,
+            "[[links]]\nurl = \"a\"\n[[links]]\nurl = \"b\""
+        );
+        assert_eq!(get("site"), Some(("site", "[site.meta]\nlang = \"ja\"")));
+        assert_eq!(get("meta"), None);
+
+        // 構文エラーの場合は位置を求めない
+        assert!(toml_field_ranges("title = [\n").is_empty());
+    }
+
+    #[test]
+    fn test_convert_frontmatter() {
+        let md = "---\ntitle: Weekly\ntags:\n- rust\n- wasm\nauthor:\n  name: alice\n---\n# Test Document\n";
+
+        let toml = convert_frontmatter(md, FrontmatterFormat::Toml).unwrap();
+        assert!(toml.starts_with("+++\ntitle = \"Weekly\"\n"));
+        assert!(toml.ends_with("+++\n# Test Document\n"));
+        let fm = parse_frontmatter(&toml).unwrap();
+        assert_eq!(fm.format, FrontmatterFormat::Toml);
+        assert_eq!(fm.tags(), vec!["rust", "wasm"]);
+
+        let json = convert_frontmatter(&toml, FrontmatterFormat::Json).unwrap();
+        assert!(json.starts_with("{\n  \"title\": \"Weekly\""));
+        let yaml = convert_frontmatter(&json, FrontmatterFormat::Yaml).unwrap();
+        assert_eq!(yaml, md);
+
+        // TOMLで表現できない値
+        let error =
+            convert_frontmatter("---\ntitle: ~\n---\n", FrontmatterFormat::Toml).unwrap_err();
+        assert!(matches!(error.code, ErrorCode::FrontmatterParse));
+
+        // フロントマターがない場合はそのまま
+        assert_eq!(
+            convert_frontmatter("# Title\n", FrontmatterFormat::Json).unwrap(),
+            "# Title\n"
+        );
+    }
+
+    #[test]
+    fn test_set_frontmatter_field() {
+        // 元の形式のまま書き換える
+        let md = "+++\nschema_path = \"note.schema.yaml\"\nvalidated = true\n+++\n# Note\n";
+        let updated = set_frontmatter_field(md, "validated", Value::Bool(false)).unwrap();
+        assert_eq!(
+            updated,
+            "+++\nschema_path = \"note.schema.yaml\"\nvalidated = false\n+++\n# Note\n"
+        );
+
+        // ない場合は追加する
+        let updated = set_frontmatter_field(
+            "{\n\"title\": \"Note\"\n}\n# Note\n",
+            "validated",
+            Value::Bool(false),
+        )
+        .unwrap();
+        let fm = parse_frontmatter(&updated).unwrap();
+        assert_eq!(fm.format, FrontmatterFormat::Json);
+        assert!(!fm.validated);
+        assert_eq!(fm.title(), Some("Note"));
+        assert!(updated.ends_with("}\n# Note\n"));
+
+        let updated =
+            set_frontmatter_field("---\n---\n# Note\n", "validated", Value::Bool(false)).unwrap();
+        assert_eq!(updated, "---\nvalidated: false\n---\n# Note\n");
+
+        let error = set_frontmatter_field("# Note\n", "validated", Value::Bool(false)).unwrap_err();
+        assert!(matches!(error.code, ErrorCode::FrontmatterParse));
+    }
+
+    const NOTE_SCHEMA: &str = r##"
+type: object
+frontmatter:
+  type: object
+  properties:
+    title:
+      type: string
+    tags:
+      type: array
+      items:
+        $ref: "#/definitions/tag"
+  required: [title]
+definitions:
+  tag:
+    type: string
+    pattern: "^[a-z]+$"
+"##;
+
+    #[test]
+    fn test_validate_fields_with_frontmatter_section() {
+        let md = "---\nschema_path: ./note.yaml\ntags:\n  - rust\n  - Wasm\n---\n# Test Document";
+
+        let result = parse_and_validate(md, Some(NOTE_SCHEMA));
+        assert!(!result.success);
+        assert_eq!(result.errors.len(), 2);
+        // キーワードごとのコードで報告し、フロントマターの検証エラーであることを記録する
+        assert!(result.errors.iter().all(|e| e.frontmatter));
+
+        let pattern = result.errors.iter().find(|e| e.path == "/tags/1").unwrap();
+        assert!(matches!(pattern.code, ErrorCode::PatternMismatch));
+        assert_eq!(
+            pattern.params.as_ref().unwrap().pattern.as_deref(),
+            Some("^[a-z]+$")
+        );
+        assert_eq!((pattern.line, pattern.column), (5, 5));
+        assert_eq!(
+            &md[pattern.offset as usize..pattern.end_offset as usize],
+            "Wasm"
+        );
+        // required はフロントマター全体（先頭行）を指す
+        let required = result.errors.iter().find(|e| e.path.is_empty()).unwrap();
+        assert!(matches!(required.code, ErrorCode::RequiredMissing));
+        assert_eq!(required.line, 2);
+        assert!(result.to_json().contains(r#""frontmatter":true"#));
+
+        // validated: false なら検証しない
+        let md = "---\nvalidated: false\ntags: [Wasm]\n---\n";
+        assert!(parse_and_validate(md, Some(NOTE_SCHEMA)).success);
+
+        // frontmatter セクションがなければ検証しない
+        let md = "---\ntags: [Wasm]\n---\n";
+        assert!(parse_and_validate(md, Some("type: object")).success);
+    }
+
+    #[test]
+    fn test_validate_fields_with_frontmatter_schema_path() {
+        let schema =
+            "type: object\nproperties:\n  draft:\n    type: boolean\nadditionalProperties: false\n";
+
+        // ノートの設定のキーは additionalProperties の対象外
+        let md = "+++\nfrontmatter_schema_path = \"./fm.yaml\"\nschema_path = \"./note.yaml\"\nvalidated = true\ndraft = true\n+++\n# Test Document";
+        let result = parse_and_validate(md, Some(schema));
+        assert!(result.success, "{:?}", result.errors);
+
+        let md = "+++\nfrontmatter_schema_path = \"./fm.yaml\"\ndraft = true\nauthor = \"alice\"\n+++\n# Test Document";
+        let result = parse_and_validate(md, Some(schema));
+        assert!(!result.success);
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert!(error.message.contains("author"));
+        assert!(!error.message.contains("frontmatter_schema_path"));
+        assert_eq!((error.path.as_str(), error.line), ("", 2));
+
+        // 設定のキーもスキーマの required や制約の対象
+        let schema =
+            "required: [schema_path]\nproperties:\n  schema_path:\n    pattern: \"\\\\.yaml$\"\n";
+        let md = "---\nfrontmatter_schema_path: ./fm.yaml\n---\n";
+        let result = parse_and_validate(md, Some(schema));
+        assert!(matches!(result.errors[0].code, ErrorCode::RequiredMissing));
+        assert_eq!(
+            result.errors[0]
+                .params
+                .as_ref()
+                .unwrap()
+                .property
+                .as_deref(),
+            Some("schema_path")
+        );
+        let md = "---\nfrontmatter_schema_path: ./fm.yaml\nschema_path: ./note.json\n---\n";
+        let result = parse_and_validate(md, Some(schema));
+        assert!(matches!(result.errors[0].code, ErrorCode::PatternMismatch));
+        assert_eq!(
+            (result.errors[0].path.as_str(), result.errors[0].line),
+            ("/schema_path", 3)
+        );
+        let md = "---\nfrontmatter_schema_path: ./fm.yaml\nschema_path: ./note.yaml\n---\n";
+        assert!(parse_and_validate(md, Some(schema)).success);
+
+        let md = "+++\ndraft = \"no\"\n+++\n";
+        let result = parse_and_validate(
+            md,
+            Some("frontmatter:\n  properties:\n    draft:\n      type: boolean\n"),
+        );
+        assert_eq!(result.errors[0].path, "/draft");
+        assert_eq!((result.errors[0].line, result.errors[0].column), (2, 9));
+
+        // スキーマ自体のエラー
+        let result = parse_and_validate(md, Some("type: [object"));
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::YamlParse));
+    }
+}
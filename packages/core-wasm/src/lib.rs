@@ -7,24 +7,55 @@
 //! ## 主な公開API
 //! - `parse_yaml`: YAML→JSON変換
 //! - `validate_yaml`: YAML+スキーマのバリデーション
+//! - `validate_markdown`: Markdown+スキーマのバリデーション（エラー位置はMarkdownの行）
+//! - `CompiledSchema`: コンパイル済みスキーマによる繰り返しバリデーション
 //! - `stringify_yaml`: JSON→YAML変換
+//! - `apply_patch`: YAMLへのJSON Patch / Merge Patch適用
+//! - `md_headings_to_yaml` / `yaml_to_md`: Markdown⇔YAMLの相互変換
+//! - `parse_and_validate_frontmatter` / `convert_frontmatter`: フロントマター（YAML / TOML / JSON）の解析と形式変換
+//! - `SchemaRegistry`: `schema_path` などのスキーマ参照の解決と、ファイルをまたぐ `$ref` を含むスキーマのコンパイル（JSから登録したスキーマ）
 //!
 //! ## 内部モジュール
 //! - `error`: エラー型とバリデーション結果
 //! - `validate`: バリデーションロジック
+//! - `patch`: パッチ適用ロジック
+//! - `schema_resolver`: スキーマ参照の正規化と解決（`SchemaResolver` トレイト）
+//! - `schema_bundle`: 外部の `$ref`（相対パス / `$id`）の解決と1つのスキーマへのバンドル
+//! - `schema_lint`: スキーマのリント（ルールごとに重大度を設定できる警告）
+//! - `schema_severity`: スキーマの `x-severity` による検証エラーの重大度の変更
+//! - `yaml_span`: YAMLノードの位置情報
 
 use wasm_bindgen::prelude::*;
 
 mod error;
 mod error_code;
 mod frontmatter;
+mod md_mapping;
 mod md_transform;
+mod patch;
+mod schema_bundle;
 mod schema_compile;
+mod schema_lint;
+mod schema_resolver;
+mod schema_severity;
 mod validate;
+mod yaml_span;
 
 pub use error_code::ErrorCode;
 
-pub use error::{CoreError, ErrorInfo, ValidationResult};
+pub use error::{CoreError, ErrorInfo, ErrorParams, Severity, ValidationResult};
+
+pub use frontmatter::{parse_frontmatter, Frontmatter, FrontmatterFormat};
+pub use schema_bundle::{bundle_schema, BUNDLE_KEY};
+pub use schema_lint::{lint_schema, LintOptions, LintRule, RuleLevel};
+#[cfg(not(target_arch = "wasm32"))]
+pub use schema_resolver::FileSystemResolver;
+pub use schema_resolver::{
+    normalize_path, MemoryResolver, RelativeResolver, ResolvedSchema, SchemaRegistry,
+    SchemaResolver, WorkspaceResolver,
+};
+pub use schema_severity::SEVERITY_KEY;
+pub use validate::CompiledSchema;
 
 /// YAMLを指定されたスキーマに対してバリデーションする
 ///
@@ -39,21 +70,42 @@ pub fn validate_yaml(yaml_str: &str, schema_str: &str) -> String {
     validate::validate_yaml(yaml_str, schema_str)
 }
 
+/// Markdownを見出し構造のYAMLに変換し、スキーマに対してバリデーションする
+///
+/// # 引数
+/// * `md_str` - バリデーション対象のMarkdown文字列（フロントマターを含んでよい）
+/// * `schema_str` - JSON Schema形式のバリデーションスキーマ（YAML形式）
+/// * `options_str` - Markdown → YAML変換のオプション（`md_headings_to_yaml` と同じ、任意）
+///
+/// # 戻り値
+/// * バリデーション結果を含むJSON文字列
+///   - エラー位置は生成したYAMLではなく元のMarkdown上の行を指す
+#[wasm_bindgen]
+pub fn validate_markdown(md_str: &str, schema_str: &str, options_str: Option<String>) -> String {
+    validate::validate_markdown(md_str, schema_str, options_str.as_deref())
+}
+
 /// JSON Schemaをコンパイルし、スキーマ自体が有効かどうかを検証する
 ///
 /// # 引数
 /// * `schema_str` - 検証対象のJSON Schema文字列（YAML形式）
+/// * `options_str` - リントの設定（YAML/JSON形式、任意）
+///   - `lints` → ルール名ごとに `error` / `warning` / `off`
+///     （例: `{lints: {unknown-keyword: off, unused-definition: error}}`、`invalid-pattern` は変更不可）
 ///
 /// # 戻り値
 /// * バリデーション結果を含むJSON文字列
+///   - すべての問題を、スキーマ内のJSON Pointer（`path`）と行・列の位置とともに返す
+///   - リントの診断は `code: "SchemaLint"` と `rule`（ルール名）を持ち、
+///     `severity: "warning"` のものは `success` に影響しない
 ///
 /// # エラーケース
 /// - YAMLパースエラー
-/// - スキーマ構文エラー（無効なtypeフィールドなど）
-/// - 論理エラー（存在しないプロパティをrequiredに指定など）
+/// - スキーマ構文エラー（`$schema` のdraftのメタスキーマに違反、無効なtypeフィールドなど）
+/// - 論理エラー（存在しないプロパティをrequiredに指定、minLength > maxLength など）
 #[wasm_bindgen]
-pub fn compile_schema(schema_str: &str) -> String {
-    schema_compile::compile_schema(schema_str)
+pub fn compile_schema(schema_str: &str, options_str: Option<String>) -> String {
+    schema_compile::compile_schema(schema_str, options_str.as_deref())
 }
 
 /// バージョン情報を取得する
@@ -66,40 +118,202 @@ pub fn version() -> String {
 ///
 /// # 引数
 /// * `md_str` - フロントマターを含むMarkdown文字列
+///   - `---`（YAML）/ `+++`（TOML）/ `{` 〜 `}`（JSON）のいずれか。文書の先頭にある場合のみ
+/// * `schema_str` - フィールドの検証に使うスキーマ（YAML/JSON形式、任意）
+///   - フロントマターに `frontmatter_schema_path` がある場合はそのスキーマを渡す（全体を適用）
+///   - それ以外は `schema_path` のスキーマを渡す（`frontmatter` セクションのみ適用）
 ///
 /// # 戻り値
 /// * 検証結果を含むJSON文字列
-///   - 成功時: `{"success":true,"errors":[]}`
-///   - 失敗時: `{"success":false,"errors":[ErrorInfo, ...]}`
+///   - 成功時: `{"success":true,"errors":[],"frontmatter":{...}}`
+///   - 失敗時: `{"success":false,"errors":[ErrorInfo, ...],"frontmatter":{...} | null}`
+///   - `frontmatter`: `schema_path` / `validated` を含む全フィールド（記述順）。
+///     解析できなかった場合はnull
+///   - `format`: フロントマターの形式（`"yaml"` / `"toml"` / `"json"`、見つからない場合はnull）
 ///
 /// # エラーケース
 /// - フロントマターがない、または不完全な場合
 /// - YAMLパースエラー
 /// - フロントマター構文エラー（空のschema_pathなど）
+/// - スキーマに適合しないフィールド（キーワードごとのコードまたは `FrontmatterValidation`、
+///   `frontmatter: true` を持ち、位置はフロントマター内の行）
 #[wasm_bindgen]
-pub fn parse_and_validate_frontmatter(md_str: &str) -> String {
-    match frontmatter::parse_frontmatter(md_str) {
-        Ok(frontmatter) => {
-            let validation_result = frontmatter::validate_frontmatter(&frontmatter);
-            validation_result.to_json()
-        }
-        Err(e) => ValidationResult::single_error(ErrorInfo::new(0, e.to_string(), "", ErrorCode::FrontmatterParse)).to_json(),
+pub fn parse_and_validate_frontmatter(md_str: &str, schema_str: Option<String>) -> String {
+    frontmatter::parse_and_validate(md_str, schema_str.as_deref()).to_json()
+}
+
+/// フロントマターを別の形式に書き換える
+///
+/// # 引数
+/// * `md_str` - フロントマターを含むMarkdown文字列
+/// * `format` - 変換先の形式（`yaml` / `toml` / `json`）
+///
+/// # 戻り値
+/// * 変換結果を含むJSON文字列
+///   - 成功時: `{"success":true,"markdown":"...","errors":[]}`
+///     （フロントマターがない場合や同じ形式の場合は元のMarkdown）
+///   - 失敗時: `{"success":false,"markdown":"","errors":[ErrorInfo, ...]}`
+///
+/// # エラーケース
+/// - 不明な形式名
+/// - フロントマターの解析エラー
+/// - 変換先の形式で表現できない値（TOMLのnullなど）
+#[wasm_bindgen]
+pub fn convert_frontmatter(md_str: &str, format: &str) -> String {
+    let result = match frontmatter::FrontmatterFormat::from_name(format) {
+        Some(to) => frontmatter::convert_frontmatter(md_str, to),
+        None => Err(ErrorInfo::new(
+            0,
+            format!("不明なフロントマターの形式です: {}", format),
+            "",
+            ErrorCode::FrontmatterParse,
+        )),
+    };
+    match result {
+        Ok(markdown) => md_transform::MarkdownResult {
+            success: true,
+            markdown,
+            errors: vec![],
+        },
+        Err(error) => md_transform::MarkdownResult::error(error),
     }
+    .to_json()
+}
+
+/// フロントマターのフィールドを1つ書き換える（ない場合は追加する）
+///
+/// # 引数
+/// * `md_str` - フロントマターを含むMarkdown文字列
+/// * `key` - フィールド名（`validated` など）
+/// * `value_json` - 新しい値（JSON形式、例: `false`）
+///
+/// # 戻り値
+/// * 書き換え結果を含むJSON文字列（`convert_frontmatter` と同じ形式）
+///   - フロントマターは元の形式（YAML / TOML / JSON）のまま書き直す
+///
+/// # エラーケース
+/// - フロントマターがない、または解析エラー
+/// - 値がJSONとして不正な場合
+/// - フロントマターの形式で表現できない値（TOMLのnullなど）
+#[wasm_bindgen]
+pub fn set_frontmatter_field(md_str: &str, key: &str, value_json: &str) -> String {
+    let result = serde_json::from_str::<serde_yaml::Value>(value_json)
+        .map_err(|e| {
+            ErrorInfo::new(
+                0,
+                format!("フィールドの値が不正です: {}", e),
+                "",
+                ErrorCode::FrontmatterParse,
+            )
+        })
+        .and_then(|value| frontmatter::set_frontmatter_field(md_str, key, value));
+    match result {
+        Ok(markdown) => md_transform::MarkdownResult {
+            success: true,
+            markdown,
+            errors: vec![],
+        },
+        Err(error) => md_transform::MarkdownResult::error(error),
+    }
+    .to_json()
 }
 
 /// Markdownの見出し構造をYAML形式に変換する
 ///
 /// # 引数
 /// * `md_str` - Markdown文字列
+/// * `options_str` - 変換オプション（YAML/JSON形式、任意）
+///   - `lists: true` → リストを `content` から取り出し `items` 配列として出力する
+///     （入れ子のリストは配列、タスクリストの項目は `{text, checked}`）
+///   - `tables: true` → GFMの表を `content` から取り出し `tables` 配列として出力する
+///     （`{columns: [...], rows: [{列名: 値}]}`、セルは文字列のまま（空のセルはnull）で、
+///     `validate_markdown` ではスキーマが指定する型に変換する。列名の重複は `DuplicateKey` エラー）
+///   - `code_blocks: true` → コードブロックを `content` から取り出し `code_blocks` 配列として出力する
+///     （`{lang, code, line}`、`line` は元のMarkdown上の開始行）
+///   - `fields: true` → 本文先頭の `Key: value` 行と定義リストを `fields` オブジェクトとして出力する
+///     （値は文字列のまま、定義が複数あれば配列、フィールド名の重複は `DuplicateKey` エラー）
+///     - `fields: {keys: [...], sections: [...]}` → 取り出すフィールド名・見出しを限定する
+///   - `mapping` → 見出しマッピング設定（ノートの種類ごとのYAMLの形）
+///     - `keys`: 出力キー名の変更（例: `{title: name, sections: parts}`）
+///     - `start_level`: トップレベルのセクションになる見出しレベル（標準は2）
+///     - `sections: slug` → セクションを見出しのスラッグをキーとするマップで出力
+///     - `sections: title` → セクションを見出しテキストをキーとするマップで出力
+///       （同じ階層の見出しの重複は `DuplicateHeading` エラー）
+///     - `default_title`: タイトルの見出しがない場合のタイトル
+///   - `frontmatter` → フロントマターのフィールドの取り込み
+///     - `merge: key` → `key`（標準は `frontmatter`）の下にまとめて出力
+///     - `merge: top_level` → トップレベルに展開（見出しから生成したキーを優先）
 ///
 /// # 戻り値
 /// * 見出し構造に基づいたYAML文字列
 ///   - H1 → title フィールド
 ///   - H2 → sections 配列の要素
-///   - H3 → sections[].subsections 配列の要素
+///   - H3〜H6 → sections[].sections 配列の要素（見出しレベルに応じて入れ子）
+///   - 見出しはCommonMarkとして解析する（コードブロック内の `#` 行は見出しにならない）
+/// * オプションが不正な場合は `Error parsing options: ...` 形式のエラーメッセージ
+#[wasm_bindgen]
+pub fn md_headings_to_yaml(md_str: &str, options_str: Option<String>) -> String {
+    match md_transform::TransformOptions::parse(options_str.as_deref()) {
+        Ok(options) => md_transform::md_headings_to_yaml_with_options(md_str, &options),
+        Err(error) => format!("Error parsing options: {}", error.message),
+    }
+}
+
+/// 見出し構造のYAMLをMarkdownに戻す
+///
+/// # 引数
+/// * `yaml_str` - `md_headings_to_yaml` 形式（`title` / `content` / `sections`）のYAML文字列
+/// * `frontmatter_str` - 先頭に付与するフロントマター（YAML形式、任意）
+///
+/// # 戻り値
+/// * 変換結果を含むJSON文字列
+///   - 成功時: `{"success":true,"markdown":"...","errors":[]}`
+///   - 失敗時: `{"success":false,"markdown":"","errors":[ErrorInfo, ...]}`
+///
+/// # 備考
+/// - `md_headings_to_yaml` → `yaml_to_md` → `md_headings_to_yaml` で同じYAMLに戻る
+/// - `mapping` でキー名やセクション形式を変更したYAMLには対応しない（標準の形のみ）
+#[wasm_bindgen]
+pub fn yaml_to_md(yaml_str: &str, frontmatter_str: Option<String>) -> String {
+    md_transform::yaml_to_md(yaml_str, frontmatter_str.as_deref())
+}
+
+/// Markdownの見出し構造をYAML形式に変換し、ソースマップと共に返す
+///
+/// # 引数
+/// * `md_str` - Markdown文字列
+/// * `options_str` - 変換オプション（`md_headings_to_yaml` と同じ、任意）
+///
+/// # 戻り値
+/// * `{"yaml": "...", "source_map": {...}, "errors": [...]}` 形式のJSON文字列
+///   - `source_map`: YAMLノードのパス（JSON Pointer）→ Markdown上の行範囲
+///     （`{"start_line": 1, "end_line": 3}`、フロントマターを含む元の行番号）
+///   - `errors`: オプションが不正な場合のエラー情報（通常は空配列）
+#[wasm_bindgen]
+pub fn md_headings_to_yaml_with_source_map(md_str: &str, options_str: Option<String>) -> String {
+    md_transform::md_headings_to_yaml_with_source_map(md_str, options_str.as_deref())
+}
+
+/// YAMLにパッチを適用する
+///
+/// # 引数
+/// * `yaml_str` - パッチ適用対象のYAML文字列
+/// * `patch_str` - パッチ（JSON/YAML形式）
+///   - 配列の場合: RFC 6902 JSON Patch
+///   - オブジェクトの場合: RFC 7386 JSON Merge Patch
+///
+/// # 戻り値
+/// * パッチ適用結果を含むJSON文字列
+///   - 成功時: `{"success":true,"yaml":"...","errors":[]}`
+///   - 失敗時: `{"success":false,"yaml":"<元のYAML>","errors":[ErrorInfo, ...]}`
+///
+/// # エラーケース
+/// - YAML/パッチのパースエラー
+/// - 操作のpathが存在しない場合
+/// - `test` 操作の値が一致しない場合
 #[wasm_bindgen]
-pub fn md_headings_to_yaml(md_str: &str) -> String {
-    md_transform::md_headings_to_yaml(md_str)
+pub fn apply_patch(yaml_str: &str, patch_str: &str) -> String {
+    patch::apply_patch(yaml_str, patch_str)
 }
 
 #[cfg(test)]
@@ -0,0 +1,479 @@
+//! md_mapping.rs
+//!
+//! Markdownから生成したYAMLの形を決める見出しマッピング設定のモジュール。
+//! - 出力キー名（`title` / `content` / `sections` など）の変更
+//! - ツリーの起点となる見出しレベルの指定
+//! - セクションを配列ではなく見出しテキスト（またはスラッグ）をキーとするマップで出力
+//! - タイトルがない場合のデフォルトタイトル
+//!
+//! 日報・ADR・議事録などノートの種類ごとに、スキーマが期待する形のYAMLを生成するために利用する。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::ErrorInfo;
+use crate::error_code::ErrorCode;
+use crate::md_transform::SourceMap;
+use crate::yaml_span::escape_pointer_segment;
+
+/// 出力キー名
+///
+/// 未指定のフィールドは標準のキー名を使う
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyNames {
+    pub title: String,
+    pub content: String,
+    pub sections: String,
+    pub items: String,
+    pub tables: String,
+    pub code_blocks: String,
+    pub fields: String,
+}
+
+impl Default for KeyNames {
+    fn default() -> Self {
+        Self {
+            title: "title".to_string(),
+            content: "content".to_string(),
+            sections: "sections".to_string(),
+            items: "items".to_string(),
+            tables: "tables".to_string(),
+            code_blocks: "code_blocks".to_string(),
+            fields: "fields".to_string(),
+        }
+    }
+}
+
+impl KeyNames {
+    /// 標準のキー名を設定されたキー名に変換する
+    fn rename<'a>(&'a self, key: &'a str) -> &'a str {
+        match key {
+            "title" => &self.title,
+            "content" => &self.content,
+            "sections" => &self.sections,
+            "items" => &self.items,
+            "tables" => &self.tables,
+            "code_blocks" => &self.code_blocks,
+            "fields" => &self.fields,
+            _ => key,
+        }
+    }
+
+    fn all(&self) -> [&str; 7] {
+        [
+            &self.title,
+            &self.content,
+            &self.sections,
+            &self.items,
+            &self.tables,
+            &self.code_blocks,
+            &self.fields,
+        ]
+    }
+}
+
+/// セクションの出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionLayout {
+    /// 見出し順の配列（標準）
+    #[default]
+    Array,
+    /// 見出しテキストのスラッグをキーとするマップ（重複時は `-1`, `-2` ... を付与）
+    Slug,
+    /// 見出しテキストをキーとするマップ（重複した見出しはエラーとして報告し、最初のものを残す）
+    Title,
+}
+
+/// 見出しマッピング設定
+///
+/// # フィールド
+/// - `keys`: 出力キー名
+/// - `start_level`: トップレベルのセクションになる見出しレベル（1〜6、標準は2）。
+///   2以上の場合は最初のH1がタイトルになり、1の場合はタイトルに `default_title` を使う
+/// - `sections`: セクションの出力形式
+/// - `default_title`: タイトルの見出しがない場合のタイトル
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeadingMapping {
+    pub keys: KeyNames,
+    pub start_level: usize,
+    pub sections: SectionLayout,
+    pub default_title: String,
+}
+
+impl Default for HeadingMapping {
+    fn default() -> Self {
+        Self {
+            keys: KeyNames::default(),
+            start_level: 2,
+            sections: SectionLayout::Array,
+            default_title: "Untitled Document".to_string(),
+        }
+    }
+}
+
+impl HeadingMapping {
+    /// 設定値の整合性を検証する
+    ///
+    /// # エラーケース
+    /// - `start_level` が1〜6の範囲外
+    /// - キー名が空、または複数のフィールドで同じキー名を使っている
+    pub fn check(&self) -> Result<(), String> {
+        if !(1..=6).contains(&self.start_level) {
+            return Err(format!(
+                "mapping.start_level は1〜6で指定してください: {}",
+                self.start_level
+            ));
+        }
+        let mut seen = HashSet::new();
+        for key in self.keys.all() {
+            if key.is_empty() {
+                return Err("mapping.keys に空のキー名は指定できません".to_string());
+            }
+            if !seen.insert(key) {
+                return Err(format!("mapping.keys のキー名が重複しています: {}", key));
+            }
+        }
+        Ok(())
+    }
+
+    /// 標準の形で生成したドキュメントとソースマップに、キー名とセクション形式の設定を適用する
+    ///
+    /// `errors`（本文の変換時のエラー）のパスも変換後のパスにする
+    ///
+    /// # 戻り値
+    /// * 変換後のドキュメント、ソースマップ、`errors` と見出しの重複エラー（`sections: title` の場合のみ）
+    pub(crate) fn apply(
+        &self,
+        md: &str,
+        document: Value,
+        source_map: SourceMap,
+        mut errors: Vec<ErrorInfo>,
+    ) -> (Value, SourceMap, Vec<ErrorInfo>) {
+        // 元のノードのパス → 変換後のノードのパス
+        let mut nodes = HashMap::new();
+        let mut duplicates = Vec::new();
+        let document = self.map_node(
+            document,
+            String::new(),
+            String::new(),
+            &mut nodes,
+            &mut duplicates,
+        );
+
+        let duplicates: Vec<ErrorInfo> = duplicates
+            .into_iter()
+            .map(|Duplicate { old, new, title }| {
+                let error = ErrorInfo::new(
+                    0,
+                    format!("見出し「{}」が重複しています。見出しテキストをキーとする出力では同じ階層の見出しは一意である必要があります", title),
+                    new,
+                    ErrorCode::DuplicateHeading,
+                );
+                match source_map.locate(&format!("{}/title", old)) {
+                    Some(range) => error.with_span(range.span(md)),
+                    None => error,
+                }
+            })
+            .collect();
+
+        let map_path = |path: &str| {
+            // 最も近いノードを探し、残りのパスの先頭（フィールド名）を変換する
+            let mut node = path;
+            while !nodes.contains_key(node) {
+                node = &node[..node.rfind('/').unwrap_or(0)];
+            }
+            let rest = &path[node.len()..];
+            match rest.strip_prefix('/') {
+                Some(rest) => {
+                    let (field, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+                    format!(
+                        "{}/{}{}",
+                        nodes[node],
+                        escape_pointer_segment(self.keys.rename(field)),
+                        tail
+                    )
+                }
+                None => nodes[node].clone(),
+            }
+        };
+        for error in &mut errors {
+            error.path = map_path(&error.path);
+        }
+        errors.extend(duplicates);
+        let source_map = source_map.map_paths(map_path);
+
+        (document, source_map, errors)
+    }
+
+    fn map_node(
+        &self,
+        node: Value,
+        old: String,
+        new: String,
+        nodes: &mut HashMap<String, String>,
+        duplicates: &mut Vec<Duplicate>,
+    ) -> Value {
+        let Value::Object(fields) = node else {
+            return node;
+        };
+
+        let mut mapped = Map::new();
+        for (key, value) in fields {
+            let name = self.keys.rename(&key).to_string();
+            let value = match (key.as_str(), value) {
+                ("sections", Value::Array(sections)) => {
+                    let old = format!("{}/sections", old);
+                    let new = format!("{}/{}", new, escape_pointer_segment(&name));
+                    self.map_sections(sections, &old, &new, nodes, duplicates)
+                }
+                (_, value) => value,
+            };
+            mapped.insert(name, value);
+        }
+        nodes.insert(old, new);
+        Value::Object(mapped)
+    }
+
+    fn map_sections(
+        &self,
+        sections: Vec<Value>,
+        old: &str,
+        new: &str,
+        nodes: &mut HashMap<String, String>,
+        duplicates: &mut Vec<Duplicate>,
+    ) -> Value {
+        match self.sections {
+            SectionLayout::Array => Value::Array(
+                sections
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, section)| {
+                        self.map_node(
+                            section,
+                            format!("{}/{}", old, i),
+                            format!("{}/{}", new, i),
+                            nodes,
+                            duplicates,
+                        )
+                    })
+                    .collect(),
+            ),
+            SectionLayout::Slug => {
+                let mut map = Map::new();
+                for (i, section) in sections.into_iter().enumerate() {
+                    let title = section
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    let base = slugify(title);
+                    let mut key = base.clone();
+                    let mut n = 0;
+                    while map.contains_key(&key) {
+                        n += 1;
+                        key = format!("{}-{}", base, n);
+                    }
+                    let path = format!("{}/{}", new, escape_pointer_segment(&key));
+                    let section =
+                        self.map_node(section, format!("{}/{}", old, i), path, nodes, duplicates);
+                    map.insert(key, section);
+                }
+                Value::Object(map)
+            }
+            SectionLayout::Title => {
+                let mut map = Map::new();
+                for (i, section) in sections.into_iter().enumerate() {
+                    let title = section
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    let old = format!("{}/{}", old, i);
+                    let path = format!("{}/{}", new, escape_pointer_segment(&title));
+                    if map.contains_key(&title) {
+                        duplicates.push(Duplicate {
+                            old,
+                            new: path,
+                            title,
+                        });
+                        continue;
+                    }
+                    let section = self.map_node(section, old, path, nodes, duplicates);
+                    map.insert(title, section);
+                }
+                Value::Object(map)
+            }
+        }
+    }
+}
+
+/// 見出しテキストをキーとする出力で重複した見出し
+struct Duplicate {
+    /// 標準の形でのセクションのパス
+    old: String,
+    /// 変換後のキーのパス（最初の見出しと同じ）
+    new: String,
+    title: String,
+}
+
+/// 見出しテキストをスラッグに変換する
+///
+/// 英字は小文字にし、空白・記号の連続は `-` 1つにまとめる（日本語などの文字はそのまま）。
+/// 空になる場合は `section`
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::md_transform::{md_to_value, TransformOptions};
+
+    fn options(yaml: &str) -> TransformOptions {
+        TransformOptions::parse(Some(yaml)).unwrap()
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started!"), "getting-started");
+        assert_eq!(slugify("  API / CLI  "), "api-cli");
+        assert_eq!(slugify("議事録 2024"), "議事録-2024");
+        assert_eq!(slugify("???"), "section");
+    }
+
+    #[test]
+    fn test_rename_keys() {
+        let md = "# ADR 1\n\nContext\n\n## Decision\n\nUse YAML\n";
+        let (value, source_map, _) = md_to_value(
+            md,
+            &options("mapping:\n  keys: {title: name, content: body, sections: parts}\n"),
+            None,
+        );
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "ADR 1",
+                "body": "Context",
+                "parts": [{"name": "Decision", "body": "Use YAML", "parts": []}]
+            })
+        );
+        assert_eq!(source_map.locate("/parts/0/body").unwrap().start_line, 7);
+        assert_eq!(source_map.locate("/name").unwrap().start_line, 1);
+    }
+
+    #[test]
+    fn test_sections_keyed_by_slug() {
+        let md = "# Meeting\n\n## Attendees\n\nAlice\n\n## Action Items\n\n### Follow up\n\nx\n\n## Attendees\n\nBob\n";
+        let (value, source_map, _) =
+            md_to_value(md, &options("mapping:\n  sections: slug\n"), None);
+
+        let sections = value["sections"].as_object().unwrap();
+        assert_eq!(
+            sections.keys().collect::<Vec<_>>(),
+            vec!["attendees", "action-items", "attendees-1"]
+        );
+        assert_eq!(
+            sections["action-items"]["sections"]["follow-up"]["content"],
+            "x"
+        );
+        assert_eq!(sections["attendees-1"]["content"], "Bob");
+        assert_eq!(
+            source_map
+                .locate("/sections/action-items/sections/follow-up/content")
+                .unwrap()
+                .start_line,
+            11
+        );
+    }
+
+    #[test]
+    fn test_sections_keyed_by_title() {
+        let md = "# Note\n\n## Introduction\n\nHello\n\n## Features\n\n### Fast / Small\n\nx\n";
+        let (value, source_map, errors) =
+            md_to_value(md, &options("mapping:\n  sections: title\n"), None);
+
+        assert!(errors.is_empty());
+        assert_eq!(value["sections"]["Introduction"]["content"], "Hello");
+        assert_eq!(
+            value["sections"]["Features"]["sections"]["Fast / Small"]["content"],
+            "x"
+        );
+        assert_eq!(
+            source_map
+                .locate("/sections/Features/sections/Fast ~1 Small/content")
+                .unwrap()
+                .start_line,
+            11
+        );
+    }
+
+    #[test]
+    fn test_duplicate_headings_are_reported() {
+        let md = "# Note\n\n## Features\n\nfirst\n\n## Usage\n\n## Features\n\nsecond\n";
+        let (value, _, errors) = md_to_value(md, &options("mapping:\n  sections: title\n"), None);
+
+        assert_eq!(value["sections"]["Features"]["content"], "first");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/sections/Features");
+        assert_eq!(errors[0].line, 9);
+        assert!(matches!(errors[0].code, ErrorCode::DuplicateHeading));
+
+        // 別の階層であれば同じ見出しでもよい
+        let md = "# Note\n\n## A\n\n### Notes\n\n## B\n\n### Notes\n";
+        let (_, _, errors) = md_to_value(md, &options("mapping:\n  sections: title\n"), None);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_start_level_and_default_title() {
+        let md = "# 2024-01-02\n\nDone\n\n# 2024-01-03\n\n## Notes\n\nTodo\n";
+        let (value, _, _) = md_to_value(
+            md,
+            &options("mapping:\n  start_level: 1\n  default_title: Daily Log\n"),
+            None,
+        );
+
+        assert_eq!(value["title"], "Daily Log");
+        assert_eq!(value["sections"][0]["title"], "2024-01-02");
+        assert_eq!(value["sections"][1]["sections"][0]["title"], "Notes");
+
+        // H3から始まる場合、H2もトップレベルのセクションとして扱う
+        let (value, _, _) = md_to_value(
+            "# Title\n\n## A\n\n### B\n\n#### C\n",
+            &options("mapping:\n  start_level: 3\n"),
+            None,
+        );
+        assert_eq!(value["sections"][0]["title"], "A");
+        assert_eq!(value["sections"][1]["title"], "B");
+        assert_eq!(value["sections"][1]["sections"][0]["title"], "C");
+    }
+
+    #[test]
+    fn test_invalid_mapping() {
+        assert!(TransformOptions::parse(Some("mapping:\n  start_level: 7\n")).is_err());
+        assert!(TransformOptions::parse(Some("mapping:\n  keys: {title: content}\n")).is_err());
+        assert!(TransformOptions::parse(Some("mapping:\n  sections: tree\n")).is_err());
+
+        let error = TransformOptions::parse(Some("mapping:\n  start_level: 0\n")).unwrap_err();
+        assert!(matches!(error.code, ErrorCode::InvalidOptions));
+        assert_eq!(error.path, "/mapping");
+    }
+}
@@ -3,227 +3,1751 @@
 //! Markdown形式とYAML形式の相互変換を行うモジュール。
 //! - Markdownテキスト → YAML構造データの変換
 //! - YAML構造データ → Markdownテキストの変換
-//! - ヘッダー部分とコンテンツ部分の分離・結合処理
+//! - ヘッダー部分とコンテンツ部分の分離・結合処理（フロントマターのフィールドの取り込み）
 //! - 見出し構造のYAML階層構造への変換
+//! - リスト・表・コードブロック・`Key: value` フィールドの構造化（`TransformOptions` で有効化）
 
-use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ops::Range;
 
-#[derive(Debug, Default, Serialize)]
-struct Section {
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, ErrorInfo};
+use crate::error_code::ErrorCode;
+use crate::frontmatter::{locate_frontmatter, parse_fields, FieldSpans};
+use crate::md_mapping::HeadingMapping;
+use crate::yaml_span::{escape_pointer_segment, SourceSpan};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Section {
     title: String,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    fields: serde_json::Map<String, serde_json::Value>,
     #[serde(default)]
     content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    items: Vec<ListItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tables: Vec<Table>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    code_blocks: Vec<CodeBlock>,
     // always include sections field
     #[serde(default)]
     sections: Vec<Section>,
 }
 
-#[derive(Debug, Default, Serialize)]
-struct Document {
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Document {
     title: String,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    fields: serde_json::Map<String, serde_json::Value>,
     #[serde(default)]
     content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    items: Vec<ListItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tables: Vec<Table>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    code_blocks: Vec<CodeBlock>,
     // always include sections field
     #[serde(default)]
     sections: Vec<Section>,
 }
 
+/// リストの要素（`lists` オプション有効時）
+///
+/// - 通常の項目 → 文字列
+/// - タスクリストの項目 → `{text, checked}`
+/// - 入れ子のリスト → 親項目の直後に置かれる配列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ListItem {
+    Text(String),
+    Task { text: String, checked: bool },
+    List(Vec<ListItem>),
+}
+
+/// Markdown → YAML変換のオプション
+///
+/// # フィールド
+/// - `lists`: 箇条書き・番号付きリストを `content` から取り出し、`items` 配列として出力する
+/// - `tables`: GFMの表を `content` から取り出し、`tables` 配列として出力する
+/// - `code_blocks`: コードブロック（フェンス・インデント）を `content` から取り出し、
+///   `code_blocks` 配列として出力する
+/// - `fields`: 本文先頭の `Key: value` 行と定義リストを `fields` オブジェクトとして出力する
+///   （`true` またはフィールド名・見出しを限定する `FieldOptions`）
+/// - `mapping`: 見出しマッピング設定（キー名・起点の見出しレベル・セクション形式など）
+/// - `frontmatter`: フロントマターのフィールドの取り込み方法
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransformOptions {
+    pub lists: bool,
+    pub tables: bool,
+    pub code_blocks: bool,
+    #[serde(deserialize_with = "deserialize_fields")]
+    pub fields: Option<FieldOptions>,
+    pub mapping: HeadingMapping,
+    pub frontmatter: FrontmatterOptions,
+}
+
+/// 本文先頭のフィールドの取り出し設定
+///
+/// `fields: true` はすべての本文から、すべての `Key: value` 行を取り出す。
+/// `Note: ...` のような散文の行をフィールドとして取り出さないよう、
+/// `keys` / `sections` で対象を限定できる
+///
+/// # フィールド
+/// - `keys`: 取り出すフィールド名（省略時はすべて）。それ以外の行が現れたところで取り出しを止める
+/// - `sections`: 取り出す本文の見出しテキスト（タイトルを含む。省略時はすべての本文）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldOptions {
+    pub keys: Option<Vec<String>>,
+    pub sections: Option<Vec<String>>,
+}
+
+impl FieldOptions {
+    /// 見出しテキストが `title` の本文からフィールドを取り出すかどうか
+    fn applies_to(&self, title: &str) -> bool {
+        self.sections
+            .as_ref()
+            .is_none_or(|sections| sections.iter().any(|section| section == title))
+    }
+
+    /// `key` をフィールドとして取り出すかどうか
+    fn accepts(&self, key: &str) -> bool {
+        self.keys
+            .as_ref()
+            .is_none_or(|keys| keys.iter().any(|k| k == key))
+    }
+}
+
+/// `fields` オプションを読む（`true` / `false`、または `FieldOptions`）
+fn deserialize_fields<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<FieldOptions>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Fields {
+        Enabled(bool),
+        Options(FieldOptions),
+    }
+
+    Ok(match Fields::deserialize(deserializer)? {
+        Fields::Enabled(enabled) => enabled.then(FieldOptions::default),
+        Fields::Options(options) => Some(options),
+    })
+}
+
+/// フロントマターの取り込み方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontmatterMerge {
+    /// 取り込まない（標準）
+    #[default]
+    None,
+    /// `key` で指定したキーの下にまとめて出力する
+    Key,
+    /// ドキュメントのトップレベルに展開する（見出しから生成したキーと重なる場合は見出し側を優先）
+    TopLevel,
+}
+
+/// フロントマターの取り込み設定
+///
+/// # フィールド
+/// - `merge`: 取り込み方法
+/// - `key`: `merge: key` の場合の出力キー（標準は `frontmatter`）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrontmatterOptions {
+    pub merge: FrontmatterMerge,
+    pub key: String,
+}
+
+impl Default for FrontmatterOptions {
+    fn default() -> Self {
+        Self {
+            merge: FrontmatterMerge::None,
+            key: "frontmatter".to_string(),
+        }
+    }
+}
+
+impl TransformOptions {
+    /// YAML/JSON形式のオプション文字列をパースする（未指定・空文字列の場合はデフォルト）
+    pub fn parse(options: Option<&str>) -> Result<TransformOptions, ErrorInfo> {
+        match options.filter(|o| !o.trim().is_empty()) {
+            Some(o) => {
+                let invalid = |e: serde_yaml::Error, code| {
+                    let mut error = ErrorInfo::from_yaml_error(&e, code);
+                    error.message = format!("変換オプションが不正です: {}", e);
+                    error
+                };
+                // YAMLとしての構文エラーと、オプションの値の誤りを区別する
+                serde_yaml::from_str::<serde_yaml::Value>(o)
+                    .map_err(|e| invalid(e, ErrorCode::YamlParse))?;
+                let options = serde_yaml::from_str::<Option<TransformOptions>>(o)
+                    .map(Option::unwrap_or_default)
+                    .map_err(|e| invalid(e, ErrorCode::InvalidOptions))?;
+                options.mapping.check().map_err(|message| {
+                    ErrorInfo::new(
+                        0,
+                        format!("変換オプションが不正です: {}", message),
+                        "/mapping",
+                        ErrorCode::InvalidOptions,
+                    )
+                })?;
+                Ok(options)
+            }
+            None => Ok(TransformOptions::default()),
+        }
+    }
+}
+
 /// -------------------------
 /// フロントマター除去
 /// -------------------------
+#[cfg(test)]
 fn remove_frontmatter(md: &str) -> String {
-    let mut lines = md.lines();
+    md[frontmatter_len(md)..].to_string()
+}
+
+/// -------------------------
+/// ソースマップ
+/// -------------------------
+/// Markdown上の行範囲（1始まり、両端を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LineRange {
+    pub start_line: u32,
+    pub end_line: u32,
+}
 
-    if lines.next().map(|l| l.trim()) == Some("---") {
-        for l in lines.by_ref() {
-            if l.trim() == "---" {
+impl LineRange {
+    /// Markdown上の位置をSourceSpanに変換する（開始行の先頭から終了行の末尾まで）
+    pub fn span(self, md: &str) -> SourceSpan {
+        let mut span = SourceSpan {
+            start_line: self.start_line,
+            start_column: 1,
+            end_line: self.end_line,
+            ..SourceSpan::default()
+        };
+        let mut offset = 0;
+        for (idx, line) in md.split_inclusive('\n').enumerate() {
+            let line_no = idx as u32 + 1;
+            let content = line.trim_end_matches(['\n', '\r']);
+            if line_no == self.start_line {
+                span.start_offset = offset as u32;
+            }
+            if line_no == self.end_line {
+                span.end_column = content.chars().count() as u32 + 1;
+                span.end_offset = (offset + content.len()) as u32;
                 break;
             }
+            offset += line.len();
         }
-        lines.collect::<Vec<_>>().join("\n")
-    } else {
-        md.to_owned()
+        span
     }
 }
 
-pub fn md_headings_to_yaml(md: &str) -> String {
-    // 1. フロントマターを落とす
-    let cleaned_md = remove_frontmatter(md);
-    let trimmed_md = cleaned_md.trim();
+/// 生成したYAMLノードのパス（JSON Pointer）→ Markdown上の行範囲の対応表
+///
+/// 行番号はフロントマターを含む元のMarkdownに対するもの
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct SourceMap {
+    entries: BTreeMap<String, LineRange>,
+}
 
-    // データ構造を構築
-    let mut document = Document::default();
-    let mut found_title = false;
+impl SourceMap {
+    fn insert(&mut self, path: String, start_idx: usize, end_idx: usize) {
+        self.entries.insert(
+            path,
+            LineRange {
+                start_line: start_idx as u32 + 1,
+                end_line: end_idx.max(start_idx) as u32 + 1,
+            },
+        );
+    }
+
+    /// 全てのパスを変換したソースマップを返す
+    pub(crate) fn map_paths(self, mut f: impl FnMut(&str) -> String) -> SourceMap {
+        SourceMap {
+            entries: self
+                .entries
+                .into_iter()
+                .map(|(path, range)| (f(&path), range))
+                .collect(),
+        }
+    }
 
-    // マークダウンの行ごとの処理
-    let lines: Vec<&str> = trimmed_md.lines().collect();
+    /// パスに対応する行範囲を返す。対応がない場合は最も近い祖先ノードの行範囲を返す
+    pub fn locate(&self, path: &str) -> Option<LineRange> {
+        let mut current = path;
+        loop {
+            if let Some(range) = self.entries.get(current) {
+                return Some(*range);
+            }
+            match current.rfind('/') {
+                Some(idx) => current = &current[..idx],
+                None => return None,
+            }
+        }
+    }
+}
 
-    // 各行を解析して見出しレベルを判定
-    let mut i = 0;
-    let mut doc_content = String::new();
-
-    // マークダウンから見出し構造を抽出する関数
-    fn extract_headings(
-        lines: &[&str],
-        start_idx: &mut usize,
-        _current_level: usize,
-        target_level: usize,
-    ) -> Vec<Section> {
-        let mut sections = Vec::new();
-        let mut current_section: Option<Section> = None;
-        let mut section_content = String::new();
-
-        while *start_idx < lines.len() {
-            let line = lines[*start_idx].trim();
-
-            // 見出しレベルを判定
-            let heading_level = if line.starts_with("##### ") {
-                5
-            } else if line.starts_with("#### ") {
-                4
-            } else if line.starts_with("### ") {
-                3
-            } else if line.starts_with("## ") {
-                2
-            } else if line.starts_with("# ") {
-                1
-            } else {
-                0 // 見出しでない
-            };
+/// バイトオフセットと行番号の対応
+struct LineIndex<'a> {
+    src: &'a str,
+    /// 各行の開始バイトオフセット
+    starts: Vec<usize>,
+}
 
-            // 現在の見出しと同じか上のレベルなら、処理を終了
-            if heading_level > 0 && heading_level <= target_level {
-                // 現在のセクションをコンテンツと一緒に保存して終了
-                if let Some(mut section) = current_section.take() {
-                    section.content = section_content.trim().to_string();
-                    sections.push(section);
-                }
-                return sections;
+impl<'a> LineIndex<'a> {
+    fn new(src: &'a str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(src.match_indices('\n').map(|(i, _)| i + 1));
+        Self { src, starts }
+    }
+
+    /// オフセットを含む行のインデックス（0始まり）
+    fn line_of(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    fn line(&self, idx: usize) -> &'a str {
+        let start = self.starts[idx];
+        let end = self.starts.get(idx + 1).copied().unwrap_or(self.src.len());
+        &self.src[start..end]
+    }
+
+    /// `start..end` の範囲にある空でない行の最初と最後のインデックス
+    fn nonblank_lines(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        if start >= end {
+            return None;
+        }
+        let from = self.line_of(start);
+        let to = self.line_of(end - 1);
+        let is_text = |&i: &usize| !self.line(i).trim().is_empty();
+        let first = (from..=to).find(is_text)?;
+        let last = (from..=to).rev().find(is_text)?;
+        Some((first, last))
+    }
+}
+
+/// フロントマター部分のバイト長を返す（フロントマターがなければ0）
+fn frontmatter_len(md: &str) -> usize {
+    locate_frontmatter(md).map_or(0, |block| block.end)
+}
+
+/// フロントマターのフィールドをドキュメントに取り込む
+///
+/// フィールドの位置はソースマップに記録し、YAMLとして不正な場合は `errors` に追加する
+fn merge_frontmatter(
+    md: &str,
+    options: &FrontmatterOptions,
+    document: &mut serde_json::Value,
+    source_map: &mut SourceMap,
+    errors: &mut Vec<ErrorInfo>,
+) {
+    use serde_json::Value;
+
+    if options.merge == FrontmatterMerge::None {
+        return;
+    }
+    let Some(block) = locate_frontmatter(md) else {
+        return;
+    };
+    let line = block.body_line;
+    let frontmatter = match parse_fields(md, &block)
+        .and_then(|v| serde_json::to_value(v).map_err(CoreError::from))
+    {
+        Ok(Value::Null) => Value::Object(serde_json::Map::new()),
+        Ok(v) => v,
+        Err(error) => {
+            errors.push(ErrorInfo::from_core_error(
+                &error,
+                ErrorCode::FrontmatterParse,
+            ));
+            return;
+        }
+    };
+    let Value::Object(document_fields) = document else {
+        return;
+    };
+
+    // フィールドの位置（キーの行から値の最終行まで）
+    let spans = FieldSpans::parse(block.format, &md[block.body.clone()]);
+    let field_lines = |key: &str| {
+        let start = spans.key(key)?;
+        let end = spans.value(key).unwrap_or(start);
+        Some((
+            line + start.start_line as usize - 1,
+            line + end.end_line as usize - 1,
+        ))
+    };
+    let prefix = match options.merge {
+        FrontmatterMerge::Key => format!("/{}", escape_pointer_segment(&options.key)),
+        _ => String::new(),
+    };
+    if let Value::Object(fields) = &frontmatter {
+        for key in fields.keys() {
+            if let Some((first, last)) = field_lines(key) {
+                source_map.insert(
+                    format!("{}/{}", prefix, escape_pointer_segment(key)),
+                    first,
+                    last,
+                );
             }
+        }
+    }
 
-            // 見出しレベルに基づいて処理を分岐
-            match heading_level.cmp(&(target_level + 1)) {
-                std::cmp::Ordering::Equal => {
-                    // 対象レベルの1つ下の見出しを検出
-                    // 前のセクションがあれば保存
-                    if let Some(mut section) = current_section.take() {
-                        section.content = section_content.trim().to_string();
-                        sections.push(section);
-                    }
+    match (options.merge, frontmatter) {
+        (FrontmatterMerge::Key, frontmatter) => {
+            let last = LineIndex::new(md).line_of(frontmatter_len(md) - 1);
+            source_map.insert(prefix, 0, last);
+            document_fields.insert(options.key.clone(), frontmatter);
+        }
+        (FrontmatterMerge::TopLevel, Value::Object(fields)) => {
+            // フロントマターのフィールドを先頭に置き、見出しから生成したキーを優先する
+            let mut merged: serde_json::Map<String, Value> = fields
+                .into_iter()
+                .filter(|(key, _)| !document_fields.contains_key(key))
+                .collect();
+            merged.append(document_fields);
+            *document_fields = merged;
+        }
+        _ => errors.push(ErrorInfo::new(
+            line as u32,
+            "フロントマターをトップレベルに展開するには、マッピング（キーと値の組）である必要があります",
+            "",
+            ErrorCode::FrontmatterParse,
+        )),
+    }
+}
 
-                    // 新しいセクションを作成
-                    let prefix = &line[0..heading_level];
-                    let title = line.strip_prefix(prefix).unwrap_or(line).trim().to_string();
-                    current_section = Some(Section {
-                        title,
-                        content: String::new(),
-                        sections: Vec::new(),
-                    });
-                    section_content = String::new();
-                }
-                std::cmp::Ordering::Greater => {
-                    // さらに下の階層の見出しを検出した場合は再帰的に処理
-                    // 前のセクションがなければ作成
-                    if current_section.is_none() {
-                        current_section = Some(Section {
-                            title: String::new(),
-                            content: section_content.trim().to_string(),
-                            sections: Vec::new(),
+/// -------------------------
+/// 見出しの抽出
+/// -------------------------
+/// 本文中の見出し（pulldown-cmarkのイベントから抽出）
+struct Heading {
+    level: usize,
+    title: String,
+    /// 見出し要素のバイト範囲（セットext見出しの下線を含む）
+    start: usize,
+    end: usize,
+}
+
+/// 本文中の構造化ブロック（オプションに応じて `content` から取り出される）
+struct Block {
+    /// ブロックのバイト範囲
+    range: Range<usize>,
+    kind: BlockKind,
+    /// ブロック内の要素の位置
+    /// （出力先配列内のインデックス、それ以降のJSON Pointerセグメント、バイト範囲）
+    positions: Vec<(usize, Vec<String>, Range<usize>)>,
+    /// ブロック内のエラー（要素のJSON Pointerセグメント、メッセージ、バイト範囲）
+    errors: Vec<(Vec<String>, String, Range<usize>)>,
+}
+
+enum BlockKind {
+    List(Vec<ListItem>),
+    Table(Table),
+    Code(CodeBlock),
+}
+
+impl BlockKind {
+    /// ブロックの出力先となるフィールド名
+    fn key(&self) -> &'static str {
+        match self {
+            BlockKind::List(_) => "items",
+            BlockKind::Table(_) => "tables",
+            BlockKind::Code(_) => "code_blocks",
+        }
+    }
+}
+
+impl Block {
+    /// 範囲を `offset` バイトずらす
+    fn shifted(mut self, offset: usize) -> Block {
+        self.range = self.range.start + offset..self.range.end + offset;
+        for (_, _, range) in &mut self.positions {
+            *range = range.start + offset..range.end + offset;
+        }
+        for (_, _, range) in &mut self.errors {
+            *range = range.start + offset..range.end + offset;
+        }
+        self
+    }
+}
+
+/// イベントのバイト範囲を、隣接・重複するものはまとめながら追加する
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+        _ => ranges.push(range),
+    }
+}
+
+/// インライン要素の範囲から、元のMarkdown記法のままのテキストを取り出す（改行は空白1つにまとめる）
+fn inline_text(md: &str, ranges: Vec<Range<usize>>) -> String {
+    ranges
+        .into_iter()
+        .map(|r| md[r].lines().map(str::trim).collect::<Vec<_>>().join(" "))
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 構築中のリスト
+struct ListFrame {
+    /// 最上位のリストからのインデックス列
+    path: Vec<usize>,
+    items: Vec<ListItem>,
+    current: Option<OpenItem>,
+}
+
+/// 構築中のリスト項目
+struct OpenItem {
+    range: Range<usize>,
+    /// 項目のテキスト部分（入れ子のリストとタスクマーカーを除く）
+    text: Vec<Range<usize>>,
+    checked: Option<bool>,
+    nested: Vec<Vec<ListItem>>,
+}
+
+/// トップレベルのリストをイベント列から組み立てる
+struct ListBuilder {
+    range: Range<usize>,
+    frames: Vec<ListFrame>,
+    positions: Vec<(usize, Vec<String>, Range<usize>)>,
+}
+
+impl ListBuilder {
+    fn new(range: Range<usize>) -> Self {
+        Self {
+            range,
+            frames: vec![ListFrame {
+                path: Vec::new(),
+                items: Vec::new(),
+                current: None,
+            }],
+            positions: Vec::new(),
+        }
+    }
+
+    /// イベントを1つ処理する。最上位のリストが閉じたらブロックを返す
+    fn push(&mut self, md: &str, event: Event, range: Range<usize>) -> Option<Block> {
+        match event {
+            Event::Start(Tag::List(_)) => {
+                let parent = self.frames.last()?;
+                let index = parent.items.len()
+                    + 1
+                    + parent.current.as_ref().map_or(0, |item| item.nested.len());
+                let mut path = parent.path.clone();
+                path.push(index);
+                self.record(&path, range);
+                self.frames.push(ListFrame {
+                    path,
+                    items: Vec::new(),
+                    current: None,
+                });
+            }
+            Event::End(Tag::List(_)) => {
+                let frame = self.frames.pop()?;
+                match self.frames.last_mut() {
+                    Some(parent) => {
+                        if let Some(item) = &mut parent.current {
+                            item.nested.push(frame.items);
+                        }
+                    }
+                    None => {
+                        return Some(Block {
+                            range: self.range.clone(),
+                            kind: BlockKind::List(frame.items),
+                            positions: std::mem::take(&mut self.positions),
+                            errors: Vec::new(),
                         });
-                        section_content = String::new();
                     }
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let frame = self.frames.last_mut()?;
+                frame.current = Some(OpenItem {
+                    range,
+                    text: Vec::new(),
+                    checked: None,
+                    nested: Vec::new(),
+                });
+            }
+            Event::End(Tag::Item) => {
+                let frame = self.frames.last_mut()?;
+                let item = frame.current.take()?;
+                let mut path = frame.path.clone();
+                path.push(frame.items.len());
+
+                let text = inline_text(md, item.text);
+                frame.items.push(match item.checked {
+                    Some(checked) => ListItem::Task { text, checked },
+                    None => ListItem::Text(text),
+                });
+                frame
+                    .items
+                    .extend(item.nested.into_iter().map(ListItem::List));
+                self.record(&path, item.range);
+            }
+            Event::TaskListMarker(checked) => {
+                if let Some(item) = self.frames.last_mut()?.current.as_mut() {
+                    item.checked = Some(checked);
+                }
+            }
+            _ => {
+                // 項目のテキスト部分（元のMarkdown記法のまま取り出す）
+                if let Some(item) = self.frames.last_mut()?.current.as_mut() {
+                    push_range(&mut item.text, range);
+                }
+            }
+        }
+        None
+    }
 
-                    // 現在の位置を記録
-                    let current_pos = *start_idx;
+    /// 項目（または入れ子のリスト）の位置を記録する
+    fn record(&mut self, path: &[usize], range: Range<usize>) {
+        let rest = path[1..].iter().map(|i| i.to_string()).collect();
+        self.positions.push((path[0], rest, range));
+    }
+}
 
-                    // 子セクションを再帰的に処理
-                    let sub_sections =
-                        extract_headings(lines, start_idx, heading_level, target_level + 1);
+/// GFMの表（`tables` オプション有効時）
+///
+/// 各行は列名 → セルの値のオブジェクト。セルの値は文字列のままで、空のセルはnullにする
+/// （数値・真偽値への変換はスキーマが型を指定している場合のみ、`coerce_to_schema`）。
+/// 列名が重複している場合は最初の列の値を使い、エラーとして報告する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Table {
+    columns: Vec<String>,
+    rows: Vec<serde_json::Map<String, serde_json::Value>>,
+}
 
-                    // 子セクションを現在のセクションに追加
-                    if let Some(section) = &mut current_section {
-                        section.sections = sub_sections;
-                    }
+/// 表のセル・フィールドのテキストを値に変換する（空のテキストはnull、それ以外は文字列のまま）
+///
+/// `007` や `1.10` の桁が失われないよう、数値・真偽値への変換はスキーマに任せる（`coerce_to_schema`）
+fn cell_value(text: &str) -> serde_json::Value {
+    match text {
+        "" => serde_json::Value::Null,
+        _ => serde_json::Value::String(text.to_string()),
+    }
+}
 
-                    // 再帰呼び出しが位置を進めなかった場合は、自分で進める
-                    if current_pos == *start_idx {
-                        *start_idx += 1;
-                    }
+/// トップレベルの表をイベント列から組み立てる
+struct TableBuilder {
+    range: Range<usize>,
+    columns: Vec<String>,
+    rows: Vec<serde_json::Map<String, serde_json::Value>>,
+    /// 構築中の行のセル
+    cells: Vec<String>,
+    /// 構築中のセルのテキスト部分
+    cell: Option<Vec<Range<usize>>>,
+    row_range: Range<usize>,
+    positions: Vec<(usize, Vec<String>, Range<usize>)>,
+    errors: Vec<(Vec<String>, String, Range<usize>)>,
+}
 
-                    continue;
+impl TableBuilder {
+    fn new(range: Range<usize>) -> Self {
+        Self {
+            positions: vec![(0, Vec::new(), range.clone())],
+            range,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            cells: Vec::new(),
+            cell: None,
+            row_range: 0..0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// イベントを1つ処理する。表が閉じたらブロックを返す
+    fn push(&mut self, md: &str, event: Event, range: Range<usize>) -> Option<Block> {
+        match event {
+            Event::Start(Tag::TableHead | Tag::TableRow) => self.row_range = range,
+            Event::End(Tag::TableHead) => {
+                self.columns = std::mem::take(&mut self.cells);
+                for (i, column) in self.columns.iter().enumerate() {
+                    if self.columns[..i].contains(column) {
+                        self.errors.push((
+                            vec!["columns".to_string(), i.to_string()],
+                            format!("表の列名「{}」が重複しています。行は列名をキーとするため、列名は一意である必要があります", column),
+                            self.row_range.clone(),
+                        ));
+                    }
                 }
-                std::cmp::Ordering::Less => {
-                    // 普通のテキスト行
-                    if current_section.is_some() {
-                        // 現在のセクションにコンテンツとして追加
-                        if !section_content.is_empty() {
-                            section_content.push('\n');
-                        }
-                        section_content.push_str(line);
-                    } else {
-                        // セクション外のテキストは上位レベルのコンテンツに
-                        if !section_content.is_empty() {
-                            section_content.push('\n');
-                        }
-                        section_content.push_str(line);
+            }
+            Event::End(Tag::TableRow) => {
+                let cells = std::mem::take(&mut self.cells);
+                let row_index = self.rows.len().to_string();
+                let mut row = serde_json::Map::new();
+                for (i, column) in self.columns.iter().enumerate() {
+                    // 重複した列は最初の列の値を使う
+                    if row.contains_key(column) {
+                        continue;
                     }
+                    let value = cells
+                        .get(i)
+                        .map_or(serde_json::Value::Null, |c| cell_value(c));
+                    row.insert(column.clone(), value);
+                    self.positions.push((
+                        0,
+                        vec![
+                            "rows".to_string(),
+                            row_index.clone(),
+                            escape_pointer_segment(column),
+                        ],
+                        self.row_range.clone(),
+                    ));
+                }
+                self.positions.push((
+                    0,
+                    vec!["rows".to_string(), row_index],
+                    self.row_range.clone(),
+                ));
+                self.rows.push(row);
+            }
+            Event::Start(Tag::TableCell) => self.cell = Some(Vec::new()),
+            Event::End(Tag::TableCell) => {
+                let text = inline_text(md, self.cell.take().unwrap_or_default());
+                self.cells.push(text.replace("\\|", "|"));
+            }
+            Event::End(Tag::Table(_)) => {
+                return Some(Block {
+                    range: self.range.clone(),
+                    kind: BlockKind::Table(Table {
+                        columns: std::mem::take(&mut self.columns),
+                        rows: std::mem::take(&mut self.rows),
+                    }),
+                    positions: std::mem::take(&mut self.positions),
+                    errors: std::mem::take(&mut self.errors),
+                });
+            }
+            _ => {
+                // セル内のインライン要素（元のMarkdown記法のまま取り出す）
+                if let Some(cell) = &mut self.cell {
+                    push_range(cell, range);
                 }
             }
+        }
+        None
+    }
+}
+
+/// コードブロック（`code_blocks` オプション有効時）
+///
+/// # フィールド
+/// - `lang`: フェンスの情報文字列の最初の単語（インデントコードブロックや指定なしの場合はnull）
+/// - `code`: コード本文（末尾の改行は除く）
+/// - `line`: ブロック開始行（フロントマターを含む元のMarkdown上の行番号、1始まり）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CodeBlock {
+    lang: Option<String>,
+    code: String,
+    #[serde(default)]
+    line: u32,
+}
+
+/// トップレベルのコードブロックをイベント列から組み立てる
+struct CodeBlockBuilder {
+    range: Range<usize>,
+    lang: Option<String>,
+    code: String,
+}
 
-            *start_idx += 1;
+impl CodeBlockBuilder {
+    fn new(range: Range<usize>, kind: &CodeBlockKind) -> Self {
+        let lang = match kind {
+            CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(str::to_string),
+            CodeBlockKind::Indented => None,
+        };
+        Self {
+            range,
+            lang,
+            code: String::new(),
         }
+    }
 
-        // 最後のセクションを追加
-        if let Some(mut section) = current_section {
-            section.content = section_content.trim().to_string();
-            sections.push(section);
+    /// イベントを1つ処理する。コードブロックが閉じたらブロックを返す
+    fn push(&mut self, event: Event) -> Option<Block> {
+        match event {
+            Event::Text(text) => self.code.push_str(&text),
+            Event::End(Tag::CodeBlock(_)) => {
+                let code = std::mem::take(&mut self.code);
+                return Some(Block {
+                    range: self.range.clone(),
+                    kind: BlockKind::Code(CodeBlock {
+                        lang: self.lang.take(),
+                        code: code.strip_suffix('\n').unwrap_or(&code).to_string(),
+                        // 行番号はフロントマターを含む位置が決まってから設定する
+                        line: 0,
+                    }),
+                    positions: vec![(0, Vec::new(), self.range.clone())],
+                    errors: Vec::new(),
+                });
+            }
+            _ => {}
         }
+        None
+    }
+}
 
-        sections
+/// 構築中の構造化ブロック
+enum BlockBuilder {
+    List(ListBuilder),
+    Table(TableBuilder),
+    Code(CodeBlockBuilder),
+}
+
+impl BlockBuilder {
+    fn push(&mut self, md: &str, event: Event, range: Range<usize>) -> Option<Block> {
+        match self {
+            BlockBuilder::List(builder) => builder.push(md, event, range),
+            BlockBuilder::Table(builder) => builder.push(md, event, range),
+            BlockBuilder::Code(builder) => builder.push(event),
+        }
     }
+}
 
-    // まず最初のH1を探してタイトルとして使用
-    while i < lines.len() {
-        let line = lines[i].trim();
-        if let Some(title_text) = line.strip_prefix("# ") {
-            document.title = title_text.trim().to_string();
-            found_title = true;
-            i += 1;
-            break;
+/// トップレベルの見出しと構造化ブロックを抽出する
+///
+/// コードブロック内の `#` 行は見出しとして扱われず、引用やリストの中の見出しも
+/// セクションを区切らない。ATX見出しの閉じ `#` 列は除かれ、セットext見出しにも対応する。
+fn scan(md: &str, options: &TransformOptions) -> (Vec<Heading>, Vec<Block>) {
+    let parser_options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let mut headings = Vec::new();
+    let mut blocks = Vec::new();
+    let mut builder: Option<BlockBuilder> = None;
+    let mut container_depth = 0usize;
+    // 見出しレベル、見出し範囲、インライン要素の範囲
+    let mut current: Option<(usize, Range<usize>, Option<Range<usize>>)> = None;
+
+    for (event, range) in Parser::new_ext(md, parser_options).into_offset_iter() {
+        if let Some(active) = &mut builder {
+            if let Some(block) = active.push(md, event, range) {
+                blocks.push(block);
+                builder = None;
+            }
+            continue;
         }
-        i += 1;
+
+        match event {
+            Event::Start(Tag::List(_)) if options.lists && container_depth == 0 => {
+                builder = Some(BlockBuilder::List(ListBuilder::new(range)));
+            }
+            Event::Start(Tag::Table(_)) if options.tables && container_depth == 0 => {
+                builder = Some(BlockBuilder::Table(TableBuilder::new(range)));
+            }
+            Event::Start(Tag::CodeBlock(kind)) if options.code_blocks && container_depth == 0 => {
+                builder = Some(BlockBuilder::Code(CodeBlockBuilder::new(range, &kind)));
+            }
+            Event::Start(
+                Tag::BlockQuote | Tag::List(_) | Tag::Item | Tag::FootnoteDefinition(_),
+            ) => {
+                container_depth += 1;
+            }
+            Event::End(Tag::BlockQuote | Tag::List(_) | Tag::Item | Tag::FootnoteDefinition(_)) => {
+                container_depth -= 1;
+            }
+            Event::Start(Tag::Heading(level, ..)) if container_depth == 0 => {
+                current = Some((level as usize, range, None));
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, heading_range, inline)) = current.take() {
+                    let title = inline
+                        .map(|r| md[r].lines().map(str::trim).collect::<Vec<_>>().join(" "))
+                        .unwrap_or_default();
+                    headings.push(Heading {
+                        level,
+                        title: title.trim().to_string(),
+                        start: heading_range.start,
+                        end: heading_range.end,
+                    });
+                }
+            }
+            _ => {
+                // 見出し内のインライン要素（元のMarkdown記法のまま取り出す）
+                if let Some((_, _, inline)) = &mut current {
+                    *inline = Some(match inline.take() {
+                        Some(r) => r.start..range.end.max(r.end),
+                        None => range,
+                    });
+                }
+            }
+        }
+    }
+
+    (headings, blocks)
+}
+
+/// ブロックのテキストを整形する
+///
+/// 前後の空行と行末の空白を除き、共通のインデントを取り除く（相対的なインデントは保持）
+fn block_text(src: &str) -> String {
+    let lines: Vec<&str> = src.lines().map(str::trim_end).collect();
+    let first = match lines.iter().position(|l| !l.is_empty()) {
+        Some(i) => i,
+        None => return String::new(),
+    };
+    let last = lines.iter().rposition(|l| !l.is_empty()).unwrap_or(first);
+    let lines = &lines[first..=last];
+
+    let indent = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|l| if l.is_empty() { *l } else { &l[indent..] })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 見出しに続く本文（次の見出しまで）を構造化した結果
+#[derive(Default)]
+struct Body {
+    fields: serde_json::Map<String, serde_json::Value>,
+    content: String,
+    items: Vec<ListItem>,
+    tables: Vec<Table>,
+    code_blocks: Vec<CodeBlock>,
+    /// 本文の変換時のエラー（表の列名の重複など）
+    errors: Vec<ErrorInfo>,
+}
+
+/// `Key: value` 行のキーと値を取り出す
+///
+/// キーは文字で始まり、英数字・空白・`_` `-` `.` からなる64文字以内のもの。
+/// コロンの後は空白か行末である必要がある（URLなどを誤検出しないため）
+fn split_field(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    if !(value.is_empty() || value.starts_with([' ', '\t'])) {
+        return None;
     }
+    let key = key.trim_end();
+    let valid = key.chars().next().is_some_and(char::is_alphabetic)
+        && key.chars().count() <= 64
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.'));
+    valid.then(|| (key, value.trim()))
+}
+
+/// 定義リストの定義行（`: 定義`）の内容を取り出す
+fn definition(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix(':')?;
+    (rest.is_empty() || rest.starts_with([' ', '\t'])).then(|| rest.trim())
+}
 
-    // タイトルが見つからなければデフォルト値を設定
-    if !found_title {
-        document.title = "Untitled Document".to_string();
-        i = 0; // 最初から処理
+/// 本文先頭の `Key: value` 行と定義リストをフィールドとして取り出す
+///
+/// 空行を挟んで続く限り取り出し、それ以外の行（`options.keys` にないフィールド名の行を含む）が
+/// 現れたところで止める。
+///
+/// # 戻り値
+/// * フィールド名、値、行のバイト範囲の一覧と、取り出した部分の終端オフセット
+fn leading_fields(
+    md: &str,
+    range: Range<usize>,
+    options: &FieldOptions,
+) -> (Vec<(String, serde_json::Value, Range<usize>)>, usize) {
+    let mut lines = Vec::new();
+    let mut offset = range.start;
+    for line in md[range.clone()].split_inclusive('\n') {
+        lines.push((
+            line.trim_end_matches(['\n', '\r']),
+            offset..offset + line.len(),
+        ));
+        offset += line.len();
     }
 
-    // タイトルと最初のH2の間のテキストはドキュメントコンテンツ
-    let content_start = i;
+    let mut fields = Vec::new();
+    let mut end = range.start;
+    let mut i = 0;
     while i < lines.len() {
-        let line = lines[i].trim();
-        if line.starts_with("## ") {
+        let (line, line_range) = &lines[i];
+        if line.trim().is_empty() {
+            i += 1;
+            continue;
+        }
+        // インデントされた行（コードブロックなど）は対象外
+        if line.starts_with([' ', '\t']) {
+            break;
+        }
+
+        let definitions: Vec<&str> = lines[i + 1..]
+            .iter()
+            .map_while(|(l, _)| definition(l))
+            .collect();
+        if !definitions.is_empty() && definition(line).is_none() && options.accepts(line.trim()) {
+            // 定義リスト（定義が複数あれば配列）
+            let values: Vec<serde_json::Value> =
+                definitions.iter().map(|d| cell_value(d)).collect();
+            let value = match values.len() {
+                1 => values.into_iter().next().unwrap_or_default(),
+                _ => serde_json::Value::Array(values),
+            };
+            i += 1 + definitions.len();
+            end = lines[i - 1].1.end;
+            fields.push((line.trim().to_string(), value, line_range.start..end));
+        } else if let Some((key, value)) = split_field(line).filter(|(key, _)| options.accepts(key))
+        {
+            fields.push((key.to_string(), cell_value(value), line_range.clone()));
+            end = line_range.end;
+            i += 1;
+        } else {
             break;
         }
-        i += 1;
     }
+    (fields, end)
+}
 
-    // ドキュメントコンテンツを抽出
-    if i > content_start {
-        doc_content = lines[content_start..i].join("\n").trim().to_string();
+/// 本文の範囲から構造化ブロックを取り出し、残りのテキストを `content` にする
+///
+/// `range` は見出しを除いた本文部分のバイト範囲（`md` 上の位置）。
+/// `path` は本文を持つノード（ドキュメントは空文字列）のJSON Pointer。
+/// `fields` はこの本文からフィールドを取り出す場合の設定
+fn build_body(
+    md: &str,
+    range: Range<usize>,
+    blocks: &[Block],
+    index: &LineIndex,
+    path: &str,
+    source_map: &mut SourceMap,
+    fields: Option<&FieldOptions>,
+) -> Body {
+    let mut body = Body::default();
+    let mut segments = Vec::new();
+    let mut cursor = range.start;
+
+    if let Some(field_options) = fields {
+        let (fields, end) = leading_fields(md, range.clone(), field_options);
+        if let Some((first, last)) = index.nonblank_lines(range.start, end) {
+            source_map.insert(format!("{}/fields", path), first, last);
+        }
+        for (key, value, field_range) in fields {
+            let pointer = format!("{}/fields/{}", path, escape_pointer_segment(&key));
+            let lines = index.nonblank_lines(field_range.start, field_range.end);
+            // 同じフィールド名が繰り返された場合は最初の値を使う
+            if body.fields.contains_key(&key) {
+                let error = ErrorInfo::new(
+                    0,
+                    format!("フィールド「{}」が重複しています。同じ本文の中でフィールド名は一意である必要があります", key),
+                    pointer,
+                    ErrorCode::DuplicateKey,
+                );
+                body.errors.push(match lines {
+                    Some((first, last)) => error.with_span(
+                        LineRange {
+                            start_line: first as u32 + 1,
+                            end_line: last as u32 + 1,
+                        }
+                        .span(md),
+                    ),
+                    None => error,
+                });
+                continue;
+            }
+            if let Some((first, last)) = lines {
+                source_map.insert(pointer, first, last);
+            }
+            body.fields.insert(key, value);
+        }
+        cursor = end;
+    }
+    let content_start = cursor;
+    // 出力先フィールドごとのブロック全体の範囲
+    let mut extents: BTreeMap<&str, Range<usize>> = BTreeMap::new();
+
+    for block in blocks
+        .iter()
+        .filter(|b| b.range.start >= range.start && b.range.end <= range.end)
+    {
+        segments.push(&md[cursor..block.range.start]);
+        cursor = block.range.end;
+
+        let base = match &block.kind {
+            BlockKind::List(items) => {
+                let base = body.items.len();
+                body.items.extend(items.iter().cloned());
+                base
+            }
+            BlockKind::Table(table) => {
+                body.tables.push(table.clone());
+                body.tables.len() - 1
+            }
+            BlockKind::Code(code_block) => {
+                body.code_blocks.push(CodeBlock {
+                    line: index.line_of(block.range.start) as u32 + 1,
+                    ..code_block.clone()
+                });
+                body.code_blocks.len() - 1
+            }
+        };
+        let key = block.kind.key();
+        for (rest, message, error_range) in &block.errors {
+            let mut pointer = format!("{}/{}/{}", path, key, base);
+            for segment in rest {
+                pointer.push('/');
+                pointer.push_str(segment);
+            }
+            let error = ErrorInfo::new(0, message.clone(), pointer, ErrorCode::DuplicateKey);
+            body.errors.push(
+                match index.nonblank_lines(error_range.start, error_range.end) {
+                    Some((first, last)) => error.with_span(
+                        LineRange {
+                            start_line: first as u32 + 1,
+                            end_line: last as u32 + 1,
+                        }
+                        .span(md),
+                    ),
+                    None => error,
+                },
+            );
+        }
+        for (i, rest, element_range) in &block.positions {
+            let mut pointer = format!("{}/{}/{}", path, key, base + i);
+            for segment in rest {
+                pointer.push('/');
+                pointer.push_str(segment);
+            }
+            if let Some((first, last)) =
+                index.nonblank_lines(element_range.start, element_range.end)
+            {
+                source_map.insert(pointer, first, last);
+            }
+        }
+        extents
+            .entry(key)
+            .and_modify(|r| r.end = block.range.end)
+            .or_insert_with(|| block.range.clone());
+    }
+    segments.push(&md[cursor..range.end]);
+
+    // 取り除いたブロックの前後の段落は空行1行で区切る
+    body.content = block_text(
+        &segments
+            .iter()
+            .map(|s| block_text(s))
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    );
+    if let Some((first, last)) = index.nonblank_lines(content_start, range.end) {
+        source_map.insert(format!("{}/content", path), first, last);
+    }
+    for (key, extent) in extents {
+        if let Some((first, last)) = index.nonblank_lines(extent.start, extent.end) {
+            source_map.insert(format!("{}/{}", path, key), first, last);
+        }
     }
+    body
+}
+
+#[cfg(test)]
+fn md_headings_to_yaml(md: &str) -> String {
+    md_headings_to_yaml_with_options(md, &TransformOptions::default())
+}
 
-    // 残りはセクションとして処理
-    let mut start_idx = content_start;
-    document.sections = extract_headings(&lines, &mut start_idx, 0, 1);
-    document.content = doc_content;
+/// オプションを指定してMarkdownの見出し構造をYAMLに変換する
+pub fn md_headings_to_yaml_with_options(md: &str, options: &TransformOptions) -> String {
+    let (document, _, _) = md_to_value(md, options, None);
 
     // YAMLに変換して返す
     serde_yaml::to_string(&document).unwrap_or_else(|e| format!("Error serializing to YAML: {}", e))
 }
 
+/// Markdownの見出し構造をYAMLに変換し、ソースマップと共にJSON文字列で返す
+///
+/// # 引数
+/// * `md` - Markdown文字列
+/// * `options` - 変換オプション（YAML/JSON形式、任意）
+///
+/// # 戻り値
+/// * `{"yaml": "...", "source_map": {"/title": {"start_line": 1, "end_line": 1}, ...}, "errors": []}`
+/// * オプションが不正な場合は `yaml` が空文字列で、`errors` にエラー情報が入る
+/// * 見出しの重複（`mapping.sections: title`）は `errors` に入る（`yaml` には最初の見出しのみ残る）
+pub fn md_headings_to_yaml_with_source_map(md: &str, options: Option<&str>) -> String {
+    #[derive(Serialize)]
+    struct Output {
+        yaml: String,
+        source_map: SourceMap,
+        errors: Vec<ErrorInfo>,
+    }
+
+    let output = match TransformOptions::parse(options) {
+        Ok(options) => {
+            let (document, source_map, errors) = md_to_value(md, &options, None);
+            Output {
+                yaml: serde_yaml::to_string(&document)
+                    .unwrap_or_else(|e| format!("Error serializing to YAML: {}", e)),
+                source_map,
+                errors,
+            }
+        }
+        Err(error) => Output {
+            yaml: String::new(),
+            source_map: SourceMap::default(),
+            errors: vec![error],
+        },
+    };
+    serde_json::to_string(&output).unwrap_or_else(|e| format!("Error serializing to JSON: {}", e))
+}
+
+/// -------------------------
+/// YAML → Markdown
+/// -------------------------
+/// 逆変換の結果
+///
+/// # フィールド
+/// - `success`: 変換できた場合はtrue
+/// - `markdown`: 生成したMarkdown文字列（失敗時は空文字列）
+/// - `errors`: エラー情報の配列（成功時は空配列）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownResult {
+    pub success: bool,
+    pub markdown: String,
+    pub errors: Vec<ErrorInfo>,
+}
+
+impl MarkdownResult {
+    pub(crate) fn error(error: ErrorInfo) -> Self {
+        Self {
+            success: false,
+            markdown: String::new(),
+            errors: vec![error],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            r#"{"success":false,"markdown":"","errors":[{"line":0,"message":"Failed to serialize errors","path":""}]}"#.to_string()
+        })
+    }
+}
+
+/// `md_headings_to_yaml` 形式のYAMLをMarkdownに戻し、結果をJSON文字列で返す
+///
+/// # 引数
+/// * `yaml` - `title` / `content` / `sections` を持つYAML文字列
+/// * `frontmatter` - フロントマターとして先頭に付与するYAML文字列（任意）
+///
+/// # 戻り値
+/// * 成功時: `{"success": true, "markdown": "...", "errors": []}`
+/// * 失敗時: `{"success": false, "markdown": "", "errors": [ErrorInfo, ...]}`
+pub fn yaml_to_md(yaml: &str, frontmatter: Option<&str>) -> String {
+    let document: Document = match serde_yaml::from_str(yaml) {
+        Ok(d) => d,
+        Err(e) => {
+            return MarkdownResult::error(ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse))
+                .to_json()
+        }
+    };
+
+    let frontmatter = match frontmatter.filter(|fm| !fm.trim().is_empty()) {
+        Some(fm) => match serde_yaml::from_str::<serde_yaml::Mapping>(fm) {
+            Ok(mapping) => Some(mapping),
+            Err(e) => {
+                return MarkdownResult::error(ErrorInfo::from_yaml_error(
+                    &e,
+                    ErrorCode::FrontmatterParse,
+                ))
+                .to_json();
+            }
+        },
+        None => None,
+    };
+
+    MarkdownResult {
+        success: true,
+        markdown: render_document(&document, frontmatter.as_ref()),
+        errors: vec![],
+    }
+    .to_json()
+}
+
+/// ドキュメントを正規化されたMarkdownとして出力する
+///
+/// - ブロック（見出し・本文）の間は空行1行で区切る
+/// - 見出しはATX形式（`#`）で、セクションの深さに応じてH2〜H6を使う（H6より深い場合はH6）
+/// - `md_to_document` の出力をこの関数で戻すと、再変換で同じドキュメントになる
+pub(crate) fn render_document(
+    document: &Document,
+    frontmatter: Option<&serde_yaml::Mapping>,
+) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+
+    if let Some(mapping) = frontmatter {
+        let yaml = serde_yaml::to_string(mapping).unwrap_or_default();
+        blocks.push(format!("---\n{}---", yaml));
+    }
+
+    blocks.push(heading_line(1, &document.title));
+    if !document.fields.is_empty() {
+        blocks.push(render_fields(&document.fields));
+    }
+    if !document.content.is_empty() {
+        blocks.push(document.content.clone());
+    }
+    if !document.items.is_empty() {
+        blocks.push(render_items(&document.items, 0).join("\n"));
+    }
+    blocks.extend(document.tables.iter().map(render_table));
+    blocks.extend(document.code_blocks.iter().map(render_code_block));
+
+    fn render_sections(sections: &[Section], level: usize, blocks: &mut Vec<String>) {
+        for section in sections {
+            blocks.push(heading_line(level, &section.title));
+            if !section.fields.is_empty() {
+                blocks.push(render_fields(&section.fields));
+            }
+            if !section.content.is_empty() {
+                blocks.push(section.content.clone());
+            }
+            if !section.items.is_empty() {
+                blocks.push(render_items(&section.items, 0).join("\n"));
+            }
+            blocks.extend(section.tables.iter().map(render_table));
+            blocks.extend(section.code_blocks.iter().map(render_code_block));
+            render_sections(&section.sections, (level + 1).min(6), blocks);
+        }
+    }
+    render_sections(&document.sections, 2, &mut blocks);
+
+    let mut md = blocks.join("\n\n");
+    md.push('\n');
+    md
+}
+
+/// リストの要素を箇条書きの行として出力する（入れ子のリストは2スペースずつ字下げ）
+fn render_items(items: &[ListItem], depth: usize) -> Vec<String> {
+    let indent = "  ".repeat(depth);
+    items
+        .iter()
+        .flat_map(|item| match item {
+            ListItem::Text(text) => vec![format!("{}- {}", indent, text)],
+            ListItem::Task { text, checked } => {
+                vec![format!(
+                    "{}- [{}] {}",
+                    indent,
+                    if *checked { "x" } else { " " },
+                    text
+                )]
+            }
+            ListItem::List(nested) => render_items(nested, depth + 1),
+        })
+        .collect()
+}
+
+/// フィールドを `Key: value` 行として出力する（複数の値は定義リスト）
+fn render_fields(fields: &serde_json::Map<String, serde_json::Value>) -> String {
+    let text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        v => v.to_string(),
+    };
+    fields
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::Array(values) => std::iter::once(key.clone())
+                .chain(values.iter().map(|v| format!(": {}", text(v))))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            value => format!("{}: {}", key, text(value)).trim_end().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 表をGFMの表として出力する
+fn render_table(table: &Table) -> String {
+    let cell = |value: Option<&serde_json::Value>| match value {
+        Some(serde_json::Value::String(s)) => s.replace('|', "\\|"),
+        Some(serde_json::Value::Null) | None => String::new(),
+        Some(v) => v.to_string(),
+    };
+    let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+    let mut lines = vec![
+        row(table
+            .columns
+            .iter()
+            .map(|c| c.replace('|', "\\|"))
+            .collect()),
+        row(table.columns.iter().map(|_| "---".to_string()).collect()),
+    ];
+    lines.extend(
+        table
+            .rows
+            .iter()
+            .map(|r| row(table.columns.iter().map(|c| cell(r.get(c))).collect())),
+    );
+    lines.join("\n")
+}
+
+/// コードブロックをフェンス付きコードブロックとして出力する
+///
+/// コード中にバッククォートの連続がある場合は、それより長いフェンスを使う
+fn render_code_block(code_block: &CodeBlock) -> String {
+    let longest_run = code_block
+        .code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        code_block.lang.as_deref().unwrap_or(""),
+        code_block.code,
+        fence
+    )
+}
+
+/// ATX見出し行を生成する（無題の場合は `#` のみ）
+fn heading_line(level: usize, title: &str) -> String {
+    let marker = "#".repeat(level);
+    if title.is_empty() {
+        marker
+    } else {
+        format!("{} {}", marker, title)
+    }
+}
+
+/// 構築中のセクション
+struct OpenSection {
+    level: usize,
+    path: String,
+    section: Section,
+    /// 見出しの開始行・終了行のインデックス
+    heading_lines: (usize, usize),
+}
+
+/// Markdownから、見出しマッピング設定を適用したYAMLの値とソースマップを構築する
+///
+/// # 引数
+/// * `schema` - 検証に使うスキーマ（指定した場合、Markdownのテキストから生成した値を
+///   スキーマが指定する型に変換する。`coerce_to_schema` を参照）
+///
+/// # 戻り値
+/// * YAMLの値、ソースマップ、変換時のエラー（見出しや表の列名の重複など。位置はMarkdown上の行）
+pub(crate) fn md_to_value(
+    md: &str,
+    options: &TransformOptions,
+    schema: Option<&serde_json::Value>,
+) -> (serde_json::Value, SourceMap, Vec<ErrorInfo>) {
+    let (document, source_map, errors) = md_to_document(md, options);
+    let value = serde_json::to_value(&document).unwrap_or_default();
+    let (mut value, mut source_map, mut errors) =
+        options.mapping.apply(md, value, source_map, errors);
+    // フロントマターの値は型を持つため、取り込む前に変換する
+    if let Some(schema) = schema {
+        coerce_to_schema(&mut value, schema, schema, 0);
+    }
+    merge_frontmatter(
+        md,
+        &options.frontmatter,
+        &mut value,
+        &mut source_map,
+        &mut errors,
+    );
+    (value, source_map, errors)
+}
+
+/// スキーマの参照（`$ref`）と `allOf` をたどる深さの上限（循環参照対策）
+const MAX_SCHEMA_DEPTH: usize = 32;
+
+/// Markdownのテキストから生成した文字列を、スキーマが指定する型に変換する
+///
+/// スキーマの `type` が文字列を許可せず `integer` / `number` / `boolean` を要求し、
+/// テキストがその型の正規の表記（`7`, `-1.5`, `true` など）の場合だけ変換する。
+/// `007` や `+1` のように変換すると元の表記が失われるものは文字列のまま残す（スキーマの検証で報告される）
+fn coerce_to_schema(
+    value: &mut serde_json::Value,
+    schema: &serde_json::Value,
+    root: &serde_json::Value,
+    depth: usize,
+) {
+    use serde_json::Value;
+
+    let mut schemas = Vec::new();
+    collect_schemas(schema, root, depth, &mut schemas);
+    match value {
+        Value::String(text) => {
+            if let Some(coerced) = coerce_text(text, &schemas) {
+                *value = coerced;
+            }
+        }
+        Value::Object(fields) => {
+            for (key, child) in fields.iter_mut() {
+                for schema in &schemas {
+                    let child_schema = schema
+                        .get("properties")
+                        .and_then(|properties| properties.get(key.as_str()))
+                        .or_else(|| schema.get("additionalProperties").filter(|s| s.is_object()));
+                    if let Some(child_schema) = child_schema {
+                        coerce_to_schema(child, child_schema, root, depth + 1);
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                for schema in &schemas {
+                    let item_schema = match (schema.get("prefixItems"), schema.get("items")) {
+                        (Some(Value::Array(prefix)), items) => prefix.get(i).or(items),
+                        (_, Some(Value::Array(tuple))) => tuple.get(i),
+                        (_, items) => items,
+                    };
+                    if let Some(item_schema) = item_schema.filter(|s| s.is_object()) {
+                        coerce_to_schema(item, item_schema, root, depth + 1);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 値に適用されるスキーマ（`$ref` の参照先と `allOf` の各スキーマを含む）を集める
+fn collect_schemas<'a>(
+    schema: &'a serde_json::Value,
+    root: &'a serde_json::Value,
+    depth: usize,
+    schemas: &mut Vec<&'a serde_json::Value>,
+) {
+    if depth > MAX_SCHEMA_DEPTH || !schema.is_object() {
+        return;
+    }
+    schemas.push(schema);
+    let target = schema
+        .get("$ref")
+        .and_then(serde_json::Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer));
+    if let Some(target) = target {
+        collect_schemas(target, root, depth + 1, schemas);
+    }
+    for member in schema
+        .get("allOf")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        collect_schemas(member, root, depth + 1, schemas);
+    }
+}
+
+/// テキストを、スキーマが要求する型の値に変換する（変換しない場合はNone）
+fn coerce_text(text: &str, schemas: &[&serde_json::Value]) -> Option<serde_json::Value> {
+    let mut types: Vec<&str> = Vec::new();
+    for schema in schemas {
+        match schema.get("type") {
+            Some(serde_json::Value::String(t)) => types.push(t),
+            Some(serde_json::Value::Array(ts)) => {
+                types.extend(ts.iter().filter_map(serde_json::Value::as_str))
+            }
+            _ => {}
+        }
+    }
+    if types.contains(&"string") {
+        return None;
+    }
+    // JSONの数値の表記は正規の表記（符号 `+` や先頭の0を含まない）
+    let number = serde_json::from_str::<serde_json::Number>(text)
+        .ok()
+        .filter(|_| text.trim() == text);
+    match number {
+        Some(n) if types.contains(&"integer") && (n.is_i64() || n.is_u64()) => {
+            Some(serde_json::Value::Number(n))
+        }
+        Some(n) if types.contains(&"number") => Some(serde_json::Value::Number(n)),
+        _ if types.contains(&"boolean") => text.parse::<bool>().ok().map(serde_json::Value::Bool),
+        _ => None,
+    }
+}
+
+/// Markdownから見出し構造のドキュメントとソースマップを構築する
+///
+/// - 最初のH1 → `title`（見つからなければ `mapping.default_title`）
+/// - タイトルと最初の見出しの間のテキスト → `content`
+/// - H2以降 → 見出しレベルに応じて入れ子になった `sections`
+///   （レベルが飛んだ場合は無題のセクションで補う。タイトル以降のH1はH2として扱う）
+/// - `mapping.start_level` を指定した場合は、そのレベルの見出しがトップレベルのセクションになる
+///   （それより上位の見出しは同じレベルとして扱い、1の場合はH1もセクションになる）
+///
+/// # 戻り値
+/// * ドキュメント、ソースマップ、本文の変換時のエラー（表の列名の重複など）
+pub(crate) fn md_to_document(
+    md: &str,
+    options: &TransformOptions,
+) -> (Document, SourceMap, Vec<ErrorInfo>) {
+    // 1. フロントマターを落とす（オフセットは元のMarkdown上の位置で扱う）
+    let body_start = frontmatter_len(md);
+    let body = &md[body_start..];
+    let index = LineIndex::new(md);
+
+    let mut document = Document::default();
+    let mut source_map = SourceMap::default();
+
+    let (headings, blocks) = scan(body, options);
+    let blocks: Vec<Block> = blocks.into_iter().map(|b| b.shifted(body_start)).collect();
+
+    // 最初のH1をタイトルとして使用（それより前のテキストと見出しは対象外）
+    let start_level = options.mapping.start_level;
+    let title_pos = headings
+        .iter()
+        .position(|h| h.level == 1)
+        .filter(|_| start_level > 1);
+    let (content_start, rest) = match title_pos {
+        Some(pos) => {
+            let heading = &headings[pos];
+            document.title = heading.title.clone();
+            let (first, last) = (
+                index.line_of(body_start + heading.start),
+                index.line_of(body_start + heading.end - 1),
+            );
+            source_map.insert("/title".to_string(), first, last);
+            (heading.end, &headings[pos + 1..])
+        }
+        None => {
+            // タイトルが見つからなければデフォルト値を設定
+            document.title = options.mapping.default_title.clone();
+            (0, &headings[..])
+        }
+    };
+
+    // タイトルと最初の見出しの間のテキストはドキュメントコンテンツ
+    let content_end = rest.first().map_or(body.len(), |h| h.start);
+    let content = build_body(
+        md,
+        body_start + content_start..body_start + content_end,
+        &blocks,
+        &index,
+        "",
+        &mut source_map,
+        options
+            .fields
+            .as_ref()
+            .filter(|f| f.applies_to(&document.title)),
+    );
+    let mut errors = content.errors;
+    document.fields = content.fields;
+    document.content = content.content;
+    document.items = content.items;
+    document.tables = content.tables;
+    document.code_blocks = content.code_blocks;
+
+    // 残りの見出しをスタックで入れ子にする
+    let mut stack: Vec<OpenSection> = Vec::new();
+
+    // スタック先頭のセクションを閉じて親に追加する（`end` はセクション範囲の終端オフセット）
+    let close = |stack: &mut Vec<OpenSection>,
+                 document: &mut Document,
+                 source_map: &mut SourceMap,
+                 end: usize| {
+        let open = stack.pop().expect("section stack is not empty");
+        let (heading_first, heading_last) = open.heading_lines;
+        let last = index
+            .nonblank_lines(index.starts[heading_first], end)
+            .map_or(heading_last, |(_, last)| last);
+        source_map.insert(open.path.clone(), heading_first, last);
+        source_map.insert(format!("{}/title", open.path), heading_first, heading_last);
+        match stack.last_mut() {
+            Some(parent) => parent.section.sections.push(open.section),
+            None => document.sections.push(open.section),
+        }
+    };
+
+    for (i, heading) in rest.iter().enumerate() {
+        let level = heading.level.max(start_level);
+        let start = body_start + heading.start;
+
+        // 同じか上位レベルのセクションを閉じる
+        while stack.last().is_some_and(|open| open.level >= level) {
+            close(&mut stack, &mut document, &mut source_map, start);
+        }
+
+        // セクションの本文（次の見出しまで）
+        let content_end = rest.get(i + 1).map_or(body.len(), |h| h.start);
+        let heading_lines = (
+            index.line_of(start),
+            index.line_of(body_start + heading.end - 1),
+        );
+
+        // レベルが飛んでいる場合は無題のセクションで補う
+        let parent_level = stack.last().map_or(start_level - 1, |open| open.level);
+        for placeholder_level in parent_level + 1..=level {
+            let sibling_count = stack
+                .last()
+                .map_or(document.sections.len(), |open| open.section.sections.len());
+            let path = format!(
+                "{}/sections/{}",
+                stack.last().map_or("", |open| open.path.as_str()),
+                sibling_count
+            );
+            let section = if placeholder_level == level {
+                let content = build_body(
+                    md,
+                    body_start + heading.end..body_start + content_end,
+                    &blocks,
+                    &index,
+                    &path,
+                    &mut source_map,
+                    options
+                        .fields
+                        .as_ref()
+                        .filter(|f| f.applies_to(&heading.title)),
+                );
+                errors.extend(content.errors);
+                Section {
+                    title: heading.title.clone(),
+                    fields: content.fields,
+                    content: content.content,
+                    items: content.items,
+                    tables: content.tables,
+                    code_blocks: content.code_blocks,
+                    sections: Vec::new(),
+                }
+            } else {
+                Section::default()
+            };
+            stack.push(OpenSection {
+                level: placeholder_level,
+                path,
+                section,
+                heading_lines,
+            });
+        }
+    }
+
+    // 残りのセクションを閉じる
+    while !stack.is_empty() {
+        close(&mut stack, &mut document, &mut source_map, md.len());
+    }
+
+    // ドキュメント全体
+    if let Some((first, last)) = index.nonblank_lines(body_start, md.len()) {
+        source_map.insert(String::new(), first, last);
+    }
+
+    (document, source_map, errors)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,6 +1802,23 @@ Nested content"#;
         assert!(yaml.contains("sections: []"));
     }
 
+    #[test]
+    fn test_remove_frontmatter_only_at_start() {
+        // 文書途中の水平線はフロントマターではない
+        let md = "Intro\n\n---\n\nkey: value\n\n---\n\n# Title\n";
+        assert_eq!(remove_frontmatter(md), md);
+
+        // 閉じられていない `---` も本文として扱う
+        let md = "---\n# Title\nContent\n";
+        assert_eq!(remove_frontmatter(md), md);
+
+        // BOMと `...` の閉じ行
+        let md = "\u{feff}---\nschema_path: a.yaml\n...\n# Title\n";
+        assert_eq!(remove_frontmatter(md), "# Title\n");
+        let (document, _, _) = md_to_document(md, &TransformOptions::default());
+        assert_eq!(document.title, "Title");
+    }
+
     #[test]
     fn test_remove_frontmatter() {
         let md_with_frontmatter = r#"---
@@ -329,43 +1870,43 @@ Content
     #[test]
     fn hierarchical_conversion() {
         let md = r#"---
-    schema_path: ./schema.yaml
-    validated: true
-    ---
-    # Sample Note with Deep Nesting
+schema_path: ./schema.yaml
+validated: true
+---
+# Sample Note with Deep Nesting
 
-    This is the main document content.
+This is the main document content.
 
-    ## Introduction
-    This is a sample note.
+## Introduction
+This is a sample note.
 
-    ## Features
-    Shows appropriate error messages
+## Features
+Shows appropriate error messages
 
-    ### Advanced Features
-    These are advanced features.
+### Advanced Features
+These are advanced features.
 
-    #### Sub-feature 1
-    This is a sub-feature.
+#### Sub-feature 1
+This is a sub-feature.
 
-    ##### Detail Point 1
-    Very detailed explanation.
+##### Detail Point 1
+Very detailed explanation.
 
-    ##### Detail Point 2
-    Another detailed explanation.
+##### Detail Point 2
+Another detailed explanation.
 
-    #### Sub-feature 2
-    Another sub-feature.
+#### Sub-feature 2
+Another sub-feature.
 
-    ### Basic Features
-    These are basic features.
+### Basic Features
+These are basic features.
 
-    ## Conclusion
-    The relative schema path feature makes the note more portable.
+## Conclusion
+The relative schema path feature makes the note more portable.
 
-    ### Final Thoughts
-    Some final thoughts.
-    "#;
+### Final Thoughts
+Some final thoughts.
+"#;
 
         // デバッグ用：元のMarkdownを出力
         eprintln!("ORIGINAL MARKDOWN (Cleaned):\n{}", remove_frontmatter(md));
@@ -379,6 +1920,7 @@ Content
                     title: "Introduction".to_string(),
                     content: "This is a sample note.".to_string(),
                     sections: vec![],
+                    ..Default::default()
                 },
                 Section {
                     title: "Features".to_string(),
@@ -396,27 +1938,34 @@ Content
                                             title: "Detail Point 1".to_string(),
                                             content: "Very detailed explanation.".to_string(),
                                             sections: vec![],
+                                            ..Default::default()
                                         },
                                         Section {
                                             title: "Detail Point 2".to_string(),
                                             content: "Another detailed explanation.".to_string(),
                                             sections: vec![],
+                                            ..Default::default()
                                         },
                                     ],
+                                    ..Default::default()
                                 },
                                 Section {
                                     title: "Sub-feature 2".to_string(),
                                     content: "Another sub-feature.".to_string(),
                                     sections: vec![],
+                                    ..Default::default()
                                 },
                             ],
+                            ..Default::default()
                         },
                         Section {
                             title: "Basic Features".to_string(),
                             content: "These are basic features.".to_string(),
                             sections: vec![],
+                            ..Default::default()
                         },
                     ],
+                    ..Default::default()
                 },
                 Section {
                     title: "Conclusion".to_string(),
@@ -426,9 +1975,12 @@ Content
                         title: "Final Thoughts".to_string(),
                         content: "Some final thoughts.".to_string(),
                         sections: vec![],
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
             ],
+            ..Default::default()
         };
 
         // 期待値をYAMLに変換
@@ -442,4 +1994,724 @@ Content
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_source_map_lines() {
+        let md = "---\nschema_path: ./schema.yaml\n---\n# Title\n\nIntro text\n\n## Section 1\nLine A\nLine B\n### Sub\nNested\n## Section 2\n";
+
+        let (_, source_map, _) = md_to_document(md, &TransformOptions::default());
+        let range = |path: &str| {
+            let r = source_map.locate(path).unwrap();
+            (r.start_line, r.end_line)
+        };
+
+        assert_eq!(range("/title"), (4, 4));
+        assert_eq!(range("/content"), (6, 6));
+        assert_eq!(range("/sections/0"), (8, 12));
+        assert_eq!(range("/sections/0/title"), (8, 8));
+        assert_eq!(range("/sections/0/content"), (9, 10));
+        assert_eq!(range("/sections/0/sections/0"), (11, 12));
+        assert_eq!(range("/sections/0/sections/0/content"), (12, 12));
+        assert_eq!(range("/sections/1"), (13, 13));
+        // 存在しないノードは祖先の行範囲にフォールバックする
+        assert_eq!(range("/sections/1/content"), (13, 13));
+    }
+
+    #[test]
+    fn test_md_headings_to_yaml_with_source_map() {
+        let json = md_headings_to_yaml_with_source_map("# Title\n## Section\nBody\n", None);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert!(value["yaml"].as_str().unwrap().contains("title: Title"));
+        assert_eq!(value["source_map"]["/sections/0/content"]["start_line"], 3);
+    }
+
+    fn to_value(md: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(&md_headings_to_yaml(md)).unwrap()
+    }
+
+    #[test]
+    fn test_hash_lines_in_code_blocks_are_not_headings() {
+        let md = "# Title\n## Setup\n```bash\n# install dependencies\npnpm install\n```\n## Usage\nRun it\n";
+        let value = to_value(md);
+
+        let sections = value["sections"].as_sequence().unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0]["title"], "Setup");
+        assert_eq!(
+            sections[0]["content"],
+            "```bash\n# install dependencies\npnpm install\n```"
+        );
+        assert_eq!(sections[1]["title"], "Usage");
+    }
+
+    #[test]
+    fn test_setext_headings() {
+        let md = "Main Title\n==========\n\nIntro\n\nFirst Section\n-------------\nBody\n";
+        let value = to_value(md);
+
+        assert_eq!(value["title"], "Main Title");
+        assert_eq!(value["content"], "Intro");
+        assert_eq!(value["sections"][0]["title"], "First Section");
+        assert_eq!(value["sections"][0]["content"], "Body");
+    }
+
+    #[test]
+    fn test_closing_hash_sequence_and_h6() {
+        let md = "# Title #\n## Section ##\n### A\n#### B\n##### C\n###### Deepest ######\nLeaf\n";
+        let value = to_value(md);
+
+        assert_eq!(value["title"], "Title");
+        let section = &value["sections"][0];
+        assert_eq!(section["title"], "Section");
+        let deepest = &section["sections"][0]["sections"][0]["sections"][0]["sections"][0];
+        assert_eq!(deepest["title"], "Deepest");
+        assert_eq!(deepest["content"], "Leaf");
+    }
+
+    #[test]
+    fn test_skipped_heading_level_gets_untitled_parent() {
+        let md = "# Title\n## Section\n#### Deep\nBody\n";
+        let value = to_value(md);
+
+        let placeholder = &value["sections"][0]["sections"][0];
+        assert_eq!(placeholder["title"], "");
+        assert_eq!(placeholder["sections"][0]["title"], "Deep");
+        assert_eq!(placeholder["sections"][0]["content"], "Body");
+    }
+
+    #[test]
+    fn test_headings_in_blockquotes_do_not_split_sections() {
+        let md = "# Title\n## Quote\n> ## Not a section\n> quoted\n";
+        let value = to_value(md);
+
+        let sections = value["sections"].as_sequence().unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0]["content"], "> ## Not a section\n> quoted");
+    }
+
+    #[test]
+    fn test_yaml_to_md() {
+        let yaml = "title: Note\ncontent: Intro\nsections:\n- title: Section 1\n  content: Body\n  sections:\n  - title: Sub\n    content: ''\n    sections: []\n";
+
+        let result: MarkdownResult = serde_json::from_str(&yaml_to_md(yaml, None)).unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.markdown,
+            "# Note\n\nIntro\n\n## Section 1\n\nBody\n\n### Sub\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_to_md_with_frontmatter() {
+        let yaml = "title: Note\n";
+        let frontmatter = "schema_path: ./schema.yaml\nvalidated: true\n";
+
+        let result: MarkdownResult =
+            serde_json::from_str(&yaml_to_md(yaml, Some(frontmatter))).unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.markdown,
+            "---\nschema_path: ./schema.yaml\nvalidated: true\n---\n\n# Note\n"
+        );
+        // 生成したMarkdownはフロントマターを除いて同じドキュメントに戻る
+        assert_eq!(
+            md_headings_to_yaml(&result.markdown),
+            md_headings_to_yaml("# Note\n")
+        );
+    }
+
+    #[test]
+    fn test_yaml_to_md_errors() {
+        let result: MarkdownResult =
+            serde_json::from_str(&yaml_to_md("content: no title\n", None)).unwrap();
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::YamlParse));
+
+        let result: MarkdownResult =
+            serde_json::from_str(&yaml_to_md("title: Note\n", Some("a: [\n"))).unwrap();
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::FrontmatterParse));
+    }
+
+    #[test]
+    fn test_lists_become_items() {
+        let md = "# Note\n\n## Features\n\nSome features:\n\n- Fast\n- Small\n  1. Tiny core\n  2. No deps\n- **Safe**\n\nMore text.\n";
+        let (doc, source_map, _) = md_to_document(
+            md,
+            &TransformOptions {
+                lists: true,
+                ..Default::default()
+            },
+        );
+
+        let features = &doc.sections[0];
+        assert_eq!(features.content, "Some features:\n\nMore text.");
+        assert_eq!(
+            features.items,
+            vec![
+                ListItem::Text("Fast".to_string()),
+                ListItem::Text("Small".to_string()),
+                ListItem::List(vec![
+                    ListItem::Text("Tiny core".to_string()),
+                    ListItem::Text("No deps".to_string()),
+                ]),
+                ListItem::Text("**Safe**".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            source_map.locate("/sections/0/items/1").unwrap().start_line,
+            8
+        );
+        assert_eq!(
+            source_map
+                .locate("/sections/0/items/2/1")
+                .unwrap()
+                .start_line,
+            10
+        );
+        assert_eq!(
+            source_map.locate("/sections/0/items/3").unwrap().start_line,
+            11
+        );
+    }
+
+    #[test]
+    fn test_task_list_items() {
+        let md = "# Todo\n\n- [x] Write spec\n- [ ] Ship it\n";
+        let yaml = md_headings_to_yaml_with_options(
+            md,
+            &TransformOptions {
+                lists: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            yaml,
+            "title: Todo\ncontent: ''\nitems:\n- text: Write spec\n  checked: true\n- text: Ship it\n  checked: false\nsections: []\n"
+        );
+    }
+
+    #[test]
+    fn test_lists_stay_in_content_by_default() {
+        let md = "# Note\n\n- a\n- b\n";
+        let (doc, _, _) = md_to_document(md, &TransformOptions::default());
+
+        assert_eq!(doc.content, "- a\n- b");
+        assert!(doc.items.is_empty());
+        assert!(!md_headings_to_yaml(md).contains("items"));
+    }
+
+    #[test]
+    fn test_invalid_transform_options() {
+        assert!(TransformOptions::parse(Some("lists: true")).unwrap().lists);
+        assert!(!TransformOptions::parse(None).unwrap().lists);
+
+        let error = TransformOptions::parse(Some("{\"list\": true}")).unwrap_err();
+        assert!(matches!(error.code, ErrorCode::InvalidOptions));
+        let error = TransformOptions::parse(Some("lists: [\n")).unwrap_err();
+        assert!(matches!(error.code, ErrorCode::YamlParse));
+
+        let json = md_headings_to_yaml_with_source_map("# Title\n", Some("lists: maybe"));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["yaml"], "");
+        assert_eq!(value["errors"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_tables_become_rows() {
+        let md = "# Meeting\n\n## Attendees\n\nWho came:\n\n| Name | Role | Age | Remote |\n| --- | :---: | ---: | --- |\n| Alice | `lead` | 34 | true |\n| Bob \\| Jr. | dev |  | false |\n";
+        let (doc, source_map, _) = md_to_document(
+            md,
+            &TransformOptions {
+                tables: true,
+                ..Default::default()
+            },
+        );
+
+        let section = &doc.sections[0];
+        assert_eq!(section.content, "Who came:");
+        let table = &section.tables[0];
+        assert_eq!(table.columns, vec!["Name", "Role", "Age", "Remote"]);
+        assert_eq!(
+            serde_json::Value::Array(
+                table
+                    .rows
+                    .iter()
+                    .cloned()
+                    .map(serde_json::Value::Object)
+                    .collect()
+            ),
+            serde_json::json!([
+                {"Name": "Alice", "Role": "`lead`", "Age": "34", "Remote": "true"},
+                {"Name": "Bob | Jr.", "Role": "dev", "Age": null, "Remote": "false"},
+            ])
+        );
+
+        assert_eq!(
+            source_map
+                .locate("/sections/0/tables/0")
+                .unwrap()
+                .start_line,
+            7
+        );
+        assert_eq!(
+            source_map
+                .locate("/sections/0/tables/0/rows/1/Age")
+                .unwrap()
+                .start_line,
+            10
+        );
+    }
+
+    #[test]
+    fn test_table_cells_keep_their_text() {
+        let md = "# Parts
+
+| Code | Count | Answer |
+| --- | --- | --- |
+| 007 | +1 | yes |
+";
+        let (doc, _, errors) = md_to_document(
+            md,
+            &TransformOptions {
+                tables: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            serde_json::Value::Object(doc.tables[0].rows[0].clone()),
+            serde_json::json!({"Code": "007", "Count": "+1", "Answer": "yes"})
+        );
+    }
+
+    #[test]
+    fn test_duplicate_table_columns_are_reported() {
+        let md = "# Log
+
+## Decisions
+
+| Date | Date |
+| --- | --- |
+| 2024-01-02 | 2024-01-03 |
+";
+        let (doc, _, errors) = md_to_document(
+            md,
+            &TransformOptions {
+                tables: true,
+                ..Default::default()
+            },
+        );
+
+        // 最初の列の値を使う
+        assert_eq!(
+            serde_json::Value::Object(doc.sections[0].tables[0].rows[0].clone()),
+            serde_json::json!({"Date": "2024-01-02"})
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].code, ErrorCode::DuplicateKey));
+        assert_eq!(errors[0].path, "/sections/0/tables/0/columns/1");
+        assert_eq!(errors[0].line, 5);
+        assert!(errors[0].message.contains("Date"));
+    }
+
+    #[test]
+    fn test_table_cells_are_coerced_to_schema_types() {
+        let md = "# Parts
+
+| Code | Count | Done |
+| --- | --- | --- |
+| 007 | 3 | true |
+| 008 | 04 | no |
+";
+        let schema = serde_json::json!({
+            "definitions": {"count": {"type": "integer"}},
+            "properties": {"tables": {"items": {"properties": {"rows": {"items": {
+                "properties": {
+                    "Code": {"type": "string"},
+                    "Count": {"$ref": "#/definitions/count"},
+                    "Done": {"type": ["boolean", "null"]}
+                }
+            }}}}}}
+        });
+        let (value, _, _) = md_to_value(
+            md,
+            &TransformOptions {
+                tables: true,
+                ..Default::default()
+            },
+            Some(&schema),
+        );
+
+        assert_eq!(
+            value["tables"][0]["rows"],
+            serde_json::json!([
+                {"Code": "007", "Count": 3, "Done": true},
+                // 正規の表記でない値は文字列のまま（スキーマの検証で報告される）
+                {"Code": "008", "Count": "04", "Done": "no"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_code_blocks_are_extracted() {
+        let md = "---\nschema_path: ./schema.yaml\n---\n# Report\n\n## Query\n\nRun this:\n\n```sql title=\"q\"\n-- # not a heading\nSELECT 1;\n```\n\nThen:\n\n    # indented\n    echo done\n";
+        let (doc, source_map, _) = md_to_document(
+            md,
+            &TransformOptions {
+                code_blocks: true,
+                ..Default::default()
+            },
+        );
+
+        let query = &doc.sections[0];
+        assert_eq!(query.content, "Run this:\n\nThen:");
+        assert_eq!(
+            query.code_blocks,
+            vec![
+                CodeBlock {
+                    lang: Some("sql".to_string()),
+                    code: "-- # not a heading\nSELECT 1;".to_string(),
+                    line: 10,
+                },
+                CodeBlock {
+                    lang: None,
+                    code: "# indented\necho done".to_string(),
+                    line: 17,
+                },
+            ]
+        );
+        assert_eq!(
+            source_map
+                .locate("/sections/0/code_blocks/1")
+                .unwrap()
+                .start_line,
+            17
+        );
+    }
+
+    #[test]
+    fn test_leading_fields_are_extracted() {
+        let md = "# Task\n\n## Review\n\nStatus: done\nOwner: @alice\nPriority: 2\nBlocked: false\nDue:\n\nReviewers\n: Bob\n: Carol\n\nNote: this line is prose.\nSee https://example.com for details.\n";
+        let options = TransformOptions::parse(Some(
+            "fields: {keys: [Status, Owner, Priority, Blocked, Due, Reviewers], sections: [Review]}",
+        ))
+        .unwrap();
+        let (doc, source_map, errors) = md_to_document(md, &options);
+
+        let review = &doc.sections[0];
+        assert_eq!(
+            serde_json::Value::Object(review.fields.clone()),
+            serde_json::json!({
+                "Status": "done",
+                "Owner": "@alice",
+                "Priority": "2",
+                "Blocked": "false",
+                "Due": null,
+                "Reviewers": ["Bob", "Carol"],
+            })
+        );
+        // 設定にないフィールド名の行は本文のまま
+        assert_eq!(
+            review.content,
+            "Note: this line is prose.\nSee https://example.com for details."
+        );
+        assert!(errors.is_empty());
+
+        assert_eq!(
+            source_map
+                .locate("/sections/0/fields/Owner")
+                .unwrap()
+                .start_line,
+            6
+        );
+        let reviewers = source_map.locate("/sections/0/fields/Reviewers").unwrap();
+        assert_eq!((reviewers.start_line, reviewers.end_line), (11, 13));
+        assert_eq!(
+            source_map.locate("/sections/0/content").unwrap().start_line,
+            15
+        );
+
+        // 設定にない見出しの本文からは取り出さない
+        let (doc, _, _) = md_to_document(
+            "# Task\n\nStatus: open\n\n## Review\n\nStatus: done\n",
+            &options,
+        );
+        assert!(doc.fields.is_empty());
+        assert_eq!(doc.content, "Status: open");
+        assert_eq!(doc.sections[0].fields["Status"], "done");
+    }
+
+    #[test]
+    fn test_field_values_keep_their_text() {
+        let md = "# Item\n\nZip: 007\nVersion: 1.10\nID: 1e3\nCount: 2\nDone: true\n";
+        let (doc, _, _) = md_to_document(
+            md,
+            &TransformOptions {
+                fields: Some(FieldOptions::default()),
+                ..Default::default()
+            },
+        );
+
+        // 先頭・末尾の0や指数表記は文字列のまま（桁を失わない）
+        assert_eq!(
+            serde_json::Value::Object(doc.fields.clone()),
+            serde_json::json!({"Zip": "007", "Version": "1.10", "ID": "1e3", "Count": "2", "Done": "true"})
+        );
+
+        // スキーマが型を指定しているフィールドだけ変換する
+        let schema = serde_json::json!({
+            "properties": {
+                "fields": {
+                    "properties": {
+                        "Zip": {"type": "string"},
+                        "Version": {"type": "string"},
+                        "Count": {"type": "integer"},
+                        "Done": {"type": "boolean"},
+                    }
+                }
+            }
+        });
+        let (value, _, _) = md_to_value(
+            md,
+            &TransformOptions {
+                fields: Some(FieldOptions::default()),
+                ..Default::default()
+            },
+            Some(&schema),
+        );
+        assert_eq!(
+            value["fields"],
+            serde_json::json!({"Zip": "007", "Version": "1.10", "ID": "1e3", "Count": 2, "Done": true})
+        );
+    }
+
+    #[test]
+    fn test_duplicate_fields_are_reported() {
+        let md = "# Task\n\n## Review\n\nStatus: done\nOwner: alice\nStatus: todo\n";
+        let (doc, _, errors) = md_to_document(
+            md,
+            &TransformOptions {
+                fields: Some(FieldOptions::default()),
+                ..Default::default()
+            },
+        );
+
+        // 最初の値を使う
+        assert_eq!(doc.sections[0].fields["Status"], "done");
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].code, ErrorCode::DuplicateKey));
+        assert_eq!(errors[0].path, "/sections/0/fields/Status");
+        assert_eq!(errors[0].line, 7);
+
+        // 見出しマッピングを適用した場合は変換後のパスで報告する
+        let options =
+            TransformOptions::parse(Some("fields: true\nmapping: {sections: title}")).unwrap();
+        let (_, _, errors) = md_to_value(md, &options, None);
+        assert_eq!(errors[0].path, "/sections/Review/fields/Status");
+    }
+
+    #[test]
+    fn test_fields_stop_at_first_other_line() {
+        let md = "# Doc\n\nIntro text\nStatus: draft\n\n## Links\n\nhttp://example.com\n";
+        let (doc, _, _) = md_to_document(
+            md,
+            &TransformOptions {
+                fields: Some(FieldOptions::default()),
+                ..Default::default()
+            },
+        );
+
+        assert!(doc.fields.is_empty());
+        assert_eq!(doc.content, "Intro text\nStatus: draft");
+        assert!(doc.sections[0].fields.is_empty());
+
+        // オプションなしでは本文のまま
+        let (doc, _, _) = md_to_document("# Doc\n\nStatus: draft\n", &TransformOptions::default());
+        assert!(doc.fields.is_empty());
+        assert_eq!(doc.content, "Status: draft");
+    }
+
+    #[test]
+    fn test_structured_content_roundtrips_through_yaml_to_md() {
+        let cases = [
+            ("# Note\n\n## Tasks\n\nIntro\n\n- one\n  - [x] nested task\n- two\n", TransformOptions { lists: true, ..Default::default() }),
+            ("# Log\n\n## Decisions\n\n| Date | Decision |\n| --- | --- |\n| 2024-01-02 | Use a \\| b |\n| 2024-01-03 |  |\n", TransformOptions { tables: true, ..Default::default() }),
+            ("# Doc\n\n## Example\n\n````md\n```js\nx\n```\n````\n\n```\nplain\n```\n", TransformOptions { code_blocks: true, ..Default::default() }),
+            ("# Doc\n\nStatus: draft\nVersion: 1.5\n\n## Meeting\n\nAttendees\n: Alice\n: Bob\n\nDiscussed the plan.\n", TransformOptions { fields: Some(FieldOptions::default()), ..Default::default() }),
+        ];
+        for (md, options) in cases {
+            let yaml = md_headings_to_yaml_with_options(md, &options);
+
+            let result: MarkdownResult = serde_json::from_str(&yaml_to_md(&yaml, None)).unwrap();
+            assert!(result.success, "{}", md);
+            assert_eq!(result.markdown, md);
+            assert_eq!(
+                md_headings_to_yaml_with_options(&result.markdown, &options),
+                yaml
+            );
+        }
+    }
+
+    fn merge(yaml: &str) -> TransformOptions {
+        TransformOptions::parse(Some(yaml)).unwrap()
+    }
+
+    #[test]
+    fn test_frontmatter_under_key() {
+        let md = "---\nschema_path: ./schema.yaml\ntags:\n  - rust\n  - wasm\nauthor: alice\n---\n# Note\n\nBody\n";
+        let (value, source_map, errors) =
+            md_to_value(md, &merge("frontmatter: {merge: key, key: meta}"), None);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            value["meta"],
+            serde_json::json!({"schema_path": "./schema.yaml", "tags": ["rust", "wasm"], "author": "alice"})
+        );
+        assert_eq!(value["title"], "Note");
+        let tags = source_map.locate("/meta/tags/1").unwrap();
+        assert_eq!((tags.start_line, tags.end_line), (3, 5));
+        assert_eq!(source_map.locate("/meta/author").unwrap().start_line, 6);
+        assert_eq!(source_map.locate("/meta").unwrap().end_line, 7);
+
+        // 標準では取り込まない
+        let (value, _, _) = md_to_value(md, &TransformOptions::default(), None);
+        assert!(value.get("frontmatter").is_none());
+        assert!(value.get("tags").is_none());
+    }
+
+    #[test]
+    fn test_frontmatter_at_top_level() {
+        let md = "---\ntitle: From frontmatter\ndate: 2024-01-02\n---\n# Heading title\n";
+        let (value, source_map, errors) =
+            md_to_value(md, &merge("frontmatter: {merge: top_level}"), None);
+
+        assert!(errors.is_empty());
+        assert_eq!(value["title"], "Heading title");
+        assert_eq!(value["date"], "2024-01-02");
+        assert_eq!(value.as_object().unwrap().keys().next().unwrap(), "date");
+        assert_eq!(source_map.locate("/date").unwrap().start_line, 3);
+    }
+
+    #[test]
+    fn test_toml_and_json_frontmatter() {
+        let md =
+            "+++\ntags = [\n  \"rust\",\n]\n\n[author]\nname = \"alice\"\n+++\n# Note\n\nBody\n";
+        let (value, source_map, errors) =
+            md_to_value(md, &merge("frontmatter: {merge: key}"), None);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            value["frontmatter"],
+            serde_json::json!({"tags": ["rust"], "author": {"name": "alice"}})
+        );
+        assert_eq!(value["title"], "Note");
+        assert_eq!(value["content"], "Body");
+        let tags = source_map.locate("/frontmatter/tags").unwrap();
+        assert_eq!((tags.start_line, tags.end_line), (2, 4));
+        let author = source_map.locate("/frontmatter/author/name").unwrap();
+        assert_eq!((author.start_line, author.end_line), (6, 7));
+
+        let md = "{\n  \"date\": \"2024-01-02\"\n}\n# Note\n";
+        let (value, source_map, errors) =
+            md_to_value(md, &merge("frontmatter: {merge: top_level}"), None);
+        assert!(errors.is_empty());
+        assert_eq!(value["date"], "2024-01-02");
+        assert_eq!(value["title"], "Note");
+        assert_eq!(source_map.locate("/date").unwrap().start_line, 2);
+    }
+
+    #[test]
+    fn test_invalid_frontmatter_is_reported() {
+        let md = "---\ntitle: ok\ntags: [a\n---\n# Note\n";
+        let (value, _, errors) = md_to_value(md, &merge("frontmatter: {merge: key}"), None);
+
+        assert!(value.get("frontmatter").is_none());
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].code, ErrorCode::FrontmatterParse));
+        assert!(errors[0].line >= 3);
+
+        let (_, _, errors) = md_to_value(
+            "---\n- a\n---\n# Note\n",
+            &merge("frontmatter: {merge: top_level}"),
+            None,
+        );
+        assert!(matches!(errors[0].code, ErrorCode::FrontmatterParse));
+    }
+
+    mod roundtrip {
+        use super::super::*;
+        use proptest::prelude::*;
+
+        /// 見出し・本文として安全な1行のテキスト
+        fn text_line() -> impl Strategy<Value = String> {
+            "[A-Za-z][A-Za-z0-9,.!?() ]{0,30}[A-Za-z0-9.!?)]".prop_map(|s| s.to_string())
+        }
+
+        /// 段落（複数行）を空行で区切った本文
+        fn content() -> impl Strategy<Value = String> {
+            prop::collection::vec(prop::collection::vec(text_line(), 1..3), 0..3).prop_map(
+                |paragraphs| {
+                    paragraphs
+                        .into_iter()
+                        .map(|lines| lines.join("\n"))
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                },
+            )
+        }
+
+        /// 深さ `depth`（0 = H2）のセクション
+        fn section(depth: usize) -> BoxedStrategy<Section> {
+            let children = if depth >= 4 {
+                Just(Vec::new()).boxed()
+            } else {
+                prop::collection::vec(section(depth + 1), 0..3).boxed()
+            };
+            (text_line(), content(), children)
+                .prop_map(|(title, content, sections)| Section {
+                    title,
+                    content,
+                    sections,
+                    ..Default::default()
+                })
+                .boxed()
+        }
+
+        fn document() -> impl Strategy<Value = Document> {
+            (
+                text_line(),
+                content(),
+                prop::collection::vec(section(0), 0..4),
+            )
+                .prop_map(|(title, content, sections)| Document {
+                    title,
+                    content,
+                    sections,
+                    ..Default::default()
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn document_survives_md_roundtrip(doc in document()) {
+                let md = render_document(&doc, None);
+                let (parsed, _, _) = md_to_document(&md, &TransformOptions::default());
+                prop_assert_eq!(parsed, doc);
+            }
+
+            #[test]
+            fn canonical_md_survives_yaml_roundtrip(doc in document()) {
+                let md = render_document(&doc, None);
+                let yaml = md_headings_to_yaml(&md);
+                let result: MarkdownResult = serde_json::from_str(&yaml_to_md(&yaml, None)).unwrap();
+                prop_assert!(result.success);
+                prop_assert_eq!(result.markdown, md);
+            }
+        }
+    }
 }
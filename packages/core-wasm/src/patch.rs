@@ -0,0 +1,228 @@
+//! patch.rs
+//!
+//! YAMLドキュメントに対してパッチを適用するモジュール。
+//! - RFC 6902 JSON Patch（操作の配列）
+//! - RFC 7386 JSON Merge Patch（オブジェクト）
+//!
+//! パッチはJSON/YAMLいずれの形式でも受け付ける。
+//! JSON Patchは全操作がアトミックに適用され、途中で失敗した場合は元のYAMLを返す。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ErrorInfo;
+use crate::error_code::ErrorCode;
+use crate::yaml_span::SpanMap;
+
+/// パッチ適用結果
+///
+/// # フィールド
+/// - `success`: 全ての操作が適用できた場合はtrue
+/// - `yaml`: パッチ適用後のYAML文字列（失敗時は元のYAML文字列）
+/// - `errors`: エラー情報の配列（成功時は空配列）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchResult {
+    pub success: bool,
+    pub yaml: String,
+    pub errors: Vec<ErrorInfo>,
+}
+
+impl PatchResult {
+    fn success(yaml: String) -> Self {
+        Self {
+            success: true,
+            yaml,
+            errors: vec![],
+        }
+    }
+
+    fn error(yaml: &str, error: ErrorInfo) -> Self {
+        Self {
+            success: false,
+            yaml: yaml.to_string(),
+            errors: vec![error],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            r#"{"success":false,"yaml":"","errors":[{"line":0,"message":"Failed to serialize errors","path":""}]}"#.to_string()
+        })
+    }
+}
+
+/// YAMLにパッチを適用し、結果をJSON文字列で返す
+///
+/// # 引数
+/// * `yaml_str` - パッチ適用対象のYAML文字列
+/// * `patch_str` - パッチ（JSON/YAML形式）
+///   - 配列の場合: RFC 6902 JSON Patch
+///   - オブジェクトの場合: RFC 7386 JSON Merge Patch
+///
+/// # 戻り値
+/// * 成功時: `{"success": true, "yaml": "...", "errors": []}`
+/// * 失敗時: `{"success": false, "yaml": "<元のYAML>", "errors": [ErrorInfo, ...]}`
+///
+/// # エラーケース
+/// - YAML/パッチのパースエラー
+/// - 操作のpathが存在しない場合
+/// - `test` 操作の値が一致しない場合
+pub fn apply_patch(yaml_str: &str, patch_str: &str) -> String {
+    let mut doc: Value = match serde_yaml::from_str(yaml_str) {
+        Ok(v) => v,
+        Err(e) => {
+            return PatchResult::error(
+                yaml_str,
+                ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse),
+            )
+            .to_json();
+        }
+    };
+
+    let patch_value: Value = match serde_yaml::from_str(patch_str) {
+        Ok(v) => v,
+        Err(e) => {
+            return PatchResult::error(
+                yaml_str,
+                ErrorInfo::from_yaml_error(&e, ErrorCode::PatchApply),
+            )
+            .to_json();
+        }
+    };
+
+    match patch_value {
+        Value::Array(_) => {
+            let patch: json_patch::Patch = match serde_json::from_value(patch_value) {
+                Ok(p) => p,
+                Err(e) => {
+                    return PatchResult::error(
+                        yaml_str,
+                        ErrorInfo::new(
+                            0,
+                            format!("JSON Patchの形式が不正です: {}", e),
+                            "",
+                            ErrorCode::PatchApply,
+                        ),
+                    )
+                    .to_json();
+                }
+            };
+
+            if let Err(e) = json_patch::patch(&mut doc, &patch) {
+                let error = ErrorInfo::new(
+                    0,
+                    format!(
+                        "パッチ操作 {} の適用に失敗しました: {}",
+                        e.operation, e.kind
+                    ),
+                    e.path,
+                    ErrorCode::PatchApply,
+                );
+                // 存在しないパスは最も近い祖先ノードの位置を指す
+                let error = match SpanMap::parse(yaml_str).and_then(|map| map.locate(&error.path)) {
+                    Some(span) => error.with_span(span),
+                    None => error,
+                };
+                return PatchResult::error(yaml_str, error).to_json();
+            }
+        }
+        Value::Object(_) => json_patch::merge(&mut doc, &patch_value),
+        _ => {
+            return PatchResult::error(
+                yaml_str,
+                ErrorInfo::new(
+                    0,
+                    "パッチは操作の配列（JSON Patch）またはオブジェクト（Merge Patch）である必要があります",
+                    "",
+                    ErrorCode::PatchApply,
+                ),
+            )
+            .to_json();
+        }
+    }
+
+    match serde_yaml::to_string(&doc) {
+        Ok(yaml) => PatchResult::success(yaml).to_json(),
+        Err(e) => PatchResult::error(
+            yaml_str,
+            ErrorInfo::from_yaml_error(&e, ErrorCode::PatchApply),
+        )
+        .to_json(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(yaml: &str, patch: &str) -> PatchResult {
+        serde_json::from_str(&apply_patch(yaml, patch)).unwrap()
+    }
+
+    #[test]
+    fn test_json_patch_replace_and_add() {
+        let yaml = "title: Old\ntags:\n  - a\n";
+        let patch = r#"[
+            {"op": "replace", "path": "/title", "value": "New"},
+            {"op": "add", "path": "/tags/-", "value": "b"}
+        ]"#;
+
+        let result = run(yaml, patch);
+        assert!(result.success);
+        assert_eq!(result.yaml, "title: New\ntags:\n- a\n- b\n");
+    }
+
+    #[test]
+    fn test_json_patch_preserves_key_order() {
+        let yaml = "zeta: 1\nalpha: 2\n";
+        let patch = r#"[{"op": "replace", "path": "/alpha", "value": 3}]"#;
+
+        let result = run(yaml, patch);
+        assert!(result.success);
+        assert_eq!(result.yaml, "zeta: 1\nalpha: 3\n");
+    }
+
+    #[test]
+    fn test_json_patch_missing_path() {
+        let yaml = "title: Note\n";
+        let patch = r#"[{"op": "remove", "path": "/content"}]"#;
+
+        let result = run(yaml, patch);
+        assert!(!result.success);
+        assert_eq!(result.yaml, yaml);
+        assert_eq!(result.errors[0].path, "/content");
+        assert!(matches!(result.errors[0].code, ErrorCode::PatchApply));
+    }
+
+    #[test]
+    fn test_json_patch_test_failure_is_atomic() {
+        let yaml = "title: Note\nstatus: draft\n";
+        let patch = r#"[
+            {"op": "replace", "path": "/title", "value": "Changed"},
+            {"op": "test", "path": "/status", "value": "done"}
+        ]"#;
+
+        let result = run(yaml, patch);
+        assert!(!result.success);
+        assert_eq!(result.yaml, yaml);
+        assert_eq!(result.errors[0].path, "/status");
+        assert_eq!((result.errors[0].line, result.errors[0].column), (2, 9));
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let yaml = "title: Note\nmeta:\n  author: alice\n  draft: true\n";
+        let patch = r#"{"title": "Renamed", "meta": {"draft": null}}"#;
+
+        let result = run(yaml, patch);
+        assert!(result.success);
+        assert_eq!(result.yaml, "title: Renamed\nmeta:\n  author: alice\n");
+    }
+
+    #[test]
+    fn test_invalid_patch_shape() {
+        let result = run("title: Note\n", "42");
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::PatchApply));
+    }
+}
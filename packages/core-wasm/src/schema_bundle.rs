@@ -0,0 +1,594 @@
+//! schema_bundle.rs
+//!
+//! 複数ファイルにまたがるスキーマを、1つのスキーマドキュメントにまとめる（バンドルする）モジュール。
+//! - 相対パスの `$ref`（例: `./common/person.yaml#/definitions/person`）を `SchemaResolver` で解決
+//! - `$id` を基準にした `$ref`（絶対URL、または `$id` からの相対URL）を登録済みスキーマの `$id` から解決
+//! - 参照先のスキーマをルートの `x-bundled` の下に埋め込み、`$ref` をドキュメント内の参照に書き換える
+//! - 参照先が存在しない `$ref` と、`$ref` だけをたどって元に戻る循環参照の検出
+//!
+//! スキーマのコンパイル時には外部のスキーマを取得しないため、
+//! 検証の前にこのモジュールで参照先をすべて取り込んでおく。
+//! ファイル同士が互いを参照すること（再帰的な定義）は許可し、各ファイルは1回だけ取り込む。
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::error::ErrorInfo;
+use crate::error_code::ErrorCode;
+use crate::schema_resolver::{ResolvedSchema, SchemaResolver};
+use crate::yaml_span::escape_pointer_segment;
+
+/// 参照先のスキーマを埋め込むキー（キーはワークスペース内のパス）
+pub const BUNDLE_KEY: &str = "x-bundled";
+
+/// 値がスキーマではなくデータであるキーワード（中の `$ref` / `$id` は書き換えない）
+const DATA_KEYWORDS: [&str; 4] = ["enum", "const", "default", "examples"];
+
+/// 値が「名前 → スキーマ」のマップであるキーワード（名前は `$ref` などのキーワードとして扱わない）
+const SCHEMA_MAP_KEYWORDS: [&str; 6] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependencies",
+    "dependentSchemas",
+];
+
+/// `reference` のスキーマを読み込み、参照している他のスキーマを取り込んだ1つのスキーマにする
+///
+/// # 引数
+/// * `resolver` - スキーマファイルの解決方法
+/// * `reference` - ルートのスキーマの参照（`schema_path` など）
+/// * `base` - 参照元のワークスペース内パス（例: `notes/today.md`）
+///
+/// # 戻り値
+/// * 外部の `$ref` をすべてドキュメント内の参照に書き換えたスキーマ
+///
+/// # エラーケース
+/// - スキーマを解決できない、またはYAML/JSONとしてパースできない場合
+/// - `$ref` の参照先のスキーマやJSONポインタが存在しない場合
+/// - `$ref` が循環している場合（`$ref` だけをたどって同じ場所に戻る場合）
+pub fn bundle_schema<R: SchemaResolver + ?Sized>(
+    resolver: &R,
+    reference: &str,
+    base: &str,
+) -> Result<Value, ErrorInfo> {
+    let root = resolver.resolve(reference, base)?;
+    let mut bundler = Bundler {
+        resolver,
+        root_path: root.path.clone(),
+        loaded: HashSet::new(),
+        ids: HashMap::new(),
+        pending: Vec::new(),
+    };
+
+    bundler.load(root)?;
+    let mut bundled = Map::new();
+    let mut root_value = None;
+    while let Some((path, mut value)) = bundler.pending.pop() {
+        bundler.rewrite_document(&path, &mut value)?;
+        match path == bundler.root_path {
+            true => root_value = Some(value),
+            false => {
+                bundled.insert(path, value);
+            }
+        }
+    }
+
+    let mut root_value = root_value.unwrap_or(Value::Null);
+    if !bundled.is_empty() {
+        if let Value::Object(map) = &mut root_value {
+            // 取り込んだ順ではなくパス順に並べ、結果を安定させる
+            bundled.sort_keys();
+            map.insert(BUNDLE_KEY.to_string(), Value::Object(bundled));
+        }
+    }
+
+    check_references(
+        &root_value,
+        &root_value,
+        "",
+        &root_path_label(&bundler.root_path),
+    )?;
+    Ok(root_value)
+}
+
+struct Bundler<'a, R: ?Sized> {
+    resolver: &'a R,
+    root_path: String,
+    /// 読み込んだスキーマのパス
+    loaded: HashSet<String>,
+    /// 読み込んだスキーマの `$id` → パス
+    ids: HashMap<String, String>,
+    /// 読み込んだが `$ref` をまだ書き換えていないスキーマ
+    pending: Vec<(String, Value)>,
+}
+
+impl<R: SchemaResolver + ?Sized> Bundler<'_, R> {
+    /// スキーマをパースして書き換え待ちに追加する（読み込み済みなら何もしない）
+    fn load(&mut self, resolved: ResolvedSchema) -> Result<String, ErrorInfo> {
+        if self.loaded.contains(&resolved.path) {
+            return Ok(resolved.path);
+        }
+
+        let value: Value = serde_yaml::from_str(&resolved.source).map_err(|e| {
+            let error = ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse);
+            ErrorInfo {
+                message: format!("/{}: {}", resolved.path, error.message),
+                ..error
+            }
+        })?;
+        if let Some(id) = value
+            .get("$id")
+            .and_then(Value::as_str)
+            .and_then(|id| Url::parse(id).ok())
+        {
+            self.ids
+                .entry(id_key(&id))
+                .or_insert_with(|| resolved.path.clone());
+        }
+
+        self.loaded.insert(resolved.path.clone());
+        self.pending.push((resolved.path.clone(), value));
+        Ok(resolved.path)
+    }
+
+    /// `$id` がURLのスキーマを探す（読み込み済みのもの、次に `SchemaResolver::resolve_id`）
+    fn find_by_id(&mut self, url: &Url) -> Result<Option<String>, ErrorInfo> {
+        let key = id_key(url);
+        if let Some(path) = self.ids.get(&key) {
+            return Ok(Some(path.clone()));
+        }
+        match self.resolver.resolve_id(&key) {
+            Some(resolved) => self.load(resolved).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// スキーマドキュメント内の `$ref` をすべて書き換える
+    fn rewrite_document(&mut self, path: &str, value: &mut Value) -> Result<(), ErrorInfo> {
+        let base = value
+            .get("$id")
+            .and_then(Value::as_str)
+            .and_then(|id| Url::parse(id).ok());
+        let keep_id = path == self.root_path;
+        self.rewrite_node(path, value, base.as_ref(), String::new(), keep_id)
+    }
+
+    fn rewrite_node(
+        &mut self,
+        path: &str,
+        node: &mut Value,
+        base: Option<&Url>,
+        pointer: String,
+        keep_id: bool,
+    ) -> Result<(), ErrorInfo> {
+        match node {
+            Value::Object(map) => {
+                // 入れ子の `$id` は参照の基準URLとしてのみ使う
+                // （残すとバンドル後のドキュメント内の参照の基準が変わってしまう）
+                let nested_base = match map.get("$id").and_then(Value::as_str) {
+                    // `#name` 形式の `$id`（アンカー）はドキュメント内の参照先として残す
+                    Some(id) if (!pointer.is_empty() || !keep_id) && !id.starts_with('#') => {
+                        let url = base
+                            .map_or_else(|| Url::parse(id), |base| base.join(id))
+                            .ok();
+                        map.remove("$id");
+                        url
+                    }
+                    _ => None,
+                };
+                let base = nested_base.as_ref().or(base);
+
+                if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                    *reference = self.rewrite_ref(path, reference, base, &pointer)?;
+                }
+                for (key, child) in map.iter_mut() {
+                    if key == "$ref" || DATA_KEYWORDS.contains(&key.as_str()) {
+                        continue;
+                    }
+                    let child_pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+                    match (SCHEMA_MAP_KEYWORDS.contains(&key.as_str()), child) {
+                        (true, Value::Object(schemas)) => {
+                            for (name, schema) in schemas.iter_mut() {
+                                let pointer =
+                                    format!("{}/{}", child_pointer, escape_pointer_segment(name));
+                                self.rewrite_node(path, schema, base, pointer, keep_id)?;
+                            }
+                        }
+                        (_, child) => {
+                            self.rewrite_node(path, child, base, child_pointer, keep_id)?
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.rewrite_node(path, item, base, format!("{}/{}", pointer, i), keep_id)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `$ref` をバンドル後のドキュメント内の参照に書き換える
+    fn rewrite_ref(
+        &mut self,
+        path: &str,
+        reference: &str,
+        base: Option<&Url>,
+        pointer: &str,
+    ) -> Result<String, ErrorInfo> {
+        let located = |error: ErrorInfo| ErrorInfo {
+            message: format!("/{}{}/$ref: {}", path, pointer, error.message),
+            path: format!("{}/$ref", pointer),
+            ..error
+        };
+        let (target, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+
+        let target_path = if target.is_empty() {
+            path.to_string()
+        } else if is_metaschema(target) {
+            // メタスキーマはjsonschemaが解決する
+            return Ok(reference.to_string());
+        } else {
+            let url = match base {
+                Some(base) => base.join(target).ok(),
+                None => Url::parse(target).ok(),
+            };
+            match url {
+                Some(url) => match self.find_by_id(&url).map_err(located)? {
+                    Some(target_path) => target_path,
+                    // `$id` が一致しない相対URLはファイルの相対パスとして解決する
+                    None if Url::parse(target).is_err() => {
+                        self.load_file(target, path).map_err(located)?
+                    }
+                    None => {
+                        return Err(located(reference_error(
+                            reference,
+                            "この $id のスキーマは登録されていません",
+                        )));
+                    }
+                },
+                None => self.load_file(target, path).map_err(located)?,
+            }
+        };
+
+        if target_path == self.root_path {
+            return Ok(format!("#{}", fragment));
+        }
+        if !fragment.is_empty() && !fragment.starts_with('/') {
+            return Err(located(reference_error(
+                reference,
+                "アンカー名による参照には対応していません",
+            )));
+        }
+        Ok(format!(
+            "#/{}/{}{}",
+            BUNDLE_KEY,
+            escape_pointer_segment(&target_path),
+            fragment
+        ))
+    }
+
+    fn load_file(&mut self, target: &str, path: &str) -> Result<String, ErrorInfo> {
+        let resolved = self.resolver.resolve(target, path)?;
+        self.load(resolved)
+    }
+}
+
+/// `$id` の比較に使う文字列（空のフラグメント `#` を取り除く）
+fn id_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
+
+fn is_metaschema(target: &str) -> bool {
+    target.starts_with("http://json-schema.org/") || target.starts_with("https://json-schema.org/")
+}
+
+fn reference_error(reference: &str, reason: &str) -> ErrorInfo {
+    ErrorInfo::new(
+        0,
+        format!("$refを解決できません: {} ({})", reference, reason),
+        "",
+        ErrorCode::SchemaResolve,
+    )
+}
+
+fn root_path_label(root_path: &str) -> String {
+    format!("/{}", root_path)
+}
+
+/// バンドル後のドキュメント内の参照を `/schemas/note.yaml#/definitions/x` の形式で表す
+fn describe(reference: &str, root_label: &str) -> String {
+    let fragment = reference.trim_start_matches('#');
+    let prefix = format!("/{}/", BUNDLE_KEY);
+    match fragment.strip_prefix(&prefix) {
+        Some(rest) => {
+            let (file, pointer) = rest
+                .split_once('/')
+                .map_or((rest, ""), |(file, pointer)| (file, pointer));
+            let file = file.replace("~1", "/").replace("~0", "~");
+            match pointer.is_empty() {
+                true => format!("/{}", file),
+                false => format!("/{}#/{}", file, pointer),
+            }
+        }
+        None if fragment.is_empty() => root_label.to_string(),
+        None => format!("{}#{}", root_label, fragment),
+    }
+}
+
+/// ドキュメント内の `$ref` の参照先が存在し、`$ref` だけで循環していないことを確認する
+fn check_references(
+    document: &Value,
+    node: &Value,
+    pointer: &str,
+    root_label: &str,
+) -> Result<(), ErrorInfo> {
+    match node {
+        Value::Object(map) => {
+            if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                check_reference(document, reference, pointer, root_label)?;
+            }
+            for (key, child) in map {
+                if key != "$ref" && !DATA_KEYWORDS.contains(&key.as_str()) {
+                    let pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+                    check_references(document, child, &pointer, root_label)?;
+                }
+            }
+            Ok(())
+        }
+        Value::Array(items) => items.iter().enumerate().try_for_each(|(i, item)| {
+            check_references(document, item, &format!("{}/{}", pointer, i), root_label)
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn check_reference(
+    document: &Value,
+    reference: &str,
+    pointer: &str,
+    root_label: &str,
+) -> Result<(), ErrorInfo> {
+    let error = |message: String| {
+        ErrorInfo::new(
+            0,
+            message,
+            format!("{}/$ref", pointer),
+            ErrorCode::SchemaResolve,
+        )
+    };
+
+    let mut chain = vec![reference];
+    let mut current = reference;
+    loop {
+        // アンカー名や外部URL（メタスキーマなど）はjsonschemaに任せる
+        let Some(fragment) = current
+            .strip_prefix('#')
+            .filter(|f| f.is_empty() || f.starts_with('/'))
+        else {
+            return Ok(());
+        };
+        let Some(target) = document.pointer(fragment) else {
+            return Err(error(format!(
+                "$refの参照先が見つかりません: {}",
+                describe(current, root_label)
+            )));
+        };
+        match target.get("$ref").and_then(Value::as_str) {
+            Some(next) if chain.contains(&next) => {
+                let cycle: Vec<String> = chain
+                    .iter()
+                    .skip_while(|r| **r != next)
+                    .chain([&next])
+                    .map(|r| describe(r, root_label))
+                    .collect();
+                return Err(error(format!(
+                    "$refが循環しています: {}",
+                    cycle.join(" → ")
+                )));
+            }
+            Some(next) => {
+                chain.push(next);
+                current = next;
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_resolver::MemoryResolver;
+
+    fn registry(schemas: &[(&str, &str)]) -> MemoryResolver {
+        let mut registry = MemoryResolver::new();
+        for (path, source) in schemas {
+            registry.register(path, *source).unwrap();
+        }
+        registry
+    }
+
+    fn is_valid(schema: &Value, instance: Value) -> bool {
+        jsonschema::options()
+            .with_draft(jsonschema::Draft::Draft7)
+            .build(schema)
+            .unwrap()
+            .is_valid(&instance)
+    }
+
+    const PERSON: &str = r##"
+definitions:
+  person:
+    type: object
+    required: [name]
+    properties:
+      name: { type: string }
+      email: { $ref: "#/definitions/email" }
+  email:
+    type: string
+    pattern: "@"
+"##;
+
+    #[test]
+    fn test_bundle_relative_refs() {
+        let registry = registry(&[
+            ("schemas/note.yaml", "type: object\nproperties:\n  author:\n    $ref: ./common/person.yaml#/definitions/person\n"),
+            ("schemas/common/person.yaml", PERSON),
+        ]);
+        let schema = bundle_schema(&registry, "../schemas/note.yaml", "notes/today.md").unwrap();
+
+        assert_eq!(
+            schema["properties"]["author"]["$ref"],
+            "#/x-bundled/schemas~1common~1person.yaml/definitions/person"
+        );
+        // 取り込んだスキーマ内の参照も書き換える
+        assert_eq!(
+            schema[BUNDLE_KEY]["schemas/common/person.yaml"]["definitions"]["person"]["properties"]
+                ["email"]["$ref"],
+            "#/x-bundled/schemas~1common~1person.yaml/definitions/email"
+        );
+        assert!(is_valid(
+            &schema,
+            serde_json::json!({"author": {"name": "a", "email": "a@example.com"}})
+        ));
+        assert!(!is_valid(
+            &schema,
+            serde_json::json!({"author": {"name": "a", "email": "invalid"}})
+        ));
+        assert!(!is_valid(&schema, serde_json::json!({"author": {}})));
+    }
+
+    #[test]
+    fn test_bundle_id_refs() {
+        let registry = registry(&[
+            (
+                "schemas/note.schema.yaml",
+                "$id: https://yamalnote.example/schemas/note.schema.yaml\nproperties:\n  author:\n    $ref: common.schema.yaml#/definitions/person\n  reviewer:\n    $ref: https://yamalnote.example/shared/person.yaml#/definitions/person\n",
+            ),
+            (
+                "shared/person.yaml",
+                &format!("$id: https://yamalnote.example/shared/person.yaml\n{}", PERSON),
+            ),
+            ("other/common.yaml", "$id: https://yamalnote.example/schemas/common.schema.yaml\ndefinitions:\n  person:\n    type: string\n"),
+        ]);
+        let schema = bundle_schema(&registry, "/schemas/note.schema.yaml", "").unwrap();
+
+        // ルートの $id は残し、取り込んだスキーマの $id は取り除く
+        assert_eq!(
+            schema["$id"],
+            "https://yamalnote.example/schemas/note.schema.yaml"
+        );
+        assert_eq!(
+            schema["properties"]["author"]["$ref"],
+            "#/x-bundled/other~1common.yaml/definitions/person"
+        );
+        assert!(schema[BUNDLE_KEY]["shared/person.yaml"]
+            .get("$id")
+            .is_none());
+        assert!(is_valid(
+            &schema,
+            serde_json::json!({"author": "a", "reviewer": {"name": "b"}})
+        ));
+        assert!(!is_valid(&schema, serde_json::json!({"author": 1})));
+        assert!(!is_valid(
+            &schema,
+            serde_json::json!({"reviewer": {"email": "b@example.com"}})
+        ));
+
+        // 登録されていない $id
+        let registry = self::registry(&[(
+            "note.yaml",
+            "$ref: https://yamalnote.example/missing.yaml\n",
+        )]);
+        let error = bundle_schema(&registry, "note.yaml", "").unwrap_err();
+        assert!(matches!(error.code, ErrorCode::SchemaResolve));
+        assert_eq!(error.path, "/$ref");
+    }
+
+    #[test]
+    fn test_bundle_recursive_files() {
+        // ファイル同士が互いを参照する再帰的な定義は許可する
+        let registry = registry(&[
+            ("tree.yaml", "type: object\nproperties:\n  children:\n    type: array\n    items:\n      $ref: ./node.yaml\n"),
+            ("node.yaml", "type: object\nrequired: [name]\nproperties:\n  name: { type: string }\n  children:\n    $ref: ./tree.yaml#/properties/children\n"),
+        ]);
+        let schema = bundle_schema(&registry, "tree.yaml", "").unwrap();
+        assert_eq!(
+            schema[BUNDLE_KEY]["node.yaml"]["properties"]["children"]["$ref"],
+            "#/properties/children"
+        );
+        assert!(is_valid(
+            &schema,
+            serde_json::json!({"children": [{"name": "a", "children": [{"name": "b"}]}]})
+        ));
+        assert!(!is_valid(
+            &schema,
+            serde_json::json!({"children": [{"name": "a", "children": [{}]}]})
+        ));
+    }
+
+    #[test]
+    fn test_bundle_detects_cycles_and_missing_targets() {
+        let registry = registry(&[
+            (
+                "a.yaml",
+                "properties:\n  x:\n    $ref: ./b.yaml#/definitions/b\n",
+            ),
+            ("b.yaml", "definitions:\n  b:\n    $ref: ./c.yaml\n"),
+            ("c.yaml", "$ref: ./b.yaml#/definitions/b\n"),
+        ]);
+        let error = bundle_schema(&registry, "a.yaml", "").unwrap_err();
+        assert!(matches!(error.code, ErrorCode::SchemaResolve));
+        assert!(
+            error
+                .message
+                .contains("/b.yaml#/definitions/b → /c.yaml → /b.yaml#/definitions/b"),
+            "{}",
+            error.message
+        );
+
+        let registry = self::registry(&[
+            (
+                "a.yaml",
+                "properties:\n  x:\n    $ref: ./b.yaml#/definitions/missing\n",
+            ),
+            ("b.yaml", "definitions: {}\n"),
+        ]);
+        let error = bundle_schema(&registry, "a.yaml", "").unwrap_err();
+        assert!(
+            error.message.contains("/b.yaml#/definitions/missing"),
+            "{}",
+            error.message
+        );
+        assert_eq!(error.path, "/properties/x/$ref");
+
+        let registry = self::registry(&[("a.yaml", "properties:\n  x:\n    $ref: ./none.yaml\n")]);
+        let error = bundle_schema(&registry, "a.yaml", "").unwrap_err();
+        assert!(
+            error.message.starts_with("/a.yaml/properties/x/$ref: "),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn test_bundle_leaves_data_and_property_names() {
+        let registry = registry(&[(
+            "a.yaml",
+            "properties:\n  $ref:\n    type: string\n  kind:\n    const: { $ref: ./none.yaml }\n",
+        )]);
+        let schema = bundle_schema(&registry, "a.yaml", "").unwrap();
+        assert_eq!(schema["properties"]["kind"]["const"]["$ref"], "./none.yaml");
+        assert!(schema.get(BUNDLE_KEY).is_none());
+    }
+}
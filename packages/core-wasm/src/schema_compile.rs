@@ -2,168 +2,165 @@
 //!
 //! JSONスキーマの構文/意味論的検証機能を提供する。
 //! - YAMLパースエラーの検出
-//! - JSONスキーマとしての検証（メタスキーマに対する検証）
+//! - `$schema` で選んだdraftの公式メタスキーマに対する検証
+//! - 無効な `type` と `x-severity` の検出（すべてのサブスキーマが対象）
+//! - リント（`schema_lint`、ルールごとに重大度を設定できる警告）
+//! - スキーマのコンパイル（`$ref` の参照先や正規表現の確認）
+//!
+//! 最初の問題で止めず、すべての問題をスキーマYAML上の位置とともに返す。
 
-use serde_json::Value;
 use crate::error::{ErrorInfo, ValidationResult};
 use crate::error_code::ErrorCode;
+use crate::schema_lint::{self, LintOptions};
+use crate::schema_severity;
+use crate::validate;
+use crate::yaml_span::SpanMap;
+use jsonschema::{Draft, Validator};
+use serde_json::{Map, Value};
+
+/// `type` に指定できる値
+const VALID_TYPES: [&str; 7] = [
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
 
 /// JSONスキーマをコンパイルして検証する
 ///
 /// # 引数
 /// * `schema_yaml` - 検証対象のスキーマYAML文字列
+/// * `options` - リントの設定（YAML/JSON形式、任意。`LintOptions` を参照）
 ///
 /// # 戻り値
 /// * JSON形式のバリデーション結果
 /// * 成功時: `{"success": true, "errors": []}`
 /// * 失敗時: `{"success": false, "errors": [ErrorInfo, ...]}`
+///   - すべての問題を、問題箇所のJSON Pointer（`path`）とスキーマYAML上の位置の順に返す
+///   - リントの警告（`severity: "warning"`）は `errors` に含めるが、`success` には影響しない
 ///
 /// # エラーケース
-/// - YAMLパースエラー時
-/// - JSONスキーマとして無効な場合（必須フィールドの欠落など）
-pub fn compile_schema(schema_yaml: &str) -> String {
-    // YAMLをパース
-    let schema_value: Result<Value, serde_yaml::Error> = serde_yaml::from_str(schema_yaml);
-    
-    // パースエラーがあればそれを返す
-    match schema_value {
-        Ok(value) => {
-            // スキーマの基本検証
-            if let Err(error) = validate_schema_basics(&value) {
-                return ValidationResult::error(vec![ErrorInfo::new(0, error, "", ErrorCode::SchemaCompile)]).to_json();
-            }
-
-            // メタスキーマによる検証
-            if let Err(error) = validate_schema_structure(&value) {
-                return ValidationResult::error(vec![ErrorInfo::new(0, error, "", ErrorCode::SchemaCompile)]).to_json();
-            }
+/// - YAMLパースエラー時、リントの設定が不正な場合
+/// - JSONスキーマとして無効な場合（メタスキーマに違反する、未対応の `$schema` など）
+/// - 重大度が `error` のリントに違反する場合（既定では正規表現としてコンパイルできない `pattern`）
+pub fn compile_schema(schema_yaml: &str, options: Option<&str>) -> String {
+    let options = match LintOptions::parse(options) {
+        Ok(options) => options,
+        Err(error) => return ValidationResult::single_error(error).to_json(),
+    };
 
-            // 成功
-            ValidationResult::success().to_json()
-        },
+    // YAMLをパース
+    let schema_value: Value = match serde_yaml::from_str(schema_yaml) {
+        Ok(value) => value,
         Err(err) => {
-            let line = match err.location() {
-                Some(location) => location.line() as u32,
-                None => 0,
-            };
-            
             // エラーを構造化して返す
-            ValidationResult::error(vec![
-                ErrorInfo::new(
-                    line,
-                    format!("スキーマ構文エラー: YAML解析に失敗しました - {}", err),
-                    "",
-                    ErrorCode::YamlParse,
-                )
-            ]).to_json()
+            let error = ErrorInfo::from_yaml_error(&err, ErrorCode::YamlParse);
+            return ValidationResult::error(vec![ErrorInfo {
+                message: format!("スキーマ構文エラー: YAML解析に失敗しました - {}", err),
+                ..error
+            }])
+            .to_json();
         }
-    }
-}
-
-/// スキーマの基本的な構造を検証する内部関数
-fn validate_schema_basics(schema: &Value) -> Result<(), String> {
-    // スキーマにはtypeフィールドが必要
-    let type_value = match schema.get("type") {
-        Some(t) => t,
-        None => return Err("スキーマ構文エラー: スキーマには 'type' フィールドが必要です".to_string()),
-    };
-
-    // typeは文字列である必要がある
-    let type_str = match type_value.as_str() {
-        Some(s) => s,
-        None => return Err("スキーマ構文エラー: 'type' フィールドは文字列でなければなりません".to_string()),
     };
 
-    // typeは有効な値である必要がある
-    let valid_types = ["object", "array", "string", "number", "integer", "boolean", "null"];
-    if !valid_types.contains(&type_str) {
-        return Err(format!(
-            "スキーマ構文エラー: '{}' は有効なtypeではありません。object, array, string, number, integer, boolean, nullのいずれかである必要があります", 
-            type_str
-        ));
-    }
-
-    // objectタイプの場合はpropertiesフィールドが必要
-    if type_str == "object" && schema.get("properties").is_none() {
-        return Err("スキーマ構文エラー: objectタイプのスキーマには 'properties' フィールドが必要です".to_string());
+    let mut errors = check_schema(&schema_value, &options);
+    if errors.is_empty() {
+        return ValidationResult::success().to_json();
     }
 
-    // arrayタイプの場合はitemsフィールドが必要
-    if type_str == "array" && schema.get("items").is_none() {
-        return Err("スキーマ構文エラー: arrayタイプのスキーマには 'items' フィールドが必要です".to_string());
+    // エラー位置をスキーマのYAML上の位置に対応付け、記述順に並べる
+    if let Some(span_map) = SpanMap::parse(schema_yaml) {
+        for error in &mut errors {
+            if let Some(span) = span_map
+                .key(&error.path)
+                .or_else(|| span_map.locate(&error.path))
+            {
+                *error = error.clone().with_span(span);
+            }
+        }
     }
-
-    Ok(())
+    errors.sort_by_key(|error| (error.line, error.column));
+    ValidationResult::from_diagnostics(errors).to_json()
 }
 
-/// スキーマの詳細構造を検証する内部関数
-/// より詳細なスキーマの構造上の問題を検出する
-fn validate_schema_structure(schema: &Value) -> Result<(), String> {
-    // プロパティの検証
-    if let Some(properties) = schema.get("properties") {
-        if !properties.is_object() {
-            return Err("スキーマ構文エラー: 'properties' フィールドはオブジェクトでなければなりません".to_string());
-        }
+/// スキーマの問題をすべて集める
+///
+/// 1. `$schema` の確認（未対応の方言ならそれ以上は検証しない）
+/// 2. 無効な `type` / `x-severity` とリント
+/// 3. メタスキーマ（1, 2で報告済みの箇所は除く）
+/// 4. 以上でエラーがなければコンパイル
+fn check_schema(schema: &Value, options: &LintOptions) -> Vec<ErrorInfo> {
+    let draft = match validate::schema_draft(schema) {
+        Ok(draft) => draft,
+        Err(error) => return vec![error],
+    };
 
-        // 各プロパティの検証
-        let props = properties.as_object().unwrap();
-        for (prop_name, prop_schema) in props {
-            // 各プロパティはオブジェクトである必要がある
-            if !prop_schema.is_object() {
-                return Err(format!(
-                    "スキーマ構文エラー: プロパティ '{}' の定義がオブジェクトではありません", 
-                    prop_name
-                ));
-            }
+    let mut errors = Vec::new();
+    schema_lint::walk_subschemas(schema, &mut |object, pointer| {
+        check_type(object, pointer, &mut errors);
+        errors.extend(schema_severity::check_severity_keyword(object, pointer));
+    });
+    errors.extend(schema_lint::lint_schema(schema, options));
 
-            // プロパティにtypeフィールドがあるか確認
-            if prop_schema.get("type").is_none() {
-                return Err(format!(
-                    "スキーマ構文エラー: プロパティ '{}' に 'type' フィールドがありません", 
-                    prop_name
-                ));
-            }
+    let reported: Vec<String> = errors.iter().map(|error| error.path.clone()).collect();
+    for err in meta_validator(draft).iter_errors(schema) {
+        let path = err.instance_path.as_str();
+        if !reported.iter().any(|reported| reported == path) {
+            errors.push(compile_error(
+                format!("スキーマ構文エラー: {}", err),
+                path.to_string(),
+            ));
         }
     }
 
-    // requiredフィールドの検証
-    if let Some(required) = schema.get("required") {
-        if !required.is_array() {
-            return Err("スキーマ構文エラー: 'required' フィールドは配列でなければなりません".to_string());
+    if !errors.iter().any(ErrorInfo::is_error) {
+        if let Err(error) = validate::build_validator(schema) {
+            errors.push(error);
         }
+    }
+    errors
+}
 
-        // requiredフィールドの各要素が文字列であることを確認
-        let required_arr = required.as_array().unwrap();
-        for (i, item) in required_arr.iter().enumerate() {
-            if !item.is_string() {
-                return Err(format!(
-                    "スキーマ構文エラー: 'required' 配列の要素 {} は文字列でなければなりません", 
-                    i
-                ));
-            }
-
-            // propertiesに存在しない項目がrequiredになっていないかチェック
-            if let Some(properties) = schema.get("properties") {
-                let props = properties.as_object().unwrap();
-                let prop_name = item.as_str().unwrap();
-                if !props.contains_key(prop_name) {
-                    return Err(format!(
-                        "スキーマ構文エラー: required指定されたプロパティ '{}' がpropertiesに定義されていません", 
-                        prop_name
-                    ));
-                }
-            }
-        }
+/// draftの公式メタスキーマのバリデータ
+fn meta_validator(draft: Draft) -> &'static Validator {
+    match draft {
+        Draft::Draft4 => &jsonschema::draft4::meta::VALIDATOR,
+        Draft::Draft6 => &jsonschema::draft6::meta::VALIDATOR,
+        Draft::Draft7 => &jsonschema::draft7::meta::VALIDATOR,
+        Draft::Draft201909 => &jsonschema::draft201909::meta::VALIDATOR,
+        _ => &jsonschema::draft202012::meta::VALIDATOR,
     }
+}
 
-    // より詳細な検証ルールはここに追加可能
+fn compile_error(message: String, path: String) -> ErrorInfo {
+    ErrorInfo::new(0, message, path, ErrorCode::SchemaCompile)
+}
 
-    Ok(())
+/// typeは有効な値である必要がある（型の配列も可）
+fn check_type(schema: &Map<String, Value>, pointer: &str, errors: &mut Vec<ErrorInfo>) {
+    let types: Vec<(String, &Value)> = match schema.get("type") {
+        Some(Value::Array(types)) => types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (format!("{}/type/{}", pointer, i), t))
+            .collect(),
+        Some(t) => vec![(format!("{}/type", pointer), t)],
+        None => vec![],
+    };
+    for (path, type_value) in types {
+        if let Some(type_str) = type_value.as_str().filter(|t| !VALID_TYPES.contains(t)) {
+            errors.push(compile_error(
+                format!(
+                    "スキーマ構文エラー: '{}' は有効なtypeではありません。object, array, string, number, integer, boolean, nullのいずれかである必要があります",
+                    type_str
+                ),
+                path,
+            ));
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Severity;
     use serde_json::json;
 
     #[test]
@@ -179,7 +176,7 @@ mod tests {
               - title
         "#;
 
-        let result = compile_schema(schema);
+        let result = compile_schema(schema, None);
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(parsed["success"], json!(true));
     }
@@ -199,26 +196,30 @@ mod tests {
               - title
         "#;
 
-        let result = compile_schema(schema);
+        let result = compile_schema(schema, None);
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(parsed["success"], json!(false));
-        assert!(parsed["errors"][0]["message"].as_str().unwrap().contains("スキーマ構文エラー"));
+        assert!(parsed["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("スキーマ構文エラー"));
     }
 
     #[test]
-    fn test_missing_type() {
-        let schema = r#"
-            properties:
-              title:
-                type: string
-              content:
-                type: string
-        "#;
-
-        let result = compile_schema(schema);
-        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
-        assert_eq!(parsed["success"], json!(false));
-        assert!(parsed["errors"][0]["message"].as_str().unwrap().contains("type"));
+    fn test_schemas_without_type_or_properties_are_valid() {
+        // トップレベルのtypeがない（oneOf / $ref）、typeの配列、propertiesのないobjectは有効
+        for schema in [
+            "oneOf:\n  - type: string\n  - type: number\n",
+            "$ref: \"#/definitions/note\"\ndefinitions:\n  note:\n    type: object\n",
+            "type: object\nproperties:\n  title:\n    type: [string, \"null\"]\n  body: {}\n",
+            "type: object\nadditionalProperties:\n  type: string\n",
+            "type: array\n",
+            "true\n",
+        ] {
+            let result = compile_schema(schema, None);
+            let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+            assert_eq!(parsed["success"], json!(true), "{}: {}", schema, result);
+        }
     }
 
     #[test]
@@ -230,24 +231,13 @@ mod tests {
                 type: string
         "#;
 
-        let result = compile_schema(schema);
-        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
-        assert_eq!(parsed["success"], json!(false));
-        assert!(parsed["errors"][0]["message"].as_str().unwrap().contains("有効なtype"));
-    }
-
-    #[test]
-    fn test_missing_properties() {
-        let schema = r#"
-            type: object
-            required:
-              - title
-        "#;
-
-        let result = compile_schema(schema);
+        let result = compile_schema(schema, None);
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(parsed["success"], json!(false));
-        assert!(parsed["errors"][0]["message"].as_str().unwrap().contains("properties"));
+        assert!(parsed["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("有効なtype"));
     }
 
     #[test]
@@ -261,9 +251,115 @@ mod tests {
               - non_existent_property
         "#;
 
-        let result = compile_schema(schema);
+        // propertiesにないrequiredの項目はリントの警告（スキーマは有効）
+        let result = compile_schema(schema, None);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["success"], json!(true));
+        assert!(parsed["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("non_existent_property"));
+        assert_eq!(parsed["errors"][0]["severity"], json!("warning"));
+        assert_eq!(parsed["errors"][0]["code"], json!("SchemaLint"));
+        assert_eq!(
+            parsed["errors"][0]["rule"],
+            json!("required-not-in-properties")
+        );
+
+        // 設定でエラーにできる
+        let result = compile_schema(schema, Some("lints: {required-not-in-properties: error}"));
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
         assert_eq!(parsed["success"], json!(false));
-        assert!(parsed["errors"][0]["message"].as_str().unwrap().contains("non_existent_property"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_error_position_in_schema_yaml() {
+        let schema = "type: object\nproperties:\n  title:\n    type: string\nrequired:\n  - title\n  - missing\n";
+
+        let result: ValidationResult = serde_json::from_str(&compile_schema(schema, None)).unwrap();
+        let error = &result.errors[0];
+        assert_eq!(error.path, "/required/1");
+        assert_eq!((error.line, error.column), (7, 5));
+        assert_eq!((error.end_line, error.end_column), (7, 12));
+        assert_eq!(
+            &schema[error.offset as usize..error.end_offset as usize],
+            "missing"
+        );
+    }
+
+    #[test]
+    fn test_reports_all_errors_at_any_depth() {
+        let schema = "type: object\nproperties:\n  title:\n    type: strin\n  tags:\n    type: array\n    items:\n      type: string\n      minLength: 5\n      maxLength: 3\n  meta:\n    type: object\n    properties:\n      count:\n        type: integer\n        minimum: x\n    required: [count, missing]\nrequired: title\n";
+
+        let result: ValidationResult = serde_json::from_str(&compile_schema(schema, None)).unwrap();
+        assert!(!result.success);
+        let errors: Vec<(&str, u32)> = result
+            .errors
+            .iter()
+            .map(|e| (e.path.as_str(), e.line))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("/properties/title/type", 4),
+                ("/properties/tags/items/minLength", 9),
+                ("/properties/meta/properties/count/minimum", 16),
+                ("/properties/meta/required/1", 17),
+                ("/required", 18),
+            ]
+        );
+        let codes: Vec<(ErrorCode, Severity)> =
+            result.errors.iter().map(|e| (e.code, e.severity)).collect();
+        assert!(matches!(
+            codes.as_slice(),
+            [
+                (ErrorCode::SchemaCompile, Severity::Error),
+                (ErrorCode::SchemaLint, Severity::Warning),
+                (ErrorCode::SchemaCompile, Severity::Error),
+                (ErrorCode::SchemaLint, Severity::Warning),
+                (ErrorCode::SchemaCompile, Severity::Error),
+            ]
+        ));
+        // 独自のルールで報告した箇所はメタスキーマのエラーと重複させない
+        assert!(result.errors[0].message.contains("有効なtype"));
+        assert!(result.errors[0].column > 0);
+    }
+
+    #[test]
+    fn test_compile_errors_after_metaschema() {
+        // 正規表現
+        let result: ValidationResult =
+            serde_json::from_str(&compile_schema("type: string\npattern: \"([\"\n", None)).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "/pattern");
+        assert_eq!(result.errors[0].line, 2);
+
+        // 存在しない参照先
+        let result: ValidationResult =
+            serde_json::from_str(&compile_schema("$ref: \"#/definitions/none\"\n", None)).unwrap();
+        assert!(!result.success);
+        assert!(result.errors[0].message.contains("/definitions/none"));
+
+        // 未対応の方言
+        let result: ValidationResult = serde_json::from_str(&compile_schema(
+            "$schema: https://example.com/schema\ntype: strin\n",
+            None,
+        ))
+        .unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "/$schema");
+    }
+
+    #[test]
+    fn test_metaschema_follows_draft() {
+        // 2020-12 の prefixItems は配列でなければならない
+        let schema = "$schema: https://json-schema.org/draft/2020-12/schema\nprefixItems: {}\n";
+        let result: ValidationResult = serde_json::from_str(&compile_schema(schema, None)).unwrap();
+        assert_eq!(result.errors[0].path, "/prefixItems");
+
+        // draft-07 では未知のキーワードなので無視される
+        let result: ValidationResult =
+            serde_json::from_str(&compile_schema("prefixItems: {}\n", None)).unwrap();
+        assert!(result.success);
+    }
+}
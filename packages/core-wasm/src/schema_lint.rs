@@ -0,0 +1,741 @@
+//! schema_lint.rs
+//!
+//! スキーマのリント（スキーマとしては有効だが、意図どおりに動かない可能性が高い記述の検出）。
+//! - ルールごとに重大度（`error` / `warning` / `info` / `hint`）の変更や無効化ができる（`LintOptions`）
+//!   - `invalid-pattern` は常にエラー（不正な正規表現を含むスキーマはコンパイルできないため変更できない）
+//! - すべてのサブスキーマ（ルートの `frontmatter` セクションを含む）が対象
+//!
+//! | ルール | 既定 | 内容 |
+//! |---|---|---|
+//! | `required-not-in-properties` | warning | `properties` に定義されていない `required` の項目 |
+//! | `unused-definition` | warning | どこからも `$ref` されていない `definitions` / `$defs` |
+//! | `enum-type-mismatch` | warning | `type` に合わない `enum` / `const` の値 |
+//! | `min-greater-than-max` | warning | 下限が上限より大きい（`minimum` > `maximum` など） |
+//! | `invalid-pattern` | error | 正規表現としてコンパイルできない `pattern` / `patternProperties` |
+//! | `additional-properties-with-all-of` | warning | `additionalProperties: false` と `allOf` の組み合わせ |
+//! | `unknown-keyword` | warning | 未知のキーワード（`requird` などの誤字、`x-` で始まるものは除く） |
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::{ErrorInfo, Severity};
+use crate::error_code::ErrorCode;
+use crate::yaml_span::escape_pointer_segment;
+
+/// 値が1つのサブスキーマ（またはサブスキーマの配列）であるキーワード
+const SCHEMA_KEYWORDS: [&str; 12] = [
+    "items",
+    "additionalItems",
+    "contains",
+    "additionalProperties",
+    "propertyNames",
+    "not",
+    "if",
+    "then",
+    "else",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contentSchema",
+];
+
+/// 値がサブスキーマの配列であるキーワード
+const SCHEMA_ARRAY_KEYWORDS: [&str; 4] = ["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// 値が「名前 → サブスキーマ」のマップであるキーワード
+const SCHEMA_MAP_KEYWORDS: [&str; 6] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependencies",
+    "dependentSchemas",
+];
+
+/// 下限と上限のキーワードの組
+const BOUNDS: [(&str, &str); 6] = [
+    ("minimum", "maximum"),
+    ("exclusiveMinimum", "exclusiveMaximum"),
+    ("minLength", "maxLength"),
+    ("minItems", "maxItems"),
+    ("minProperties", "maxProperties"),
+    ("minContains", "maxContains"),
+];
+
+/// draft-04 〜 2020-12 のキーワード
+const KNOWN_KEYWORDS: [&str; 63] = [
+    "$schema",
+    "$id",
+    "id",
+    "$ref",
+    "$comment",
+    "$defs",
+    "definitions",
+    "$anchor",
+    "$dynamicRef",
+    "$dynamicAnchor",
+    "$recursiveRef",
+    "$recursiveAnchor",
+    "$vocabulary",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+    "type",
+    "enum",
+    "const",
+    "multipleOf",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "format",
+    "contentMediaType",
+    "contentEncoding",
+    "contentSchema",
+    "items",
+    "additionalItems",
+    "prefixItems",
+    "contains",
+    "minContains",
+    "maxContains",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "unevaluatedItems",
+    "maxProperties",
+    "minProperties",
+    "required",
+    "properties",
+    "patternProperties",
+    "additionalProperties",
+    "dependencies",
+    "dependentRequired",
+    "dependentSchemas",
+    "propertyNames",
+    "unevaluatedProperties",
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+];
+
+/// ルートだけで使えるキーワード（ノートのスキーマの `frontmatter` セクション）
+const ROOT_KEYWORDS: [&str; 1] = ["frontmatter"];
+
+/// `unused-definition` の対象外にするルートのキーワード（定義だけを集めた共通スキーマの判定用）
+const DEFINITION_ONLY_KEYWORDS: [&str; 6] = [
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "definitions",
+];
+
+/// リントのルール
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    RequiredNotInProperties,
+    UnusedDefinition,
+    EnumTypeMismatch,
+    MinGreaterThanMax,
+    InvalidPattern,
+    AdditionalPropertiesWithAllOf,
+    UnknownKeyword,
+}
+
+impl LintRule {
+    /// ルール名（`required-not-in-properties` など）
+    pub fn name(self) -> &'static str {
+        match self {
+            LintRule::RequiredNotInProperties => "required-not-in-properties",
+            LintRule::UnusedDefinition => "unused-definition",
+            LintRule::EnumTypeMismatch => "enum-type-mismatch",
+            LintRule::MinGreaterThanMax => "min-greater-than-max",
+            LintRule::InvalidPattern => "invalid-pattern",
+            LintRule::AdditionalPropertiesWithAllOf => "additional-properties-with-all-of",
+            LintRule::UnknownKeyword => "unknown-keyword",
+        }
+    }
+
+    /// 既定の重大度（スキーマをコンパイルできなくなるものだけエラー）
+    fn default_severity(self) -> Severity {
+        match self {
+            LintRule::InvalidPattern => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+
+    /// 重大度を変更できるか（スキーマをコンパイルできなくなるものはエラーのまま）
+    fn configurable(self) -> bool {
+        !matches!(self, LintRule::InvalidPattern)
+    }
+}
+
+/// ルールの設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    /// 無効
+    Off,
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+/// リントの設定
+///
+/// # 例（YAML）
+/// ```yaml
+/// lints:
+///   unknown-keyword: off
+///   unused-definition: error
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintOptions {
+    /// ルールごとの設定（指定しないルールは既定の重大度）
+    #[serde(default)]
+    pub lints: BTreeMap<LintRule, RuleLevel>,
+}
+
+impl LintOptions {
+    /// YAML/JSON形式の設定文字列をパースする（未指定・空文字列の場合はデフォルト）
+    ///
+    /// # エラーケース
+    /// - YAMLとして不正な場合（`YamlParse`）
+    /// - 未知のルール・重大度や、変更できないルール（`invalid-pattern`）をエラー以外にした場合（`InvalidOptions`）
+    pub fn parse(options: Option<&str>) -> Result<LintOptions, ErrorInfo> {
+        match options.filter(|o| !o.trim().is_empty()) {
+            Some(o) => {
+                let invalid = |e: serde_yaml::Error, code| {
+                    let mut error = ErrorInfo::from_yaml_error(&e, code);
+                    error.message = format!("リントの設定が不正です: {}", e);
+                    error
+                };
+                // YAMLとしての構文エラーと、設定の値の誤りを区別する
+                serde_yaml::from_str::<serde_yaml::Value>(o)
+                    .map_err(|e| invalid(e, ErrorCode::YamlParse))?;
+                let options = serde_yaml::from_str::<Option<LintOptions>>(o)
+                    .map(Option::unwrap_or_default)
+                    .map_err(|e| invalid(e, ErrorCode::InvalidOptions))?;
+                if let Some((rule, _)) = options
+                    .lints
+                    .iter()
+                    .find(|(rule, level)| !rule.configurable() && **level != RuleLevel::Error)
+                {
+                    return Err(ErrorInfo::new(
+                        0,
+                        format!("リントの設定が不正です: {} は常にエラーとして報告されるため変更できません", rule.name()),
+                        format!("/lints/{}", rule.name()),
+                        ErrorCode::InvalidOptions,
+                    ));
+                }
+                Ok(options)
+            }
+            None => Ok(LintOptions::default()),
+        }
+    }
+
+    /// ルールの重大度（無効の場合はNone）
+    pub fn severity(&self, rule: LintRule) -> Option<Severity> {
+        match self.lints.get(&rule) {
+            Some(RuleLevel::Off) => None,
+            Some(RuleLevel::Error) => Some(Severity::Error),
+            Some(RuleLevel::Warning) => Some(Severity::Warning),
+            Some(RuleLevel::Info) => Some(Severity::Info),
+            Some(RuleLevel::Hint) => Some(Severity::Hint),
+            None => Some(rule.default_severity()),
+        }
+    }
+}
+
+/// スキーマとそのすべてのサブスキーマ（オブジェクト）を、JSON Pointerとともに訪問する
+///
+/// ルートの `frontmatter` セクションもサブスキーマとして扱う
+pub(crate) fn walk_subschemas(schema: &Value, visit: &mut impl FnMut(&Map<String, Value>, &str)) {
+    walk(schema, "", visit);
+    if let Some(frontmatter) = schema.get("frontmatter") {
+        walk(frontmatter, "/frontmatter", visit);
+    }
+}
+
+fn walk(schema: &Value, pointer: &str, visit: &mut impl FnMut(&Map<String, Value>, &str)) {
+    let Some(object) = schema.as_object() else {
+        return;
+    };
+    visit(object, pointer);
+
+    for (key, value) in object {
+        let pointer = format!("{}/{}", pointer, escape_pointer_segment(key));
+        let keyword = key.as_str();
+        match value {
+            Value::Array(items)
+                if SCHEMA_KEYWORDS.contains(&keyword)
+                    || SCHEMA_ARRAY_KEYWORDS.contains(&keyword) =>
+            {
+                for (i, item) in items.iter().enumerate() {
+                    walk(item, &format!("{}/{}", pointer, i), visit);
+                }
+            }
+            Value::Object(_) if SCHEMA_KEYWORDS.contains(&keyword) => walk(value, &pointer, visit),
+            Value::Object(schemas) if SCHEMA_MAP_KEYWORDS.contains(&keyword) => {
+                for (name, schema) in schemas {
+                    walk(
+                        schema,
+                        &format!("{}/{}", pointer, escape_pointer_segment(name)),
+                        visit,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// スキーマにリントのルールを適用する
+///
+/// # 戻り値
+/// * 診断の配列（`code` は `SchemaLint`、`rule` にルール名、重大度は設定による）
+pub fn lint_schema(schema: &Value, options: &LintOptions) -> Vec<ErrorInfo> {
+    let mut linter = Linter {
+        options,
+        diagnostics: Vec::new(),
+    };
+    walk_subschemas(schema, &mut |object, pointer| {
+        linter.lint_subschema(object, pointer)
+    });
+    linter.lint_unused_definitions(schema);
+    linter.diagnostics
+}
+
+struct Linter<'a> {
+    options: &'a LintOptions,
+    diagnostics: Vec<ErrorInfo>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: LintRule, message: String, path: String) {
+        if let Some(severity) = self.options.severity(rule) {
+            let mut diagnostic =
+                ErrorInfo::new(0, message, path, ErrorCode::SchemaLint).with_severity(severity);
+            diagnostic.rule = Some(rule.name().to_string());
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn lint_subschema(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        self.lint_required(schema, pointer);
+        self.lint_enum_type(schema, pointer);
+        self.lint_bounds(schema, pointer);
+        self.lint_patterns(schema, pointer);
+        self.lint_additional_properties(schema, pointer);
+        self.lint_unknown_keywords(schema, pointer);
+    }
+
+    /// propertiesに存在しない項目がrequiredになっていないか
+    /// （allOfの中のpropertiesも定義として扱い、patternPropertiesで名前をパターン指定している場合は対象外）
+    fn lint_required(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        if let (Some(Value::Array(required)), Some(Value::Object(properties)), None) = (
+            schema.get("required"),
+            schema.get("properties"),
+            schema.get("patternProperties"),
+        ) {
+            let all_of = schema
+                .get("allOf")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten();
+            let defined: HashSet<&str> = properties
+                .keys()
+                .chain(
+                    all_of
+                        .filter_map(|s| s.get("properties")?.as_object())
+                        .flat_map(|p| p.keys()),
+                )
+                .map(String::as_str)
+                .collect();
+            for (i, item) in required.iter().enumerate() {
+                if let Some(name) = item.as_str().filter(|name| !defined.contains(name)) {
+                    self.report(
+                        LintRule::RequiredNotInProperties,
+                        format!(
+                            "required指定されたプロパティ '{}' がpropertiesに定義されていません",
+                            name
+                        ),
+                        format!("{}/required/{}", pointer, i),
+                    );
+                }
+            }
+        }
+    }
+
+    /// enum / const の値がtypeに合っているか
+    fn lint_enum_type(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => return,
+        };
+        let mut values: Vec<(String, &Value)> = match schema.get("enum") {
+            Some(Value::Array(values)) => values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (format!("{}/enum/{}", pointer, i), value))
+                .collect(),
+            _ => vec![],
+        };
+        if let Some(value) = schema.get("const") {
+            values.push((format!("{}/const", pointer), value));
+        }
+
+        for (path, value) in values {
+            if !types.iter().any(|t| matches_type(value, t)) {
+                self.report(
+                    LintRule::EnumTypeMismatch,
+                    format!(
+                        "値 {} は type（{}）に合わないため、検証を通りません",
+                        value,
+                        types.join(", ")
+                    ),
+                    path,
+                );
+            }
+        }
+    }
+
+    /// 下限が上限を超えていると、どの値も検証を通らない
+    fn lint_bounds(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        for (min_key, max_key) in BOUNDS {
+            if let (Some(min), Some(max)) = (
+                schema.get(min_key).and_then(Value::as_f64),
+                schema.get(max_key).and_then(Value::as_f64),
+            ) {
+                if min > max {
+                    self.report(
+                        LintRule::MinGreaterThanMax,
+                        format!(
+                            "'{}'（{}）が '{}'（{}）より大きいため、どの値も検証を通りません",
+                            min_key, schema[min_key], max_key, schema[max_key]
+                        ),
+                        format!("{}/{}", pointer, min_key),
+                    );
+                }
+            }
+        }
+    }
+
+    /// pattern / patternPropertiesの名前が正規表現としてコンパイルできるか
+    fn lint_patterns(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        let mut patterns: Vec<(String, &str)> = Vec::new();
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            patterns.push((format!("{}/pattern", pointer), pattern));
+        }
+        if let Some(Value::Object(properties)) = schema.get("patternProperties") {
+            for name in properties.keys() {
+                patterns.push((
+                    format!(
+                        "{}/patternProperties/{}",
+                        pointer,
+                        escape_pointer_segment(name)
+                    ),
+                    name,
+                ));
+            }
+        }
+
+        for (path, pattern) in patterns {
+            // バリデーションと同じ正規表現エンジンで確認する
+            let probe = serde_json::json!({ "type": "string", "pattern": pattern });
+            if jsonschema::options().build(&probe).is_err() {
+                self.report(
+                    LintRule::InvalidPattern,
+                    format!("'{}' は正規表現としてコンパイルできません", pattern),
+                    path,
+                );
+            }
+        }
+    }
+
+    /// additionalProperties: false はallOfの中で定義したプロパティも拒否する
+    fn lint_additional_properties(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        if schema.get("additionalProperties") == Some(&Value::Bool(false))
+            && schema.contains_key("allOf")
+        {
+            self.report(
+                LintRule::AdditionalPropertiesWithAllOf,
+                "additionalProperties: false はallOfの中で定義したプロパティも拒否します（unevaluatedProperties: false の利用を検討してください）"
+                    .to_string(),
+                format!("{}/additionalProperties", pointer),
+            );
+        }
+    }
+
+    /// 未知のキーワード（誤字の可能性が高い）
+    fn lint_unknown_keywords(&mut self, schema: &Map<String, Value>, pointer: &str) {
+        for key in schema.keys() {
+            let known = KNOWN_KEYWORDS.contains(&key.as_str())
+                || (pointer.is_empty() && ROOT_KEYWORDS.contains(&key.as_str()))
+                || key.starts_with("x-");
+            if known {
+                continue;
+            }
+            let message = match suggest_keyword(key) {
+                Some(suggestion) => format!(
+                    "未知のキーワード '{}' は無視されます（'{}' の誤り？）",
+                    key, suggestion
+                ),
+                None => format!(
+                    "未知のキーワード '{}' は無視されます（独自の注釈は 'x-' で始めてください）",
+                    key
+                ),
+            };
+            self.report(
+                LintRule::UnknownKeyword,
+                message,
+                format!("{}/{}", pointer, escape_pointer_segment(key)),
+            );
+        }
+    }
+
+    /// ルートの definitions / $defs のうち、どこからも参照されていないもの
+    ///
+    /// 定義だけを集めたスキーマ（他のファイルから参照される共通スキーマ）は対象外
+    fn lint_unused_definitions(&mut self, schema: &Value) {
+        let Some(root) = schema.as_object() else {
+            return;
+        };
+        let definitions_only = root
+            .keys()
+            .all(|key| DEFINITION_ONLY_KEYWORDS.contains(&key.as_str()) || key == "$defs");
+        if definitions_only {
+            return;
+        }
+
+        let root_id = root
+            .get("$id")
+            .and_then(Value::as_str)
+            .map(|id| id.trim_end_matches('#'));
+        let mut referenced: HashSet<String> = HashSet::new();
+        walk_subschemas(schema, &mut |object, _| {
+            let Some((target, fragment)) = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.split_once('#'))
+            else {
+                return;
+            };
+            if target.is_empty() || Some(target) == root_id {
+                // `/definitions/name/...` の先頭2つの要素
+                let mut segments = fragment.trim_start_matches('/').splitn(3, '/');
+                if let (Some(keyword), Some(name)) = (segments.next(), segments.next()) {
+                    referenced.insert(format!("{}/{}", keyword, name));
+                }
+            }
+        });
+
+        for keyword in ["definitions", "$defs"] {
+            let Some(Value::Object(definitions)) = root.get(keyword) else {
+                continue;
+            };
+            for name in definitions.keys() {
+                let segment = escape_pointer_segment(name);
+                if !referenced.contains(&format!("{}/{}", keyword, segment)) {
+                    self.report(
+                        LintRule::UnusedDefinition,
+                        format!("定義 '{}' はどこからも $ref で参照されていません", name),
+                        format!("/{}/{}", keyword, segment),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 値がJSON Schemaのtypeに合うか
+fn matches_type(value: &Value, type_name: &str) -> bool {
+    match type_name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        // 無効なtypeは別途エラーになる
+        _ => true,
+    }
+}
+
+/// 誤字と思われるキーワードに近い既知のキーワード（編集距離2以内）
+fn suggest_keyword(key: &str) -> Option<&'static str> {
+    if key.chars().count() < 4 {
+        return None;
+    }
+    KNOWN_KEYWORDS
+        .iter()
+        .map(|keyword| (edit_distance(key, keyword), *keyword))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, keyword)| keyword)
+}
+
+/// 大文字・小文字を区別しないレーベンシュタイン距離
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current.push(
+                (previous[j] + cost)
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            );
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(schema: &str, options: Option<&str>) -> Vec<ErrorInfo> {
+        let schema: Value = serde_yaml::from_str(schema).unwrap();
+        lint_schema(&schema, &LintOptions::parse(options).unwrap())
+    }
+
+    fn rules(diagnostics: &[ErrorInfo]) -> Vec<(&str, &str)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.rule.as_deref().unwrap_or_default(), d.path.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let schema = r##"
+type: object
+requird: [title]
+properties:
+  title:
+    type: string
+    enum: [a, 1]
+  tags:
+    type: array
+    minItems: 3
+    maxItems: 1
+    items:
+      type: string
+      pattern: "(["
+  meta:
+    type: object
+    properties:
+      id: { type: string }
+    additionalProperties: false
+    allOf:
+      - properties:
+          author: { $ref: "#/definitions/person" }
+    required: [author, missing]
+  level:
+    type: integer
+    const: 1.5
+definitions:
+  person: { type: string }
+  unused: { type: string }
+"##;
+        let diagnostics = lint(schema, None);
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                ("unknown-keyword", "/requird"),
+                ("enum-type-mismatch", "/properties/title/enum/1"),
+                ("min-greater-than-max", "/properties/tags/minItems"),
+                ("invalid-pattern", "/properties/tags/items/pattern"),
+                ("required-not-in-properties", "/properties/meta/required/1"),
+                (
+                    "additional-properties-with-all-of",
+                    "/properties/meta/additionalProperties"
+                ),
+                ("enum-type-mismatch", "/properties/level/const"),
+                ("unused-definition", "/definitions/unused"),
+            ]
+        );
+        assert!(diagnostics[0].message.contains("'required' の誤り"));
+        assert!(diagnostics
+            .iter()
+            .all(|d| matches!(d.code, ErrorCode::SchemaLint)));
+        let severities: Vec<Severity> = diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(
+            severities.iter().filter(|s| **s == Severity::Error).count(),
+            1
+        );
+        assert_eq!(diagnostics[3].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_lint_options() {
+        let schema = "type: object\nrequird: []\nproperties:\n  a: { type: string, minLength: 2, maxLength: 1 }\n";
+        let diagnostics = lint(
+            schema,
+            Some("lints:\n  unknown-keyword: off\n  min-greater-than-max: error\n"),
+        );
+        assert_eq!(
+            rules(&diagnostics),
+            vec![("min-greater-than-max", "/properties/a/minLength")]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+
+        let error = LintOptions::parse(Some("lints:\n  no-such-rule: off\n")).unwrap_err();
+        assert!(error.message.contains("リントの設定が不正です"));
+        assert!(matches!(error.code, ErrorCode::InvalidOptions));
+        assert!(matches!(
+            LintOptions::parse(Some("lints: [")).unwrap_err().code,
+            ErrorCode::YamlParse
+        ));
+        assert!(LintOptions::parse(Some("")).unwrap().lints.is_empty());
+
+        // invalid-pattern はスキーマをコンパイルできなくなるため常にエラー
+        for level in ["off", "warning"] {
+            let error = LintOptions::parse(Some(&format!("lints: {{invalid-pattern: {}}}", level)))
+                .unwrap_err();
+            assert!(matches!(error.code, ErrorCode::InvalidOptions));
+            assert_eq!(error.path, "/lints/invalid-pattern");
+        }
+        assert!(LintOptions::parse(Some("lints: {invalid-pattern: error}")).is_ok());
+    }
+
+    #[test]
+    fn test_lint_ignores_annotations_and_shared_definitions() {
+        // x- で始まる注釈、ルートの frontmatter、定義だけの共通スキーマ
+        let schema = "x-note: memo\nfrontmatter:\n  type: object\n  propertes: {}\n";
+        assert_eq!(
+            rules(&lint(schema, None)),
+            vec![("unknown-keyword", "/frontmatter/propertes")]
+        );
+
+        let schema = "$id: https://example.com/s.yaml\ndefinitions:\n  person: { type: string }\n";
+        assert!(lint(schema, None).is_empty());
+
+        // $id を付けた参照も参照として数える
+        let schema = "$id: https://example.com/s.yaml\n$ref: https://example.com/s.yaml#/$defs/a\n$defs:\n  a: { type: string }\n";
+        assert!(lint(schema, None).is_empty());
+    }
+}
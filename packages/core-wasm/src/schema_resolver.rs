@@ -0,0 +1,536 @@
+//! schema_resolver.rs
+//!
+//! フロントマターの `schema_path` などのスキーマ参照を、ワークスペース内のスキーマに解決するモジュール。
+//! - 参照の正規化（参照元のノートからの相対パス / ワークスペースルートからの絶対パス）
+//! - 解決方法を差し替えられる `SchemaResolver` トレイト
+//!   - `RelativeResolver`: 参照元からの相対パスのみを解決
+//!   - `WorkspaceResolver`: ワークスペースルートからの絶対パス（`/` 始まり）のみを解決
+//!   - `MemoryResolver`: 登録済みのスキーマ文字列から解決（wasmではJSから登録する）
+//!   - `FileSystemResolver`: ファイルシステム上のワークスペースから解決（ネイティブのみ）
+//! - 登録済みスキーマ間の `$ref` をまとめたコンパイル（`SchemaRegistry::compile`）
+//!
+//! Web版とデスクトップ版で同じ規則で解決されるよう、パスの正規化はすべてこのモジュールで行う。
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{ErrorInfo, ValidationResult};
+use crate::error_code::ErrorCode;
+use crate::validate::CompiledSchema;
+
+/// 解決したスキーマ
+///
+/// # フィールド
+/// - `path`: ワークスペースルートからの正規化したパス（先頭の `/` なし）
+/// - `source`: スキーマ文字列（YAML/JSON形式）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedSchema {
+    pub path: String,
+    pub source: String,
+}
+
+/// スキーマの参照を解決する
+pub trait SchemaResolver {
+    /// `reference` を `base`（参照元のノートやスキーマのワークスペース内パス）からの参照として解決する
+    ///
+    /// # エラーケース
+    /// - 参照の形式が不正、またはワークスペースの外を指す場合
+    /// - スキーマが見つからない、または読み込めない場合
+    fn resolve(&self, reference: &str, base: &str) -> Result<ResolvedSchema, ErrorInfo>;
+
+    /// ルートの `$id` が `id`（フラグメントなしのURL）であるスキーマを探す
+    ///
+    /// `$ref` の参照先をURLで指定した場合に使う。スキーマを列挙できない場合は常にNone
+    fn resolve_id(&self, _id: &str) -> Option<ResolvedSchema> {
+        None
+    }
+}
+
+impl<R: SchemaResolver + ?Sized> SchemaResolver for &R {
+    fn resolve(&self, reference: &str, base: &str) -> Result<ResolvedSchema, ErrorInfo> {
+        (**self).resolve(reference, base)
+    }
+
+    fn resolve_id(&self, id: &str) -> Option<ResolvedSchema> {
+        (**self).resolve_id(id)
+    }
+}
+
+fn resolve_error(reference: &str, reason: impl std::fmt::Display) -> ErrorInfo {
+    ErrorInfo::new(
+        0,
+        format!("スキーマを解決できません: {} ({})", reference, reason),
+        "",
+        ErrorCode::SchemaResolve,
+    )
+}
+
+/// 参照を正規化したワークスペース内のパスに変換する
+///
+/// - `/` で始まる参照: ワークスペースルートからのパス
+/// - それ以外: `base` のあるディレクトリからの相対パス
+/// - `\` は `/` として扱い、`.` と `..` を取り除く
+///
+/// # 戻り値
+/// * 先頭の `/` を除いたパス（例: `schemas/note.yaml`）
+///
+/// # エラーケース
+/// - 空の参照、URL、ドライブ文字付きのパス
+/// - ワークスペースの外（ルートより上）を指す参照
+pub fn normalize_path(reference: &str, base: &str) -> Result<String, ErrorInfo> {
+    let reference = reference.trim().replace('\\', "/");
+    if reference.is_empty() {
+        return Err(resolve_error(&reference, "パスが空です"));
+    }
+    if reference.contains("://") || reference.as_bytes().get(1) == Some(&b':') {
+        return Err(resolve_error(
+            &reference,
+            "URLやドライブ文字付きのパスは参照できません",
+        ));
+    }
+
+    let base = base.replace('\\', "/");
+    let base_dir = match reference.starts_with('/') {
+        true => "",
+        false => base.rsplit_once('/').map_or("", |(dir, _)| dir),
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in base_dir.split('/').chain(reference.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if segments.pop().is_none() {
+                    return Err(resolve_error(
+                        &reference,
+                        "ワークスペースの外を参照しています",
+                    ));
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() || reference.ends_with('/') {
+        return Err(resolve_error(&reference, "ファイル名がありません"));
+    }
+    Ok(segments.join("/"))
+}
+
+/// 参照元からの相対パスのみを解決する（`/` で始まる参照はエラー）
+pub struct RelativeResolver<R>(pub R);
+
+impl<R: SchemaResolver> SchemaResolver for RelativeResolver<R> {
+    fn resolve(&self, reference: &str, base: &str) -> Result<ResolvedSchema, ErrorInfo> {
+        if reference.trim().starts_with('/') {
+            return Err(resolve_error(
+                reference,
+                "相対パス（例: ./schema.yaml）で指定してください",
+            ));
+        }
+        self.0.resolve(reference, base)
+    }
+
+    fn resolve_id(&self, id: &str) -> Option<ResolvedSchema> {
+        self.0.resolve_id(id)
+    }
+}
+
+/// ワークスペースルートからの絶対パス（`/` 始まり）のみを解決する
+pub struct WorkspaceResolver<R>(pub R);
+
+impl<R: SchemaResolver> SchemaResolver for WorkspaceResolver<R> {
+    fn resolve(&self, reference: &str, base: &str) -> Result<ResolvedSchema, ErrorInfo> {
+        if !reference.trim().starts_with('/') {
+            return Err(resolve_error(
+                reference,
+                "ワークスペースルートからのパス（例: /schemas/note.yaml）で指定してください",
+            ));
+        }
+        self.0.resolve(reference, base)
+    }
+
+    fn resolve_id(&self, id: &str) -> Option<ResolvedSchema> {
+        self.0.resolve_id(id)
+    }
+}
+
+/// 登録済みのスキーマ文字列から解決する
+#[derive(Debug, Default, Clone)]
+pub struct MemoryResolver {
+    schemas: BTreeMap<String, String>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// スキーマを登録する
+    ///
+    /// # 戻り値
+    /// * 正規化した登録パス（同じパスのスキーマは置き換える）
+    pub fn register(&mut self, path: &str, source: impl Into<String>) -> Result<String, ErrorInfo> {
+        let path = normalize_path(path, "")?;
+        self.schemas.insert(path.clone(), source.into());
+        Ok(path)
+    }
+
+    /// 登録済みのスキーマを削除する
+    pub fn unregister(&mut self, path: &str) -> bool {
+        normalize_path(path, "").is_ok_and(|path| self.schemas.remove(&path).is_some())
+    }
+
+    /// 登録済みのパス（正規化済み、辞書順）
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.schemas.keys().map(String::as_str)
+    }
+}
+
+impl SchemaResolver for MemoryResolver {
+    fn resolve(&self, reference: &str, base: &str) -> Result<ResolvedSchema, ErrorInfo> {
+        let path = normalize_path(reference, base)?;
+        match self.schemas.get(&path) {
+            Some(source) => Ok(ResolvedSchema {
+                source: source.clone(),
+                path,
+            }),
+            None => Err(resolve_error(
+                reference,
+                format!("/{} は登録されていません", path),
+            )),
+        }
+    }
+
+    fn resolve_id(&self, id: &str) -> Option<ResolvedSchema> {
+        let id = id.trim_end_matches('#');
+        self.schemas.iter().find_map(|(path, source)| {
+            let value: serde_json::Value = serde_yaml::from_str(source).ok()?;
+            (value.get("$id")?.as_str()?.trim_end_matches('#') == id).then(|| ResolvedSchema {
+                path: path.clone(),
+                source: source.clone(),
+            })
+        })
+    }
+}
+
+/// ファイルシステム上のワークスペースから解決する
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileSystemResolver {
+    root: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileSystemResolver {
+    /// `root` をワークスペースルートとする
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SchemaResolver for FileSystemResolver {
+    fn resolve(&self, reference: &str, base: &str) -> Result<ResolvedSchema, ErrorInfo> {
+        let path = normalize_path(reference, base)?;
+        match std::fs::read_to_string(self.root.join(&path)) {
+            Ok(source) => Ok(ResolvedSchema { path, source }),
+            Err(e) => Err(resolve_error(reference, format!("/{}: {}", path, e))),
+        }
+    }
+}
+
+/// スキーマのレジストリ（JSから登録したスキーマを `MemoryResolver` で解決する）
+///
+/// # 使用例（JS）
+/// ```js
+/// const registry = new SchemaRegistry();
+/// registry.register("/schemas/note.yaml", schemaYaml);
+/// const result = JSON.parse(registry.resolve("../schemas/note.yaml", "/notes/today.md"));
+/// registry.free();
+/// ```
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct SchemaRegistry {
+    resolver: MemoryResolver,
+}
+
+/// `SchemaRegistry::resolve` の結果
+///
+/// # フィールド
+/// - `success`: 解決できた場合はtrue
+/// - `path`: 正規化したパス（先頭の `/` あり、失敗時は空文字列）
+/// - `schema`: スキーマ文字列（失敗時は空文字列）
+/// - `errors`: エラー情報の配列（成功時は空配列）
+#[derive(Debug, Serialize)]
+struct ResolveResult {
+    success: bool,
+    path: String,
+    schema: String,
+    errors: Vec<ErrorInfo>,
+}
+
+#[wasm_bindgen]
+impl SchemaRegistry {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SchemaRegistry {
+        SchemaRegistry::default()
+    }
+
+    /// スキーマを登録し、結果をJSON文字列で返す
+    ///
+    /// * 成功時: `{"success": true, "errors": []}`
+    /// * 失敗時（パスが不正な場合）: `{"success": false, "errors": [ErrorInfo]}`
+    pub fn register(&mut self, path: &str, schema_str: &str) -> String {
+        match self.resolver.register(path, schema_str) {
+            Ok(_) => ValidationResult::success(),
+            Err(error) => ValidationResult::single_error(error),
+        }
+        .to_json()
+    }
+
+    /// 登録済みのスキーマを削除する（登録されていなければfalse）
+    pub fn unregister(&mut self, path: &str) -> bool {
+        self.resolver.unregister(path)
+    }
+
+    /// 登録済みのパスの一覧をJSON配列で返す
+    pub fn paths(&self) -> String {
+        let paths: Vec<String> = self
+            .resolver
+            .paths()
+            .map(|path| format!("/{}", path))
+            .collect();
+        serde_json::to_string(&paths).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// 参照を解決し、参照している他の登録済みスキーマとまとめてコンパイルする
+    ///
+    /// # 引数
+    /// * `reference` - `schema_path` などの参照
+    /// * `base` - 参照元のノートのワークスペース内パス
+    ///
+    /// # 戻り値
+    /// * コンパイル済みスキーマ（解決できない `$ref` や循環参照は `compile_result` で報告される）
+    pub fn compile(&self, reference: &str, base: &str) -> CompiledSchema {
+        CompiledSchema::resolve(&self.resolver, reference, base)
+    }
+
+    /// 参照を解決し、結果をJSON文字列で返す
+    ///
+    /// # 引数
+    /// * `reference` - `schema_path` などの参照（相対パスまたは `/` 始まりのワークスペース内パス）
+    /// * `base` - 参照元のノートのワークスペース内パス（例: `/notes/today.md`）
+    ///
+    /// # 戻り値
+    /// * `{"success": true, "path": "/schemas/note.yaml", "schema": "...", "errors": []}`
+    /// * 失敗時は `success: false` と `SchemaResolve` のエラー情報
+    pub fn resolve(&self, reference: &str, base: &str) -> String {
+        let result = match self.resolver.resolve(reference, base) {
+            Ok(resolved) => ResolveResult {
+                success: true,
+                path: format!("/{}", resolved.path),
+                schema: resolved.source,
+                errors: vec![],
+            },
+            Err(error) => ResolveResult {
+                success: false,
+                path: String::new(),
+                schema: String::new(),
+                errors: vec![error],
+            },
+        };
+        serde_json::to_string(&result).unwrap_or_else(|_| {
+            r#"{"success":false,"path":"","schema":"","errors":[{"line":0,"message":"Failed to serialize errors","path":""}]}"#.to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path("./schema.yaml", "notes/today.md").unwrap(),
+            "notes/schema.yaml"
+        );
+        assert_eq!(
+            normalize_path("schema.yaml", "/notes/today.md").unwrap(),
+            "notes/schema.yaml"
+        );
+        assert_eq!(
+            normalize_path("../schemas/./note.yaml", "notes/daily/today.md").unwrap(),
+            "notes/schemas/note.yaml"
+        );
+        assert_eq!(
+            normalize_path("/schemas/note.yaml", "notes/today.md").unwrap(),
+            "schemas/note.yaml"
+        );
+        assert_eq!(
+            normalize_path("..\\schemas\\note.yaml", "notes\\today.md").unwrap(),
+            "schemas/note.yaml"
+        );
+        assert_eq!(normalize_path("note.yaml", "").unwrap(), "note.yaml");
+
+        for (reference, base) in [
+            ("", "today.md"),
+            ("../schema.yaml", "today.md"),
+            ("/../schema.yaml", "notes/today.md"),
+            ("https://example.com/schema.yaml", "today.md"),
+            ("C:/schemas/note.yaml", "today.md"),
+            ("./schemas/", "today.md"),
+        ] {
+            let error = normalize_path(reference, base).unwrap_err();
+            assert!(
+                matches!(error.code, ErrorCode::SchemaResolve),
+                "{}",
+                reference
+            );
+        }
+    }
+
+    #[test]
+    fn test_memory_and_restricted_resolvers() {
+        let mut registry = MemoryResolver::new();
+        assert_eq!(
+            registry
+                .register("/schemas/note.yaml", "type: object")
+                .unwrap(),
+            "schemas/note.yaml"
+        );
+
+        let resolved = registry
+            .resolve("../schemas/note.yaml", "notes/today.md")
+            .unwrap();
+        assert_eq!(resolved.path, "schemas/note.yaml");
+        assert_eq!(resolved.source, "type: object");
+        let error = registry
+            .resolve("./missing.yaml", "notes/today.md")
+            .unwrap_err();
+        assert!(error.message.contains("/notes/missing.yaml"));
+
+        let relative = RelativeResolver(&registry);
+        assert!(relative
+            .resolve("../schemas/note.yaml", "notes/today.md")
+            .is_ok());
+        assert!(relative
+            .resolve("/schemas/note.yaml", "notes/today.md")
+            .is_err());
+
+        let workspace = WorkspaceResolver(&registry);
+        assert!(workspace
+            .resolve("/schemas/note.yaml", "notes/today.md")
+            .is_ok());
+        assert!(workspace
+            .resolve("../schemas/note.yaml", "notes/today.md")
+            .is_err());
+
+        assert!(registry.unregister("schemas/note.yaml"));
+        assert_eq!(registry.paths().count(), 0);
+    }
+
+    #[test]
+    fn test_file_system_resolver() {
+        let root = std::env::temp_dir().join(format!("core-wasm-resolver-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("schemas")).unwrap();
+        std::fs::write(root.join("schemas/note.yaml"), "type: object").unwrap();
+
+        let resolver = FileSystemResolver::new(&root);
+        let resolved = resolver
+            .resolve("/schemas/note.yaml", "notes/today.md")
+            .unwrap();
+        assert_eq!(
+            (resolved.path.as_str(), resolved.source.as_str()),
+            ("schemas/note.yaml", "type: object")
+        );
+        let error = resolver
+            .resolve("./missing.yaml", "notes/today.md")
+            .unwrap_err();
+        assert!(matches!(error.code, ErrorCode::SchemaResolve));
+        // ワークスペースの外は読まない
+        assert!(resolver
+            .resolve("../../etc/passwd", "notes/today.md")
+            .is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_schema_registry_json() {
+        let mut registry = SchemaRegistry::new();
+        assert!(registry
+            .register("/schemas/note.yaml", "type: object")
+            .contains(r#""success":true"#));
+        assert!(registry
+            .register("../x.yaml", "")
+            .contains(r#""success":false"#));
+        assert_eq!(registry.paths(), r#"["/schemas/note.yaml"]"#);
+
+        let result: serde_json::Value =
+            serde_json::from_str(&registry.resolve("../schemas/note.yaml", "/notes/today.md"))
+                .unwrap();
+        assert_eq!(result["success"], true);
+        assert_eq!(result["path"], "/schemas/note.yaml");
+        assert_eq!(result["schema"], "type: object");
+
+        let result: serde_json::Value =
+            serde_json::from_str(&registry.resolve("./none.yaml", "/notes/today.md")).unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["errors"][0]["code"], "SchemaResolve");
+    }
+
+    #[test]
+    fn test_memory_resolver_resolve_id() {
+        let mut registry = MemoryResolver::new();
+        registry
+            .register(
+                "schemas/note.schema.yaml",
+                "$id: https://yamalnote.example/schemas/note.schema.yaml\ntype: object\n",
+            )
+            .unwrap();
+        registry.register("schemas/broken.yaml", "[").unwrap();
+
+        let resolved = registry
+            .resolve_id("https://yamalnote.example/schemas/note.schema.yaml")
+            .unwrap();
+        assert_eq!(resolved.path, "schemas/note.schema.yaml");
+        assert!(registry
+            .resolve_id("https://yamalnote.example/schemas/none.yaml")
+            .is_none());
+        assert!(RelativeResolver(&registry)
+            .resolve_id("https://yamalnote.example/schemas/note.schema.yaml#")
+            .is_some());
+    }
+
+    #[test]
+    fn test_schema_registry_compile() {
+        let mut registry = SchemaRegistry::new();
+        registry.register(
+            "/schemas/note.yaml",
+            "type: object\nproperties:\n  author:\n    $ref: ./common.yaml#/definitions/person\n",
+        );
+        registry.register(
+            "/schemas/common.yaml",
+            "definitions:\n  person:\n    type: object\n    required: [name]\n",
+        );
+
+        let compiled = registry.compile("../schemas/note.yaml", "/notes/today.md");
+        assert!(compiled.compile_result().contains(r#""success":true"#));
+        assert!(compiled
+            .validate("author:\n  name: a\n")
+            .contains(r#""success":true"#));
+        let result: serde_json::Value =
+            serde_json::from_str(&compiled.validate("author: {}\n")).unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["errors"][0]["path"], "/author");
+
+        registry.unregister("/schemas/common.yaml");
+        let result: serde_json::Value =
+            serde_json::from_str(&registry.compile("/schemas/note.yaml", "").compile_result())
+                .unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["errors"][0]["code"], "SchemaResolve");
+    }
+}
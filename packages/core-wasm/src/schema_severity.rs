@@ -0,0 +1,194 @@
+//! schema_severity.rs
+//!
+//! スキーマの `x-severity` による検証エラーの重大度の変更。
+//! - `x-severity: warning`: そのサブスキーマ（子孫を含む）で発生したエラーをすべて `warning` にする
+//! - `x-severity: {maxLength: warning}`: 指定したキーワードのエラーだけ重大度を変える
+//! - プロパティのスキーマに書いた場合、そのプロパティがない `required` のエラーにも適用する
+//! - 深い位置の指定ほど優先する（`error` を指定して元に戻すこともできる）
+//!
+//! ```yaml
+//! properties:
+//!   summary:
+//!     type: string
+//!     maxLength: 200
+//!     x-severity: warning        # 任意の慣習（なくても、長すぎてもノートは有効）
+//! required: [title, summary]
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::error::{ErrorInfo, Severity};
+use crate::error_code::ErrorCode;
+
+/// 重大度を指定するキーワード
+pub const SEVERITY_KEY: &str = "x-severity";
+
+/// サブスキーマを値に持つキーワード（`x-severity` を探すときにたどる）
+const CONTAINER_KEYWORDS: [&str; 9] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependentSchemas",
+    "dependencies",
+    "allOf",
+    "anyOf",
+    "oneOf",
+];
+
+/// 検証エラーの重大度を、エラーを出したスキーマのキーワードの位置から求める
+///
+/// # 引数
+/// * `schema` - 検証に使ったスキーマ
+/// * `schema_path` - エラーを出したキーワードのJSON Pointer（`$ref` を経由した場合は `$ref` を含む）
+/// * `missing_property` - `required` のエラーの場合、存在しないプロパティ名
+///
+/// # 戻り値
+/// * `x-severity` の指定がない場合は `Severity::Error`
+pub(crate) fn error_severity(
+    schema: &Value,
+    schema_path: &str,
+    missing_property: Option<&str>,
+) -> Severity {
+    let segments: Vec<String> = schema_path
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let Some((keyword, parents)) = segments.split_last() else {
+        return declared_severity(schema, "").unwrap_or_default();
+    };
+
+    let mut severity = declared_severity(schema, keyword);
+    let mut node = schema;
+    // 直前のセグメントがプロパティ名などの場合、nodeはサブスキーマではない
+    let mut in_container = false;
+    for segment in parents {
+        let next = match segment.as_str() {
+            "$ref" if !in_container => node
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.strip_prefix('#'))
+                .and_then(|fragment| schema.pointer(fragment)),
+            _ => match node {
+                Value::Object(object) => object.get(segment),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            },
+        };
+        // 外部の参照などでたどれない場合は、そこまでの指定を使う
+        let Some(next) = next else {
+            break;
+        };
+        in_container = !in_container && CONTAINER_KEYWORDS.contains(&segment.as_str());
+        node = next;
+        if !in_container {
+            severity = declared_severity(node, keyword).or(severity);
+        }
+    }
+
+    // プロパティ自体に書いた指定は、そのプロパティの `required` にも適用する
+    if let (Some(property), "required") = (missing_property, keyword.as_str()) {
+        let property_schema = node
+            .get("properties")
+            .and_then(|properties| properties.get(property));
+        if let Some(property_schema) = property_schema {
+            severity = declared_severity(property_schema, keyword).or(severity);
+        }
+    }
+    severity.unwrap_or_default()
+}
+
+/// サブスキーマの `x-severity` から、キーワードの重大度を読む（不正な値は無視する）
+fn declared_severity(schema: &Value, keyword: &str) -> Option<Severity> {
+    match schema.get(SEVERITY_KEY)? {
+        Value::Object(keywords) => keywords
+            .get(keyword)
+            .and_then(|level| serde_json::from_value(level.clone()).ok()),
+        level => serde_json::from_value(level.clone()).ok(),
+    }
+}
+
+/// `x-severity` の値が正しいことを確認する（重大度の文字列、またはキーワードから重大度へのマップ）
+pub(crate) fn check_severity_keyword(
+    schema: &Map<String, Value>,
+    pointer: &str,
+) -> Option<ErrorInfo> {
+    let value = schema.get(SEVERITY_KEY)?;
+    let is_severity = |level: &Value| serde_json::from_value::<Severity>(level.clone()).is_ok();
+    let (path, invalid) = match value {
+        Value::Object(keywords) => keywords
+            .iter()
+            .find(|(_, level)| !is_severity(level))
+            .map(|(keyword, level)| (format!("{}/{}/{}", pointer, SEVERITY_KEY, keyword), level))?,
+        level if !is_severity(level) => (format!("{}/{}", pointer, SEVERITY_KEY), level),
+        _ => return None,
+    };
+    Some(ErrorInfo::new(
+        0,
+        format!(
+            "スキーマ構文エラー: {} の値 {} が不正です。error, warning, info, hintのいずれか（またはキーワードごとの指定）である必要があります",
+            SEVERITY_KEY, invalid
+        ),
+        path,
+        ErrorCode::SchemaCompile,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_severity_follows_schema_path() {
+        let schema = json!({
+            "definitions": {
+                "tag": {"type": "string", "x-severity": {"pattern": "hint"}, "pattern": "^[a-z]+$"}
+            },
+            "properties": {
+                "summary": {"type": "string", "maxLength": 10, "x-severity": "warning"},
+                "tags": {"items": {"$ref": "#/definitions/tag"}},
+                "x-severity": {"type": "string"}
+            },
+            "required": ["summary", "title"]
+        });
+
+        assert_eq!(
+            error_severity(&schema, "/properties/summary/maxLength", None),
+            Severity::Warning
+        );
+        assert_eq!(
+            error_severity(&schema, "/properties/tags/items/$ref/pattern", None),
+            Severity::Hint
+        );
+        assert_eq!(
+            error_severity(&schema, "/properties/tags/items/$ref/type", None),
+            Severity::Error
+        );
+        // プロパティ名が x-severity でもサブスキーマの指定とはみなさない
+        assert_eq!(
+            error_severity(&schema, "/properties/x-severity/type", None),
+            Severity::Error
+        );
+        assert_eq!(
+            error_severity(&schema, "/required", Some("summary")),
+            Severity::Warning
+        );
+        assert_eq!(
+            error_severity(&schema, "/required", Some("title")),
+            Severity::Error
+        );
+    }
+
+    #[test]
+    fn test_check_severity_keyword() {
+        let valid = json!({"x-severity": "info", "properties": {}});
+        assert!(check_severity_keyword(valid.as_object().unwrap(), "").is_none());
+
+        let invalid = json!({"x-severity": {"maxLength": "warn"}});
+        let error = check_severity_keyword(invalid.as_object().unwrap(), "/properties/a").unwrap();
+        assert_eq!(error.path, "/properties/a/x-severity/maxLength");
+        assert!(error.message.contains("\"warn\""));
+    }
+}
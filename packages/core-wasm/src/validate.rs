@@ -6,17 +6,26 @@
 //! バリデーション結果をJSON形式で返します。
 //!
 //! - serde_yaml, serde_jsonによるパース
-//! - jsonschema-validによるスキーマ検証
-//! - エラー情報の構造化
+//! - jsonschemaによるスキーマ検証（`$schema` で draft-04 / 06 / 07 / 2019-09 / 2020-12 を選択、省略時はdraft-07）
+//! - 複数ファイルにまたがるスキーマ（外部の `$ref`）のコンパイル（`schema_bundle`）
+//! - エラー情報の構造化（スキーマの `x-severity` による重大度の変更を含む）
+//! - yaml_spanによるエラー位置（行番号）の特定
+//! - Markdownから生成したYAMLのエラーを元のMarkdownの行へ対応付け
 //!
 //! WASMバインディング経由でJavaScriptから利用されることを想定しています。
 
-use crate::error::{ErrorInfo, ValidationResult};
+use crate::error::{ErrorInfo, ErrorParams, ValidationResult};
 use crate::error_code::ErrorCode;
+use crate::md_transform::{self, TransformOptions};
+use crate::schema_bundle;
+use crate::schema_resolver::SchemaResolver;
+use crate::schema_severity;
+use crate::yaml_span::{SourceSpan, SpanMap};
 use serde_json::Value;
 
-use jsonschema_valid::schemas::Draft;
-use jsonschema_valid::Config;
+use jsonschema::error::{TypeKind, ValidationErrorKind};
+use jsonschema::{Draft, Validator};
+use wasm_bindgen::prelude::*;
 
 /// YAMLデータを指定スキーマでバリデーションし、結果をJSON文字列で返す
 ///
@@ -27,6 +36,8 @@ use jsonschema_valid::Config;
 /// # 返り値
 /// * バリデーション成功時: `{"success": true, "errors": []}`
 /// * バリデーション失敗時: `{"success": false, "errors": [ErrorInfo, ...]}`
+/// * スキーマの `x-severity` で重大度を下げたエラー（`warning` など）だけの場合は
+///   `success: true` で、`errors` にその診断を含む
 ///
 /// # エラーケース
 /// - YAMLパースエラー、スキーマパースエラー、スキーマコンパイルエラー時も
@@ -36,81 +47,448 @@ use jsonschema_valid::Config;
 /// - WASMバインディング経由でJSから呼び出される
 pub fn validate_yaml(yaml_str: &str, schema_str: &str) -> String {
     // YAMLをパース
-    let yaml_value: Value = match serde_yaml::from_str(yaml_str) {
+    let yaml_value = match parse_yaml(yaml_str) {
         Ok(v) => v,
-        Err(e) => {
-            return ValidationResult::error(vec![ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse)]).to_json();
-        }
+        Err(result) => return result.to_json(),
     };
 
-    // スキーマをパース
-    let schema_value: Value = match serde_yaml::from_str(schema_str) {
-        Ok(v) => v,
-        Err(e) => {
-            return ValidationResult::error(vec![ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse)]).to_json();
-        }
+    // スキーマをパース・コンパイル
+    let compiled = match compile(schema_str) {
+        Ok(compiled) => compiled,
+        Err(result) => return result.to_json(),
     };
 
-    // スキーマをコンパイル
-    let compiled = match Config::from_schema(&schema_value, Some(Draft::Draft7)) {
-        Ok(c) => c,
-        Err(e) => {
-            return ValidationResult::error(vec![ErrorInfo::new(
-                0,
-                format!("Schema compile error: {}", e),
-                "",
-                ErrorCode::SchemaCompile,
-            )])
-            .to_json();
+    // バリデーション実行
+    validate_value(yaml_str, &yaml_value, &compiled).to_json()
+}
+
+/// Markdownを見出し構造のYAMLに変換し、スキーマでバリデーションする
+///
+/// # 引数
+/// * `md_str` - バリデーション対象のMarkdown文字列（フロントマターを含んでよい）
+/// * `schema_str` - JSON Schema（YAMLまたはJSON形式）
+/// * `options_str` - Markdown → YAML変換のオプション（YAML/JSON形式、任意）
+///
+/// # 返り値
+/// * `validate_yaml` と同じ形式のJSON文字列
+/// * スキーマ検証エラーの位置（`line` など）は生成したYAMLではなく、
+///   フロントマターを含む元のMarkdown上の行範囲を指す
+pub fn validate_markdown(md_str: &str, schema_str: &str, options_str: Option<&str>) -> String {
+    match compile(schema_str) {
+        Ok(compiled) => validate_markdown_value(md_str, &compiled, options_str).to_json(),
+        Err(result) => result.to_json(),
+    }
+}
+
+/// パース済みのスキーマと、そこからコンパイルしたバリデータ
+///
+/// スキーマは `x-severity` による重大度の判定に使う
+pub(crate) struct Compiled {
+    pub(crate) schema: Value,
+    pub(crate) validator: Validator,
+}
+
+/// コンパイル済みスキーマ
+///
+/// 同じスキーマで繰り返しバリデーションする場合（エディタでの入力ごとの再検証など）に、
+/// スキーマのパースとコンパイルをスキーマ変更時の1回だけに抑えるために利用する。
+/// コンパイルしたバリデータを保持し、`validate` などの呼び出しごとには再コンパイルしない。
+///
+/// # 使用例（JS）
+/// ```js
+/// const compiled = new CompiledSchema(schemaYaml);
+/// const result = JSON.parse(compiled.validate(noteYaml));
+/// compiled.free();
+/// ```
+#[wasm_bindgen]
+pub struct CompiledSchema {
+    schema: Result<Compiled, ValidationResult>,
+}
+
+#[wasm_bindgen]
+impl CompiledSchema {
+    /// スキーマ文字列（YAMLまたはJSON形式）をパース・コンパイルする
+    ///
+    /// スキーマが不正な場合も生成は成功し、エラーは `compile_result` と
+    /// `validate` の戻り値で報告される
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema_str: &str) -> CompiledSchema {
+        CompiledSchema {
+            schema: compile(schema_str),
+        }
+    }
+
+    /// スキーマのコンパイル結果をJSON文字列で返す
+    ///
+    /// * 成功時: `{"success": true, "errors": []}`
+    /// * 失敗時: `{"success": false, "errors": [ErrorInfo, ...]}`
+    pub fn compile_result(&self) -> String {
+        match &self.schema {
+            Ok(_) => ValidationResult::success().to_json(),
+            Err(result) => result.to_json(),
+        }
+    }
+
+    /// YAMLデータをこのスキーマでバリデーションし、結果をJSON文字列で返す
+    ///
+    /// 戻り値の形式は `validate_yaml` と同じ
+    pub fn validate(&self, yaml_str: &str) -> String {
+        let yaml_value = match parse_yaml(yaml_str) {
+            Ok(v) => v,
+            Err(result) => return result.to_json(),
+        };
+
+        match &self.schema {
+            Ok(compiled) => validate_value(yaml_str, &yaml_value, compiled).to_json(),
+            Err(result) => result.to_json(),
+        }
+    }
+
+    /// Markdownをこのスキーマでバリデーションし、結果をJSON文字列で返す
+    ///
+    /// 引数・戻り値の形式は `validate_markdown` と同じ
+    pub fn validate_markdown(&self, md_str: &str, options_str: Option<String>) -> String {
+        match &self.schema {
+            Ok(compiled) => {
+                validate_markdown_value(md_str, compiled, options_str.as_deref()).to_json()
+            }
+            Err(result) => result.to_json(),
+        }
+    }
+
+    /// `resolver` で解決したスキーマを、参照している他のスキーマとまとめてコンパイルする
+    ///
+    /// 型パラメータを持つためJSには公開しない（JSからは `SchemaRegistry::compile` を使う）
+    ///
+    /// # 引数
+    /// * `resolver` - スキーマファイルの解決方法（ネイティブでは `FileSystemResolver` など）
+    /// * `reference` - スキーマの参照（`schema_path` など）
+    /// * `base` - 参照元のワークスペース内パス
+    ///
+    /// 解決・バンドルに失敗した場合も生成は成功し、エラーは `compile_result` と
+    /// `validate` の戻り値で報告される（`SchemaResolve` など）
+    pub(crate) fn resolve<R: SchemaResolver + ?Sized>(
+        resolver: &R,
+        reference: &str,
+        base: &str,
+    ) -> CompiledSchema {
+        CompiledSchema {
+            schema: schema_bundle::bundle_schema(resolver, reference, base)
+                .map_err(ValidationResult::single_error)
+                .and_then(compile_value),
         }
+    }
+}
+
+/// Markdownから生成したドキュメントをバリデーションし、エラー位置をMarkdown上の行に対応付ける
+fn validate_markdown_value(
+    md_str: &str,
+    compiled: &Compiled,
+    options_str: Option<&str>,
+) -> ValidationResult {
+    let options = match TransformOptions::parse(options_str) {
+        Ok(options) => options,
+        Err(error) => return ValidationResult::single_error(error),
     };
+    let (yaml_value, source_map, errors) =
+        md_transform::md_to_value(md_str, &options, Some(&compiled.schema));
 
-    // バリデーション実行
-    let result = compiled.validate(&yaml_value);
-
-    match result {
-        Ok(_) => ValidationResult::success().to_json(),
-        Err(errors) => {
-            let errors: Vec<ErrorInfo> = errors
-                .map(|err| {
-                    let path = if !err.instance_path.is_empty() {
-                        format!("/{}", err.instance_path.join("/"))
-                    } else {
-                        "".to_string()
-                    };
-                    let line = find_line_for_path(yaml_str, path.clone());
-                    ErrorInfo {
-                        line,
-                        message: err.to_string(),
-                        path,
-                        code: ErrorCode::SchemaValidation,
-                    }
-                })
-                .collect();
-            ValidationResult::error(errors).to_json()
+    let mut result = validate_value("", &yaml_value, compiled);
+    for error in &mut result.errors {
+        if let Some(range) = source_map.locate(&error.path) {
+            *error = error.clone().with_span(range.span(md_str));
         }
     }
+
+    // 変換時のエラー（見出しの重複など）はスキーマ検証エラーより先に報告する
+    if !errors.is_empty() {
+        result.success = false;
+        result.errors.splice(0..0, errors);
+    }
+    result
 }
 
-/// JSONパスから対応するYAMLの行番号を見つける関数
-fn find_line_for_path(yaml_str: &str, path: String) -> u32 {
-    // 簡易実装: パスからキーを抽出して行番号を見つける
-    // 実際の実装では、より効率的で正確なアルゴリズムが必要
-    let lines: Vec<&str> = yaml_str.lines().collect();
-    let last_key = path.split('/').next_back().unwrap_or("");
+/// バリデーション対象のYAMLをパースする
+fn parse_yaml(yaml_str: &str) -> Result<Value, ValidationResult> {
+    serde_yaml::from_str(yaml_str).map_err(|e| {
+        ValidationResult::error(vec![ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse)])
+    })
+}
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.contains(last_key) && line.contains(':') {
-            return (i + 1) as u32;
+/// スキーマをパースしてコンパイルする
+pub(crate) fn compile(schema_str: &str) -> Result<Compiled, ValidationResult> {
+    // スキーマをパース
+    let schema_value: Value = serde_yaml::from_str(schema_str).map_err(|e| {
+        ValidationResult::error(vec![ErrorInfo::from_yaml_error(&e, ErrorCode::YamlParse)])
+    })?;
+
+    compile_value(schema_value).map_err(|mut result| {
+        // エラー位置をスキーマのYAML上の位置に対応付ける
+        if let Some(span_map) = SpanMap::parse(schema_str) {
+            for error in &mut result.errors {
+                if let Some(span) = span_map
+                    .key(&error.path)
+                    .or_else(|| span_map.locate(&error.path))
+                {
+                    *error = error.clone().with_span(span);
+                }
+            }
         }
+        result
+    })
+}
+
+/// パース済みのスキーマをコンパイルする
+pub(crate) fn compile_value(schema_value: Value) -> Result<Compiled, ValidationResult> {
+    match build_validator(&schema_value) {
+        Ok(validator) => Ok(Compiled {
+            schema: schema_value,
+            validator,
+        }),
+        Err(error) => Err(ValidationResult::single_error(error)),
     }
+}
 
-    0 // デフォルト値
+/// スキーマの `$schema` からJSON Schemaのバージョン（draft）を決める
+///
+/// # 戻り値
+/// * `$schema` がない場合はdraft-07
+///
+/// # エラーケース
+/// - `$schema` が文字列でない、または対応していない方言のURIの場合
+pub(crate) fn schema_draft(schema_value: &Value) -> Result<Draft, ErrorInfo> {
+    let Some(dialect) = schema_value.get("$schema") else {
+        return Ok(Draft::Draft7);
+    };
+    let uri = dialect.as_str().unwrap_or_default();
+    // `http` / `https` と末尾の空のフラグメントの違いは区別しない
+    let normalized = uri.trim_end_matches('#').replacen("https://", "http://", 1);
+    match normalized.as_str() {
+        "http://json-schema.org/draft-04/schema" => Ok(Draft::Draft4),
+        "http://json-schema.org/draft-06/schema" => Ok(Draft::Draft6),
+        "http://json-schema.org/draft-07/schema" => Ok(Draft::Draft7),
+        "http://json-schema.org/draft/2019-09/schema" => Ok(Draft::Draft201909),
+        "http://json-schema.org/draft/2020-12/schema" => Ok(Draft::Draft202012),
+        _ => Err(ErrorInfo::new(
+            0,
+            format!(
+                "Schema compile error: 対応していない$schemaです: {} (対応: draft-04, draft-06, draft-07, 2019-09, 2020-12)",
+                dialect
+            ),
+            "/$schema",
+            ErrorCode::SchemaCompile,
+        )),
+    }
+}
+
+/// `$schema` で選んだdraftでスキーマをコンパイルする
+///
+/// `format` の検証はdraftの既定に従う（draft-07以前は検証、2019-09以降は注釈のみ）
+pub(crate) fn build_validator(schema_value: &Value) -> Result<Validator, ErrorInfo> {
+    #[cfg(test)]
+    tests::BUILD_COUNT.with(|count| count.set(count.get() + 1));
+    let draft = schema_draft(schema_value)?;
+    // スキーマ自体の問題では instance_path がスキーマ内の位置を指す
+    jsonschema::options()
+        .with_draft(draft)
+        .build(schema_value)
+        .map_err(|e| {
+            ErrorInfo::new(
+                0,
+                format!("Schema compile error: {}", e),
+                e.instance_path.as_str(),
+                ErrorCode::SchemaCompile,
+            )
+        })
+}
+
+/// パース済みのYAMLをスキーマでバリデーションする
+///
+/// `yaml_str` はエラー位置の特定に利用する
+fn validate_value(yaml_str: &str, yaml_value: &Value, compiled: &Compiled) -> ValidationResult {
+    let mut span_map = None;
+    validate_value_with(
+        yaml_value,
+        compiled,
+        ErrorCode::SchemaValidation,
+        |path, is_collection| {
+            let span_map =
+                span_map.get_or_insert_with(|| SpanMap::parse(yaml_str).unwrap_or_default());
+            if is_collection {
+                span_map.key(path)
+            } else {
+                None
+            }
+            .or_else(|| span_map.locate(path))
+        },
+    )
+}
+
+/// パース済みの値をコンパイル済みのスキーマでバリデーションし、エラーを失敗したキーワードごとのコード（`RequiredMissing` など）で報告する
+///
+/// キーワードで分類できないエラーは `code` で報告する。
+/// `code` が `FrontmatterValidation` の場合は、すべてのエラーにフロントマターの検証エラーであることを記録する（`frontmatter`）
+///
+/// # 引数
+/// * `locate` - エラー箇所のJSON Pointerと、値がオブジェクト/配列かどうかからエラー位置を求める
+pub(crate) fn validate_value_with(
+    value: &Value,
+    compiled: &Compiled,
+    code: ErrorCode,
+    mut locate: impl FnMut(&str, bool) -> Option<SourceSpan>,
+) -> ValidationResult {
+    // バリデーション実行
+    let errors: Vec<ErrorInfo> = compiled
+        .validator
+        .iter_errors(value)
+        .map(|err| {
+            let path = err.instance_path.as_str().to_string();
+            // オブジェクト/配列全体に対するエラー（requiredなど）はキーの位置を指す
+            let is_collection = matches!(err.instance.as_ref(), Value::Object(_) | Value::Array(_));
+            let span = locate(&path, is_collection);
+            // スキーマの `x-severity` で重大度を下げたキーワードは検証を失敗させない
+            let missing_property = match &err.kind {
+                ValidationErrorKind::Required { property } => property.as_str(),
+                _ => None,
+            };
+            let severity = schema_severity::error_severity(
+                &compiled.schema,
+                err.schema_path.as_str(),
+                missing_property,
+            );
+            let (error_code, params) = match classify(&err.kind) {
+                Some((keyword_code, params)) => (keyword_code, Some(params)),
+                None => (code, None),
+            };
+            let mut error =
+                ErrorInfo::new(0, err.to_string(), path, error_code).with_severity(severity);
+            if let Some(params) = params {
+                error = error.with_params(params);
+            }
+            error.frontmatter = matches!(code, ErrorCode::FrontmatterValidation);
+            match span {
+                Some(span) => error.with_span(span),
+                None => error,
+            }
+        })
+        .collect();
+
+    ValidationResult::from_diagnostics(errors)
+}
+
+/// 失敗したキーワードから、エラーコードと構造化されたパラメータを求める
+///
+/// # 戻り値
+/// * キーワードごとのコードがない場合（`anyOf` / `oneOf` / `not` など）はNone
+fn classify(kind: &ValidationErrorKind) -> Option<(ErrorCode, ErrorParams)> {
+    let limit = |limit: Value| ErrorParams {
+        limit: Some(limit),
+        ..ErrorParams::default()
+    };
+    let classified = match kind {
+        ValidationErrorKind::Required { property } => (
+            ErrorCode::RequiredMissing,
+            ErrorParams {
+                property: Some(
+                    property
+                        .as_str()
+                        .map_or_else(|| property.to_string(), str::to_string),
+                ),
+                ..ErrorParams::default()
+            },
+        ),
+        ValidationErrorKind::Type { kind } => {
+            let expected = match kind {
+                TypeKind::Single(t) => vec![t.to_string()],
+                TypeKind::Multiple(types) => types.iter().map(|t| t.to_string()).collect(),
+            };
+            (
+                ErrorCode::TypeMismatch,
+                ErrorParams {
+                    expected: Some(expected),
+                    ..ErrorParams::default()
+                },
+            )
+        }
+        ValidationErrorKind::Enum { options } => (
+            ErrorCode::EnumMismatch,
+            ErrorParams {
+                allowed: Some(
+                    options
+                        .as_array()
+                        .cloned()
+                        .unwrap_or_else(|| vec![options.clone()]),
+                ),
+                ..ErrorParams::default()
+            },
+        ),
+        ValidationErrorKind::Constant { expected_value } => (
+            ErrorCode::EnumMismatch,
+            ErrorParams {
+                allowed: Some(vec![expected_value.clone()]),
+                ..ErrorParams::default()
+            },
+        ),
+        ValidationErrorKind::Pattern { pattern } => (
+            ErrorCode::PatternMismatch,
+            ErrorParams {
+                pattern: Some(pattern.clone()),
+                ..ErrorParams::default()
+            },
+        ),
+        ValidationErrorKind::MinLength { limit: n }
+        | ValidationErrorKind::MinItems { limit: n }
+        | ValidationErrorKind::MinProperties { limit: n } => {
+            (ErrorCode::TooShort, limit(Value::from(*n)))
+        }
+        ValidationErrorKind::MaxLength { limit: n }
+        | ValidationErrorKind::MaxItems { limit: n }
+        | ValidationErrorKind::MaxProperties { limit: n } => {
+            (ErrorCode::TooLong, limit(Value::from(*n)))
+        }
+        ValidationErrorKind::AdditionalItems { limit: n } => {
+            (ErrorCode::TooLong, limit(Value::from(*n)))
+        }
+        ValidationErrorKind::Minimum { limit: n }
+        | ValidationErrorKind::Maximum { limit: n }
+        | ValidationErrorKind::ExclusiveMinimum { limit: n }
+        | ValidationErrorKind::ExclusiveMaximum { limit: n } => {
+            (ErrorCode::OutOfRange, limit(n.clone()))
+        }
+        ValidationErrorKind::MultipleOf { multiple_of } => {
+            (ErrorCode::OutOfRange, limit(Value::from(*multiple_of)))
+        }
+        ValidationErrorKind::AdditionalProperties { unexpected }
+        | ValidationErrorKind::UnevaluatedProperties { unexpected } => (
+            ErrorCode::AdditionalProperty,
+            ErrorParams {
+                unexpected: Some(unexpected.clone()),
+                ..ErrorParams::default()
+            },
+        ),
+        ValidationErrorKind::Format { format } => (
+            ErrorCode::FormatMismatch,
+            ErrorParams {
+                format: Some(format.clone()),
+                ..ErrorParams::default()
+            },
+        ),
+        _ => return None,
+    };
+    Some(classified)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Severity;
+    use serde_json::json;
+    use std::cell::Cell;
+
+    thread_local! {
+        /// `build_validator` でスキーマをコンパイルした回数（ハンドルの再利用の確認用）
+        pub(super) static BUILD_COUNT: Cell<usize> = const { Cell::new(0) };
+    }
 
     #[test]
     fn validate_ok() {
@@ -155,4 +533,624 @@ mod tests {
         assert!(result.contains(r#""success":false"#));
         assert!(result.contains(r#""message":"#)); // エラーメッセージがあること
     }
+
+    #[test]
+    fn validate_error_line_points_at_sequence_item() {
+        let schema = r#"
+        type: object
+        properties:
+          sections:
+            type: array
+            items:
+              type: object
+              properties:
+                title:
+                  type: string
+        "#;
+
+        let yaml = "title: Note\nsections:\n  - title: Intro\n  - title: Features\n  - title: 3\n";
+
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(yaml, schema)).unwrap();
+        assert!(!result.success);
+        let error = &result.errors[0];
+        assert_eq!(error.path, "/sections/2/title");
+        assert_eq!((error.line, error.column), (5, 12));
+        assert_eq!((error.end_line, error.end_column), (5, 13));
+        assert_eq!(&yaml[error.offset as usize..error.end_offset as usize], "3");
+    }
+
+    #[test]
+    fn validate_error_line_for_missing_required_nested_key() {
+        let schema = r#"
+        type: object
+        properties:
+          meta:
+            type: object
+            properties:
+              title:
+                type: string
+            required:
+              - title
+        "#;
+
+        let yaml = "title: Note\nmeta:\n  author: alice\n";
+
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(yaml, schema)).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "/meta");
+        assert_eq!(result.errors[0].line, 2);
+    }
+
+    #[test]
+    fn validate_downgrades_errors_with_x_severity() {
+        let schema = r#"
+            type: object
+            properties:
+              title:
+                type: string
+              summary:
+                type: string
+                maxLength: 10
+                x-severity: warning
+              tags:
+                type: array
+                items:
+                  type: string
+                  pattern: "^[a-z]+$"
+                x-severity:
+                  pattern: hint
+            required: [title, summary]
+        "#;
+
+        // 重大度を下げたエラーだけなら成功
+        let yaml = "title: Note\ntags: [Rust]\n";
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(yaml, schema)).unwrap();
+        assert!(result.success);
+        let severities: Vec<Severity> = result.errors.iter().map(|e| e.severity).collect();
+        assert_eq!(severities, vec![Severity::Hint, Severity::Warning]);
+        assert_eq!(result.errors[1].path, "");
+
+        let yaml = "summary: a long summary\ntags: [1]\n";
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(yaml, schema)).unwrap();
+        assert!(!result.success);
+        let errors: Vec<(&str, Severity)> = result
+            .errors
+            .iter()
+            .map(|e| (e.path.as_str(), e.severity))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("/summary", Severity::Warning),
+                ("/tags/0", Severity::Error),
+                ("", Severity::Error)
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_reports_keyword_codes_and_params() {
+        let schema = r#"
+            type: object
+            properties:
+              title:
+                type: string
+                minLength: 3
+              status:
+                enum: [draft, published]
+              slug:
+                type: string
+                pattern: "^[a-z-]+$"
+              rating:
+                type: [integer, "null"]
+                maximum: 5
+              tags:
+                type: array
+                maxItems: 1
+              kind:
+                const: note
+            required: [title, author]
+            additionalProperties: false
+            anyOf:
+              - required: [title]
+              - required: [slug]
+        "#;
+        let yaml = "title: ab
+status: done
+slug: Bad Slug
+rating: 9
+tags: [a, b]
+kind: memo
+extra: 1
+";
+
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(yaml, schema)).unwrap();
+        assert!(!result.success);
+        assert!(result.errors.iter().all(|e| !e.frontmatter));
+        let params = |path: &str| {
+            let error = result.errors.iter().find(|e| e.path == path).unwrap();
+            (
+                format!("{:?}", error.code),
+                serde_json::to_value(&error.params).unwrap(),
+            )
+        };
+
+        assert_eq!(
+            params("/title"),
+            ("TooShort".to_string(), json!({"limit": 3}))
+        );
+        assert_eq!(
+            params("/status"),
+            (
+                "EnumMismatch".to_string(),
+                json!({"allowed": ["draft", "published"]})
+            )
+        );
+        assert_eq!(
+            params("/slug"),
+            (
+                "PatternMismatch".to_string(),
+                json!({"pattern": "^[a-z-]+$"})
+            )
+        );
+        assert_eq!(
+            params("/rating"),
+            ("OutOfRange".to_string(), json!({"limit": 5}))
+        );
+        assert_eq!(
+            params("/tags"),
+            ("TooLong".to_string(), json!({"limit": 1}))
+        );
+        assert_eq!(
+            params("/kind"),
+            ("EnumMismatch".to_string(), json!({"allowed": ["note"]}))
+        );
+
+        let root: Vec<(String, serde_json::Value)> = result
+            .errors
+            .iter()
+            .filter(|e| e.path.is_empty())
+            .map(|e| {
+                (
+                    format!("{:?}", e.code),
+                    serde_json::to_value(&e.params).unwrap(),
+                )
+            })
+            .collect();
+        assert!(root.contains(&("RequiredMissing".to_string(), json!({"property": "author"}))));
+        assert!(root.contains(&(
+            "AdditionalProperty".to_string(),
+            json!({"unexpected": ["extra"]})
+        )));
+
+        // 型の不一致は期待される型の一覧を返す
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(
+            "title: ab
+rating: high
+",
+            schema,
+        ))
+        .unwrap();
+        let error = result.errors.iter().find(|e| e.path == "/rating").unwrap();
+        assert!(matches!(error.code, ErrorCode::TypeMismatch));
+        assert_eq!(
+            error.params.as_ref().unwrap().expected,
+            Some(vec!["integer".to_string(), "null".to_string()])
+        );
+
+        // キーワードごとのコードがないもの（anyOf）はSchemaValidationのまま
+        let result: ValidationResult = serde_json::from_str(&validate_yaml(
+            "author: a
+",
+            schema,
+        ))
+        .unwrap();
+        let error = result.errors.iter().find(|e| e.params.is_none()).unwrap();
+        assert!(matches!(error.code, ErrorCode::SchemaValidation));
+        assert!(error.params.is_none());
+    }
+
+    #[test]
+    fn compiled_schema_validates_repeatedly() {
+        let schema = r#"
+        type: object
+        properties:
+          title:
+            type: string
+        required:
+          - title
+        "#;
+
+        let compiled = CompiledSchema::new(schema);
+        assert!(compiled.compile_result().contains(r#""success":true"#));
+        assert!(compiled
+            .validate("title: Note\n")
+            .contains(r#""success":true"#));
+
+        let result: ValidationResult =
+            serde_json::from_str(&compiled.validate("content: body\n")).unwrap();
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::RequiredMissing));
+
+        // 同じハンドルで再度検証できること
+        assert!(compiled
+            .validate("title: Another\n")
+            .contains(r#""success":true"#));
+    }
+
+    #[test]
+    fn compiled_schema_compiles_once() {
+        let compiled =
+            CompiledSchema::new("type: object\nproperties:\n  count:\n    type: integer\n");
+        let builds = BUILD_COUNT.with(Cell::get);
+
+        for i in 0..100 {
+            assert!(compiled
+                .validate(&format!("count: {}\n", i))
+                .contains(r#""success":true"#));
+            assert!(compiled
+                .validate_markdown("# count\ntext\n", None)
+                .contains(r#""success""#));
+        }
+        assert!(compiled
+            .validate("count: many\n")
+            .contains(r#""success":false"#));
+        // 検証ごとにスキーマを再コンパイルしない
+        assert_eq!(BUILD_COUNT.with(Cell::get), builds);
+    }
+
+    #[test]
+    fn compiled_schema_reports_invalid_schema() {
+        let compiled = CompiledSchema::new("type: object\nproperties: {\n");
+
+        let compile_result: ValidationResult =
+            serde_json::from_str(&compiled.compile_result()).unwrap();
+        assert!(!compile_result.success);
+        assert!(matches!(
+            compile_result.errors[0].code,
+            ErrorCode::YamlParse
+        ));
+
+        let result: ValidationResult =
+            serde_json::from_str(&compiled.validate("title: Note\n")).unwrap();
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::YamlParse));
+    }
+
+    const NOTE_SCHEMA: &str = r#"
+    type: object
+    properties:
+      title:
+        type: string
+      sections:
+        type: array
+        items:
+          type: object
+          properties:
+            title:
+              type: string
+              enum: [Introduction, Features, Conclusion]
+            content:
+              type: string
+              minLength: 1
+    "#;
+
+    #[test]
+    fn validate_markdown_ok() {
+        let md = "---\nschema_path: ./schema.yaml\n---\n# Note\n## Introduction\nHello\n";
+
+        let result = validate_markdown(md, NOTE_SCHEMA, None);
+        assert!(result.contains(r#""success":true"#));
+    }
+
+    #[test]
+    fn validate_markdown_maps_errors_to_markdown_lines() {
+        let md = "---\nschema_path: ./schema.yaml\n---\n# Note\n\n## Introduction\nHello\n\n## Unknown\nBody line 1\nBody line 2\n";
+
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(md, NOTE_SCHEMA, None)).unwrap();
+        assert!(!result.success);
+        let error = &result.errors[0];
+        assert_eq!(error.path, "/sections/1/title");
+        assert_eq!((error.line, error.column), (9, 1));
+        assert_eq!(error.end_line, 9);
+        assert_eq!(
+            &md[error.offset as usize..error.end_offset as usize],
+            "## Unknown"
+        );
+    }
+
+    #[test]
+    fn validate_markdown_missing_node_points_at_parent_section() {
+        let md = "# Note\n## Introduction\n\n## Features\nBody\n";
+
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(md, NOTE_SCHEMA, None)).unwrap();
+        assert!(!result.success);
+        let error = &result.errors[0];
+        assert_eq!(error.path, "/sections/0/content");
+        assert_eq!((error.line, error.end_line), (2, 2));
+    }
+
+    #[test]
+    fn validate_markdown_with_list_items() {
+        let schema = r#"
+type: object
+properties:
+  sections:
+    type: array
+    items:
+      type: object
+      properties:
+        items:
+          type: array
+          minItems: 3
+      required: [items]
+"#;
+        let md = "# Note\n\n## Features\n\n- Fast\n- Small\n";
+
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(md, schema, Some("lists: true"))).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "/sections/0/items");
+        assert_eq!((result.errors[0].line, result.errors[0].end_line), (5, 6));
+
+        let ok = "# Note\n\n## Features\n\n- Fast\n- Small\n- Safe\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ok, schema, Some("lists: true"))).unwrap();
+        assert!(result.success);
+
+        // オプションの誤りはエラーとして報告する
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ok, schema, Some("unknown: 1"))).unwrap();
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn validate_markdown_with_table_cells() {
+        let schema = r#"
+type: object
+properties:
+  sections:
+    type: array
+    items:
+      type: object
+      properties:
+        tables:
+          type: array
+          items:
+            type: object
+            properties:
+              rows:
+                type: array
+                items:
+                  type: object
+                  required: [Name, Age]
+                  properties:
+                    Age: { type: integer }
+"#;
+        let md = "# Team\n\n## Members\n\n| Name | Age |\n| --- | --- |\n| Alice | 34 |\n| Bob | unknown |\n";
+
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(md, schema, Some("tables: true"))).unwrap();
+        assert!(!result.success);
+        // 34 はスキーマの型（integer）に変換される
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "/sections/0/tables/0/rows/1/Age");
+        assert_eq!(result.errors[0].line, 8);
+
+        // 正規の表記でない数値は変換しない。列名の重複は変換時のエラーとして先に報告する
+        let md = "# Team
+
+## Members
+
+| Name | Age | Age |
+| --- | --- | --- |
+| Alice | 034 | 34 |
+";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(md, schema, Some("tables: true"))).unwrap();
+        assert_eq!(result.errors.len(), 2);
+        assert!(matches!(result.errors[0].code, ErrorCode::DuplicateKey));
+        assert_eq!(result.errors[0].path, "/sections/0/tables/0/columns/2");
+        assert_eq!(result.errors[0].line, 5);
+        assert!(matches!(result.errors[1].code, ErrorCode::TypeMismatch));
+        assert_eq!(result.errors[1].path, "/sections/0/tables/0/rows/0/Age");
+    }
+
+    #[test]
+    fn validate_markdown_requires_code_block_language() {
+        let schema = r#"
+type: object
+properties:
+  sections:
+    type: array
+    items:
+      type: object
+      required: [code_blocks]
+      properties:
+        code_blocks:
+          type: array
+          contains:
+            properties:
+              lang: { const: sql }
+"#;
+        let options = Some("code_blocks: true");
+
+        let ok = "# Report\n\n## Query\n\n```sql\nSELECT 1;\n```\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ok, schema, options)).unwrap();
+        assert!(result.success);
+
+        let ng = "# Report\n\n## Query\n\n```python\nprint(1)\n```\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ng, schema, options)).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "/sections/0/code_blocks");
+        assert_eq!(result.errors[0].line, 5);
+    }
+
+    #[test]
+    fn validate_markdown_with_keyed_sections() {
+        let schema = r#"
+type: object
+properties:
+  sections:
+    type: object
+    required: [Introduction, Features]
+"#;
+        let options = Some("mapping: {sections: title}");
+
+        let ok = "# Note\n\n## Introduction\n\n## Features\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ok, schema, options)).unwrap();
+        assert!(result.success);
+
+        let missing = "# Note\n\n## Introduction\n\nHello\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(missing, schema, options)).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "/sections");
+
+        let duplicate = "# Note\n\n## Introduction\n\n## Features\n\n## Introduction\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(duplicate, schema, options)).unwrap();
+        assert!(!result.success);
+        assert!(matches!(result.errors[0].code, ErrorCode::DuplicateHeading));
+        assert_eq!(result.errors[0].line, 7);
+    }
+
+    #[test]
+    fn validate_markdown_with_section_fields() {
+        let schema = r#"
+type: object
+properties:
+  sections:
+    type: array
+    items:
+      type: object
+      required: [fields]
+      properties:
+        fields:
+          type: object
+          required: [Status]
+          properties:
+            Status: { enum: [todo, doing, done] }
+"#;
+        let md =
+            "# Tasks\n\n## Write docs\n\nStatus: done\n\n## Ship\n\nStatus: later\nOwner: bob\n";
+
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(md, schema, Some("fields: true"))).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "/sections/1/fields/Status");
+        assert_eq!(result.errors[0].line, 9);
+    }
+
+    #[test]
+    fn validate_markdown_with_frontmatter_fields() {
+        let schema = r#"
+type: object
+required: [frontmatter, sections]
+properties:
+  frontmatter:
+    type: object
+    required: [tags]
+    properties:
+      tags: { type: array, minItems: 1 }
+  sections: { type: array, minItems: 1 }
+"#;
+        let options = Some("frontmatter: {merge: key}");
+
+        let ok = "---\ntags: [note]\n---\n# Note\n\n## Body\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ok, schema, options)).unwrap();
+        assert!(result.success);
+
+        let ng = "---\nauthor: alice\ntags: []\n---\n# Note\n\n## Body\n";
+        let result: ValidationResult =
+            serde_json::from_str(&validate_markdown(ng, schema, options)).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "/frontmatter/tags");
+        assert_eq!(result.errors[0].line, 3);
+    }
+
+    #[test]
+    fn validate_selects_draft_from_schema_keyword() {
+        let schema = r##"
+        $schema: https://json-schema.org/draft/2020-12/schema
+        type: object
+        properties:
+          point:
+            type: array
+            prefixItems:
+              - type: number
+              - type: number
+            items: false
+          author:
+            $ref: "#/$defs/person"
+        dependentRequired:
+          end: [start]
+        unevaluatedProperties: false
+        $defs:
+          person:
+            type: string
+        "##;
+        let compiled = CompiledSchema::new(schema);
+        assert!(compiled.compile_result().contains(r#""success":true"#));
+
+        let ok: ValidationResult =
+            serde_json::from_str(&compiled.validate("point: [1, 2]\nauthor: a\n")).unwrap();
+        assert!(ok.success, "{:?}", ok.errors);
+
+        let result: ValidationResult = serde_json::from_str(
+            &compiled.validate("point: [1, x, 3]\nauthor: 1\nend: 2\nextra: true\n"),
+        )
+        .unwrap();
+        let paths: Vec<&str> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        for path in ["/point/1", "/point/2", "/author", ""] {
+            assert!(paths.contains(&path), "{} not in {:?}", path, paths);
+        }
+        // dependentRequired と unevaluatedProperties はルートのエラー
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.path.is_empty() && e.message.contains("start")));
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.path.is_empty() && e.message.contains("extra")));
+
+        // 2019-09 でも同じキーワードを扱う
+        let schema_2019 = "$schema: https://json-schema.org/draft/2019-09/schema#\ndependentRequired:\n  end: [start]\n";
+        assert!(validate_yaml("end: 1\n", schema_2019).contains(r#""success":false"#));
+    }
+
+    #[test]
+    fn validate_defaults_to_draft7() {
+        // draft-07 では prefixItems は未知のキーワードとして無視される
+        let schema = "type: array\nprefixItems:\n  - type: number\n";
+        assert!(validate_yaml("- x\n", schema).contains(r#""success":true"#));
+
+        let schema = "$schema: http://json-schema.org/draft-07/schema#\ntype: array\nitems:\n  - type: number\n";
+        assert!(validate_yaml("- x\n", schema).contains(r#""success":false"#));
+    }
+
+    #[test]
+    fn compile_reports_unsupported_dialect() {
+        let compiled =
+            CompiledSchema::new("type: object\n$schema: https://example.com/my-dialect\n");
+        let result: ValidationResult = serde_json::from_str(&compiled.compile_result()).unwrap();
+        assert!(!result.success);
+        let error = &result.errors[0];
+        assert!(matches!(error.code, ErrorCode::SchemaCompile));
+        assert!(error.message.contains("https://example.com/my-dialect"));
+        assert_eq!(error.path, "/$schema");
+        assert_eq!(error.line, 2);
+
+        let result: ValidationResult =
+            serde_json::from_str(&validate_yaml("a: 1\n", "$schema: 7\n")).unwrap();
+        assert!(matches!(result.errors[0].code, ErrorCode::SchemaCompile));
+    }
 }
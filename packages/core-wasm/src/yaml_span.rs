@@ -0,0 +1,325 @@
+//! yaml_span.rs
+//!
+//! YAMLドキュメントの各ノードのソース上の位置を記録するモジュール。
+//! - saphyr-parserのイベントストリームからノードごとの開始・終了位置を構築
+//! - JSON Pointer形式のパス（`/sections/2/title` など）からノード位置を引けるようにする
+//!
+//! バリデーションエラーなどをエディタ上の正確な位置に対応付けるために利用する。
+
+use std::collections::HashMap;
+
+use saphyr_parser::{Event, Parser, Span};
+
+/// ソース上の範囲
+///
+/// # フィールド
+/// - `start_line` / `end_line`: 行番号（1始まり）
+/// - `start_column` / `end_column`: 列番号（1始まり、文字単位）
+/// - `start_offset` / `end_offset`: バイトオフセット（終了位置は排他的）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SourceSpan {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    pub start_offset: u32,
+    pub end_offset: u32,
+}
+
+impl SourceSpan {
+    /// 埋め込み先ドキュメント（フロントマターを含むMarkdownなど）上の位置に変換する
+    ///
+    /// 行番号とバイトオフセットのみずらし、列番号はそのまま保持する
+    pub fn shifted(self, lines: u32, bytes: u32) -> SourceSpan {
+        SourceSpan {
+            start_line: self.start_line + lines,
+            end_line: self.end_line + lines,
+            start_offset: self.start_offset + bytes,
+            end_offset: self.end_offset + bytes,
+            ..self
+        }
+    }
+
+    /// テキスト上のバイト範囲（`start..end`）から位置を求める
+    pub fn from_offsets(src: &str, start: usize, end: usize) -> SourceSpan {
+        let position = |offset: usize| {
+            let offset = offset.min(src.len());
+            let line_start = src[..offset].rfind('\n').map_or(0, |i| i + 1);
+            (
+                src[..offset].matches('\n').count() as u32 + 1,
+                src[line_start..offset].chars().count() as u32 + 1,
+            )
+        };
+        let (start_line, start_column) = position(start);
+        let (end_line, end_column) = position(end.max(start));
+        SourceSpan {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            start_offset: start.min(src.len()) as u32,
+            end_offset: end.max(start).min(src.len()) as u32,
+        }
+    }
+}
+
+/// JSON Pointer → ノード位置の対応表
+#[derive(Debug, Default)]
+pub struct SpanMap {
+    nodes: HashMap<String, SourceSpan>,
+    keys: HashMap<String, SourceSpan>,
+}
+
+/// 構築中のコレクションノード
+struct Frame {
+    /// ノードのパス（複合キーの内部など記録対象外の場合はNone）
+    path: Option<String>,
+    kind: FrameKind,
+    start: SourceSpan,
+    last_end: Option<SourceSpan>,
+}
+
+enum FrameKind {
+    Mapping {
+        pending_key: Option<(Option<String>, SourceSpan)>,
+    },
+    Sequence {
+        index: usize,
+    },
+}
+
+/// JSON Pointerのセグメントをエスケープする（RFC 6901）
+pub fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+impl SpanMap {
+    /// YAML文字列を解析し、最初のドキュメントのノード位置を記録する
+    ///
+    /// # 戻り値
+    /// * 成功時: 各ノードの位置を保持したSpanMap
+    /// * YAMLの構文エラー時: None（エラー位置はserde_yaml側で報告する）
+    pub fn parse(yaml: &str) -> Option<SpanMap> {
+        // saphyr-parserのインデックスは文字単位なので、バイトオフセットへの変換表を作る
+        let mut byte_offsets: Vec<usize> = yaml.char_indices().map(|(i, _)| i).collect();
+        byte_offsets.push(yaml.len());
+        let to_source = |span: Span| SourceSpan {
+            start_line: span.start.line() as u32,
+            start_column: span.start.col() as u32 + 1,
+            end_line: span.end.line() as u32,
+            end_column: span.end.col() as u32 + 1,
+            start_offset: byte_offsets[span.start.index().min(byte_offsets.len() - 1)] as u32,
+            end_offset: byte_offsets[span.end.index().min(byte_offsets.len() - 1)] as u32,
+        };
+
+        let mut map = SpanMap::default();
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut root_done = false;
+
+        for event in Parser::new_from_str(yaml) {
+            let (event, span) = event.ok()?;
+            let span = to_source(span);
+            match event {
+                Event::Scalar(value, ..) => {
+                    match map.child_path(&mut stack, Some(value.as_ref()), span) {
+                        ChildSlot::Key => {}
+                        ChildSlot::Value(path) => map.finish_node(&mut stack, path, span),
+                    }
+                }
+                Event::Alias(_) => match map.child_path(&mut stack, None, span) {
+                    ChildSlot::Key => {}
+                    ChildSlot::Value(path) => map.finish_node(&mut stack, path, span),
+                },
+                Event::MappingStart(..) | Event::SequenceStart(..) => {
+                    let path = match map.child_path(&mut stack, None, span) {
+                        ChildSlot::Key => None,
+                        ChildSlot::Value(path) => path,
+                    };
+                    let kind = if matches!(event, Event::MappingStart(..)) {
+                        FrameKind::Mapping { pending_key: None }
+                    } else {
+                        FrameKind::Sequence { index: 0 }
+                    };
+                    stack.push(Frame {
+                        path,
+                        kind,
+                        start: span,
+                        last_end: None,
+                    });
+                }
+                Event::MappingEnd | Event::SequenceEnd => {
+                    let frame = stack.pop()?;
+                    // フロースタイルの場合は閉じ括弧まで、ブロックスタイルの場合は最後の子ノードまで
+                    let end = if span.end_offset > span.start_offset {
+                        span
+                    } else {
+                        frame.last_end.unwrap_or(frame.start)
+                    };
+                    let node = SourceSpan {
+                        end_line: end.end_line,
+                        end_column: end.end_column,
+                        end_offset: end.end_offset,
+                        ..frame.start
+                    };
+                    map.finish_node(&mut stack, frame.path, node);
+                }
+                Event::DocumentEnd => {
+                    root_done = true;
+                }
+                Event::DocumentStart(_) if root_done => break,
+                _ => {}
+            }
+        }
+
+        Some(map)
+    }
+
+    /// 次のノードが親コレクションのどの位置に入るかを判定する
+    fn child_path(
+        &mut self,
+        stack: &mut [Frame],
+        key: Option<&str>,
+        span: SourceSpan,
+    ) -> ChildSlot {
+        let Some(frame) = stack.last_mut() else {
+            return ChildSlot::Value(Some(String::new()));
+        };
+        let parent = frame.path.clone();
+        match &mut frame.kind {
+            FrameKind::Mapping { pending_key } => match pending_key.take() {
+                None => {
+                    // マッピングのキー（スカラー以外のキーは記録しない）
+                    let name = key.map(escape_pointer_segment);
+                    if let (Some(parent), Some(name)) = (&parent, &name) {
+                        self.keys.insert(format!("{}/{}", parent, name), span);
+                    }
+                    *pending_key = Some((name, span));
+                    frame.last_end = Some(span);
+                    ChildSlot::Key
+                }
+                Some((name, _)) => {
+                    ChildSlot::Value(parent.zip(name).map(|(p, n)| format!("{}/{}", p, n)))
+                }
+            },
+            FrameKind::Sequence { index } => {
+                let path = parent.map(|p| format!("{}/{}", p, index));
+                *index += 1;
+                ChildSlot::Value(path)
+            }
+        }
+    }
+
+    /// ノードの位置を記録し、親コレクションの終了位置を更新する
+    fn finish_node(&mut self, stack: &mut [Frame], path: Option<String>, span: SourceSpan) {
+        if let Some(path) = path {
+            self.nodes.insert(path, span);
+        }
+        if let Some(parent) = stack.last_mut() {
+            parent.last_end = Some(span);
+        }
+    }
+
+    /// パスに対応するノード（値）の位置を返す
+    pub fn node(&self, path: &str) -> Option<SourceSpan> {
+        self.nodes.get(path).copied()
+    }
+
+    /// パスに対応するマッピングキーの位置を返す
+    pub fn key(&self, path: &str) -> Option<SourceSpan> {
+        self.keys.get(path).copied()
+    }
+
+    /// パスに対応する位置を返す。ノードが存在しない場合は最も近い祖先ノードの位置を返す
+    pub fn locate(&self, path: &str) -> Option<SourceSpan> {
+        let mut current = path;
+        loop {
+            if let Some(span) = self.node(current) {
+                return Some(span);
+            }
+            match current.rfind('/') {
+                Some(idx) => current = &current[..idx],
+                None => return None,
+            }
+        }
+    }
+}
+
+enum ChildSlot {
+    /// マッピングのキーとして消費された
+    Key,
+    /// 値ノード（記録対象外の場合はNone）
+    Value(Option<String>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = "title: Note\nsections:\n  - title: Intro\n    content: hello\n  - title: Features\n    tags: [a, b]\n";
+
+    #[test]
+    fn test_scalar_spans() {
+        let map = SpanMap::parse(YAML).unwrap();
+
+        let title = map.node("/title").unwrap();
+        assert_eq!((title.start_line, title.start_column), (1, 8));
+        assert_eq!((title.end_line, title.end_column), (1, 12));
+        assert_eq!(
+            &YAML[title.start_offset as usize..title.end_offset as usize],
+            "Note"
+        );
+
+        let key = map.key("/title").unwrap();
+        assert_eq!((key.start_line, key.start_column), (1, 1));
+    }
+
+    #[test]
+    fn test_sequence_items_and_repeated_keys() {
+        let map = SpanMap::parse(YAML).unwrap();
+
+        assert_eq!(map.node("/sections/0/title").unwrap().start_line, 3);
+        assert_eq!(map.node("/sections/1/title").unwrap().start_line, 5);
+
+        let item = map.node("/sections/1").unwrap();
+        assert_eq!((item.start_line, item.start_column), (5, 5));
+        assert_eq!(item.end_line, 6);
+
+        let tag = map.node("/sections/1/tags/1").unwrap();
+        assert_eq!((tag.start_line, tag.start_column), (6, 15));
+    }
+
+    #[test]
+    fn test_locate_falls_back_to_ancestor() {
+        let map = SpanMap::parse(YAML).unwrap();
+
+        let span = map.locate("/sections/0/missing").unwrap();
+        assert_eq!(span, map.node("/sections/0").unwrap());
+        assert_eq!(map.locate("").unwrap().start_line, 1);
+    }
+
+    #[test]
+    fn test_multibyte_offsets() {
+        let yaml = "タイトル: メモ\nstatus: done\n";
+        let map = SpanMap::parse(yaml).unwrap();
+
+        let title = map.node("/タイトル").unwrap();
+        assert_eq!(
+            &yaml[title.start_offset as usize..title.end_offset as usize],
+            "メモ"
+        );
+        assert_eq!(title.start_column, 7);
+        assert_eq!(map.node("/status").unwrap().start_line, 2);
+    }
+
+    #[test]
+    fn test_escaped_keys() {
+        let map = SpanMap::parse("a/b: 1\n\"c~d\": 2\n").unwrap();
+        assert_eq!(map.node("/a~1b").unwrap().start_line, 1);
+        assert_eq!(map.node("/c~0d").unwrap().start_line, 2);
+    }
+
+    #[test]
+    fn test_invalid_yaml() {
+        assert!(SpanMap::parse("title: {\n").is_none());
+    }
+}